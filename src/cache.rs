@@ -2,6 +2,7 @@
 //! Enables instant startup by loading cached data while refreshing in background
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -17,6 +18,12 @@ pub struct CachedWorktreeStatus {
     pub untracked: usize,
     pub ahead: usize,
     pub behind: usize,
+    #[serde(default)]
+    pub upstream: Option<String>,
+    #[serde(default)]
+    pub upstream_gone: bool,
+    #[serde(default)]
+    pub ignored_paths: Vec<String>,
 }
 
 /// Serializable commit info
@@ -24,7 +31,12 @@ pub struct CachedWorktreeStatus {
 pub struct CachedCommitInfo {
     pub hash: String,
     pub message: String,
-    pub time_ago: String,
+    #[serde(default)]
+    pub time: Option<i64>,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub author_email: String,
 }
 
 /// Serializable worktree data
@@ -43,8 +55,84 @@ pub struct CachedWorktree {
     pub is_locked: bool,
     pub lock_reason: Option<String>,
     pub is_prunable: bool,
+    #[serde(default)]
+    pub prune_reason: Option<String>,
     pub status: CachedWorktreeStatus,
     pub recent_commits: Vec<CachedCommitInfo>,
+    #[serde(default)]
+    pub config: Vec<(String, String)>,
+    #[serde(default)]
+    pub sparse_patterns: Option<usize>,
+    #[serde(default)]
+    pub unique_commits: Option<usize>,
+    #[serde(default)]
+    pub commit_count: Option<usize>,
+    #[serde(default)]
+    pub stash_count: usize,
+    #[serde(default)]
+    pub head_signed: Option<bool>,
+    #[serde(default)]
+    pub readme_preview: Option<String>,
+}
+
+/// UI state restored when reopening a repo (if enabled in config)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedUiState {
+    pub search_query: String,
+    pub sort_order: String,
+    pub show_recent_commits: bool,
+    #[serde(default)]
+    pub group_by: String,
+    #[serde(default)]
+    pub path_display: String,
+    #[serde(default)]
+    pub mini_view: bool,
+    #[serde(default)]
+    pub history_oldest_first: bool,
+}
+
+/// How often and how recently a worktree was opened from wtt
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessStats {
+    /// Unix timestamp of the last access
+    pub last_accessed: u64,
+    /// Number of accesses so far
+    pub count: u64,
+}
+
+impl AccessStats {
+    /// Record an access happening now
+    pub fn touch(&mut self) {
+        self.last_accessed = now_secs();
+        self.count += 1;
+    }
+
+    /// Frequency weighted by recency: recent use counts for more than old use
+    pub fn frecency(&self, now: u64) -> u64 {
+        let age = now.saturating_sub(self.last_accessed);
+        let weight = match age {
+            a if a < 60 * 60 => 16,
+            a if a < 24 * 60 * 60 => 8,
+            a if a < 7 * 24 * 60 * 60 => 2,
+            _ => 1,
+        };
+        self.count * weight
+    }
+}
+
+/// Serializable pull request info
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPrInfo {
+    pub number: u64,
+    pub state: String,
+}
+
+/// Pull requests by head branch name, as last fetched from gh
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedPullRequests {
+    /// Unix timestamp of the last gh lookup
+    pub timestamp: u64,
+    pub by_branch: HashMap<String, CachedPrInfo>,
 }
 
 /// The full cache structure with metadata
@@ -56,6 +144,18 @@ pub struct WorktreeCache {
     pub repo_root: PathBuf,
     /// Cached worktree data
     pub worktrees: Vec<CachedWorktree>,
+    /// Last UI state for this repo
+    #[serde(default)]
+    pub ui_state: Option<CachedUiState>,
+    /// Pull request lookup results (only when enabled in config)
+    #[serde(default)]
+    pub pull_requests: Option<CachedPullRequests>,
+    /// User notes by worktree path
+    #[serde(default)]
+    pub notes: HashMap<PathBuf, String>,
+    /// Access tracking by worktree path, for the frecency sort
+    #[serde(default)]
+    pub access: HashMap<PathBuf, AccessStats>,
 }
 
 impl WorktreeCache {
@@ -69,7 +169,6 @@ impl WorktreeCache {
     }
 
     /// Get age of cache in seconds
-    #[allow(dead_code)]
     pub fn age_secs(&self) -> u64 {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -79,6 +178,14 @@ impl WorktreeCache {
     }
 }
 
+/// Current Unix timestamp in seconds
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Get the cache directory path (~/.cache/wtt/)
 fn cache_dir() -> Option<PathBuf> {
     dirs::cache_dir().map(|d| d.join("wtt"))
@@ -135,8 +242,61 @@ pub fn save_cache(cache: &WorktreeCache) -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// Drop the cached worktrees and pull requests of a repo, keeping its UI state, notes and
+/// access data
+pub fn clear_cache(repo_root: &PathBuf) -> Result<(), std::io::Error> {
+    let Some(mut cache) = load_cache(repo_root) else {
+        return Ok(());
+    };
+    cache.worktrees.clear();
+    cache.pull_requests = None;
+    cache.timestamp = 0;
+    save_cache(&cache)
+}
+
+/// Update only the UI state of the repo's cache, creating a stale one if there is none yet
+pub fn save_ui_state(repo_root: &PathBuf, ui_state: CachedUiState) -> Result<(), std::io::Error> {
+    let mut cache = load_or_create_cache(repo_root);
+    cache.ui_state = Some(ui_state);
+    save_cache(&cache)
+}
+
+/// The repo's cache, or a stale empty one if there is none yet
+fn load_or_create_cache(repo_root: &PathBuf) -> WorktreeCache {
+    load_cache(repo_root).unwrap_or_else(|| {
+        // Timestamp 0 keeps the next startup from trusting the empty worktree list
+        let mut cache = create_cache(repo_root.clone(), Vec::new(), None);
+        cache.timestamp = 0;
+        cache
+    })
+}
+
+/// Update only the notes of the repo's cache, creating a stale one if there is none yet
+pub fn save_notes(
+    repo_root: &PathBuf,
+    notes: &HashMap<PathBuf, String>,
+) -> Result<(), std::io::Error> {
+    let mut cache = load_or_create_cache(repo_root);
+    cache.notes = notes.clone();
+    save_cache(&cache)
+}
+
+/// Update only the access tracking of the repo's cache, creating a stale one if needed
+pub fn save_access(
+    repo_root: &PathBuf,
+    access: &HashMap<PathBuf, AccessStats>,
+) -> Result<(), std::io::Error> {
+    let mut cache = load_or_create_cache(repo_root);
+    cache.access = access.clone();
+    save_cache(&cache)
+}
+
 /// Create a new cache with current timestamp
-pub fn create_cache(repo_root: PathBuf, worktrees: Vec<CachedWorktree>) -> WorktreeCache {
+pub fn create_cache(
+    repo_root: PathBuf,
+    worktrees: Vec<CachedWorktree>,
+    ui_state: Option<CachedUiState>,
+) -> WorktreeCache {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -146,5 +306,9 @@ pub fn create_cache(repo_root: PathBuf, worktrees: Vec<CachedWorktree>) -> Workt
         timestamp,
         repo_root,
         worktrees,
+        ui_state,
+        pull_requests: None,
+        notes: HashMap::new(),
+        access: HashMap::new(),
     }
 }
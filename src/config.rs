@@ -0,0 +1,186 @@
+//! Config module for user settings
+//! Loaded from ~/.config/wtt/config.json; any missing field falls back to its default
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Glyph set for table markers and headers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Nerd Font glyphs (needs a patched font)
+    #[default]
+    Nerd,
+    /// Plain ASCII, works in any terminal
+    Ascii,
+    Emoji,
+}
+
+/// What `Enter` does with the selected worktree
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterAction {
+    /// Show the path in the status line (what `o` does)
+    ShowPath,
+    /// Quit and change into it, like `Space`
+    #[default]
+    Cd,
+    /// Suspend the TUI and run `$VISUAL`/`$EDITOR` in it
+    OpenEditor,
+    /// Open a new terminal window there
+    OpenTerminal,
+}
+
+/// Which part of a long name is replaced with `...` when it doesn't fit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncateStyle {
+    /// Keep the end, e.g. `...worktrees/feature`
+    Start,
+    /// Keep both ends, e.g. `feature/JIRA-1...add-login`
+    Middle,
+    /// Keep the beginning, e.g. `feature/JIRA-1234-a...`
+    End,
+}
+
+/// When to ring the terminal bell after an operation finishes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BellOn {
+    #[default]
+    Off,
+    /// Only when it failed
+    Error,
+    /// Whenever it finishes, including refreshes started with `r`
+    All,
+}
+
+/// Named conventions for new worktrees, picked in the create dialog
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CreateTemplate {
+    pub name: String,
+    /// Prepended to the typed name, e.g. `feature/`
+    pub branch_prefix: String,
+    /// Branch the new one starts from (default: HEAD)
+    pub base_branch: Option<String>,
+    /// Files copied from the main worktree, relative to its root (e.g. `.env`)
+    pub copy_files: Vec<String>,
+    /// Shell commands run in the new worktree, in order, after the files are copied
+    pub post_create: Vec<String>,
+}
+
+/// User settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Restore the last search, sort order, panel visibility and history order when reopening a repo
+    pub restore_ui_state: bool,
+    /// Look up pull requests for worktree branches with the GitHub CLI (`gh`)
+    pub show_pull_requests: bool,
+    /// Git executable to run instead of `git` from PATH (the `WTT_GIT` env var wins)
+    pub git_path: Option<String>,
+    /// Ask for confirmation (with a commit count preview) before merging
+    pub confirm_merge: bool,
+    /// Maximum number of git commands run at once during a refresh (default: CPU count)
+    pub max_parallel_fetch: Option<usize>,
+    /// Show the first lines of each worktree's README in the details panel
+    pub show_readme_preview: bool,
+    /// Show the full 40-character commit hash in the details panel
+    pub show_full_hash: bool,
+    /// Look up gitignored files too: counted apart from untracked ones, listed in details
+    pub show_ignored: bool,
+    /// Glyphs for table markers and headers: `nerd`, `ascii` or `emoji`
+    pub icons: IconSet,
+    /// Show the HEAD commit author's initials as a colored column in the table
+    pub show_author_initials: bool,
+    /// Shell command run by `T` instead of opening a plain tmux window; `{path}`,
+    /// `{branch}` and `{repo}` are replaced with shell-quoted values
+    pub tmux_template: Option<String>,
+    /// Creation templates offered in the create dialog (`Ctrl+T` cycles them)
+    pub templates: Vec<CreateTemplate>,
+    /// Operations affecting more worktrees than this list every one of them for review
+    /// before running
+    pub confirm_batch_above: usize,
+    /// Refuse every action that changes the repository (same as `--read-only`)
+    pub read_only: bool,
+    /// Quit and change into a new worktree as soon as it is created
+    pub cd_after_create: bool,
+    /// Where new worktrees are created; relative paths are taken from the main worktree
+    /// (e.g. `.worktrees`) and `{repo}` is replaced with the repo name.
+    /// Default: a `<repo>-worktrees` directory next to the repo
+    pub worktrees_dir: Option<String>,
+    /// What `Enter` does: `show_path`, `cd`, `open_editor` or `open_terminal`
+    pub enter_action: EnterAction,
+    /// Branch merges target and unique commits are counted against, instead of
+    /// detecting it (origin/HEAD, `init.defaultBranch`, then main/master/develop/trunk)
+    pub main_branch: Option<String>,
+    /// Ring the terminal bell when an operation finishes: `off`, `error` or `all`
+    pub bell_on_complete: BellOn,
+    /// How branch names that don't fit are shortened: `start`, `middle` or `end`
+    pub branch_truncation: TruncateStyle,
+    /// How paths that don't fit are shortened: `start`, `middle` or `end`
+    pub path_truncation: TruncateStyle,
+    /// Build/test command `K` runs in the selected worktree (through `sh -c`)
+    pub verify_command: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            restore_ui_state: false,
+            show_pull_requests: false,
+            git_path: None,
+            confirm_merge: true,
+            max_parallel_fetch: None,
+            show_readme_preview: false,
+            show_full_hash: false,
+            show_ignored: false,
+            icons: IconSet::Nerd,
+            show_author_initials: false,
+            tmux_template: None,
+            templates: Vec::new(),
+            confirm_batch_above: 5,
+            read_only: false,
+            cd_after_create: false,
+            worktrees_dir: None,
+            enter_action: EnterAction::Cd,
+            main_branch: None,
+            bell_on_complete: BellOn::Off,
+            branch_truncation: TruncateStyle::Middle,
+            path_truncation: TruncateStyle::Start,
+            verify_command: None,
+        }
+    }
+}
+
+impl Config {
+    /// Effective refresh concurrency: the configured limit, else the number of CPUs
+    pub fn parallel_fetch_limit(&self) -> usize {
+        self.max_parallel_fetch
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(4)
+            })
+            .max(1)
+    }
+}
+
+/// Get the config file path (~/.config/wtt/config.json)
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("wtt").join("config.json"))
+}
+
+/// Load config from disk. A missing file yields the defaults; a malformed one is an error.
+pub fn load_config(path: &Path) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config '{}'", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid config '{}'", path.display()))
+}
@@ -0,0 +1,43 @@
+//! Typed errors for failures callers may want to react to, rather than just display
+
+use std::{path::PathBuf, process::Output};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum WttError {
+    /// A git command exited unsuccessfully
+    #[error("git {command} failed: {stderr}")]
+    GitCommandFailed { command: String, stderr: String },
+    /// The directory is not inside a git repository
+    #[error("Not a git repository: {}", .0.display())]
+    NotARepo(PathBuf),
+    /// Reading or writing the on-disk cache failed
+    #[error("Cache error: {0}")]
+    Cache(#[from] std::io::Error),
+    /// The worktree has to be unlocked before it can be changed
+    #[error(
+        "Worktree {} is locked{}",
+        .path.display(),
+        .reason.as_ref().map(|r| format!(" ({})", r)).unwrap_or_default()
+    )]
+    WorktreeLocked {
+        path: PathBuf,
+        reason: Option<String>,
+    },
+}
+
+impl WttError {
+    /// Capture a failed git command's message (stderr, or stdout when git wrote nothing there)
+    pub fn git_failed(command: &str, output: &Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = if stderr.trim().is_empty() {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            stderr
+        };
+        Self::GitCommandFailed {
+            command: command.to_string(),
+            stderr: message.trim().to_string(),
+        }
+    }
+}
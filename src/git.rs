@@ -0,0 +1,19 @@
+//! Git executable selection shared by every spawned git command
+
+use std::{process::Command, sync::RwLock};
+
+/// Git executable for every spawned git command; empty means `git` from PATH
+static GIT_PATH: RwLock<String> = RwLock::new(String::new());
+
+/// A `git` command using the configured executable
+pub fn git_command() -> Command {
+    let path = GIT_PATH.read().map(|p| p.clone()).unwrap_or_default();
+    Command::new(if path.is_empty() { "git" } else { &path })
+}
+
+/// Use `path` for all later git commands; an empty string means `git` from PATH
+pub fn set_git_path(path: &str) {
+    if let Ok(mut current) = GIT_PATH.write() {
+        *current = path.to_string();
+    }
+}
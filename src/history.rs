@@ -0,0 +1,123 @@
+//! Repository history module for the repo switcher
+//! Remembers recently opened repositories (and pinned favorites) across sessions
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of unpinned repos to remember (pinned repos are always kept)
+const MAX_HISTORY: usize = 20;
+
+/// A single remembered repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoEntry {
+    /// Main worktree root of the repository
+    pub path: PathBuf,
+    /// Unix timestamp of the last time the repo was opened
+    pub last_opened: u64,
+    /// Pinned repos stay at the top and are never evicted
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl RepoEntry {
+    /// Display name for the repo (its directory name)
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+}
+
+/// The full history file structure
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepoHistory {
+    pub repos: Vec<RepoEntry>,
+}
+
+impl RepoHistory {
+    /// Record that a repo was opened, moving it to the front of the history
+    pub fn record(&mut self, repo_root: &Path) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        match self.repos.iter_mut().find(|r| r.path == repo_root) {
+            Some(entry) => entry.last_opened = now,
+            None => self.repos.push(RepoEntry {
+                path: repo_root.to_path_buf(),
+                last_opened: now,
+                pinned: false,
+            }),
+        }
+
+        self.sort();
+
+        // Evict the oldest unpinned entries beyond the cap
+        let mut unpinned = 0;
+        self.repos.retain(|r| {
+            if r.pinned {
+                return true;
+            }
+            unpinned += 1;
+            unpinned <= MAX_HISTORY
+        });
+    }
+
+    /// Toggle the pinned flag of a repo, returning the new state
+    pub fn toggle_pin(&mut self, path: &Path) -> bool {
+        let pinned = match self.repos.iter_mut().find(|r| r.path == path) {
+            Some(entry) => {
+                entry.pinned = !entry.pinned;
+                entry.pinned
+            }
+            None => false,
+        };
+        self.sort();
+        pinned
+    }
+
+    /// Pinned repos first, then most recently opened
+    fn sort(&mut self) {
+        self.repos.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then(b.last_opened.cmp(&a.last_opened))
+        });
+    }
+}
+
+/// Get the history file path (~/.local/share/wtt/repos.json)
+fn history_file_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("wtt").join("repos.json"))
+}
+
+/// Load the repo history from disk (empty if missing or unreadable)
+pub fn load_history() -> RepoHistory {
+    history_file_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Save the repo history to disk
+pub fn save_history(history: &RepoHistory) -> Result<(), std::io::Error> {
+    let path = history_file_path().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not determine data directory",
+        )
+    })?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let content = serde_json::to_string_pretty(history)?;
+    fs::write(&path, content)?;
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Worktree engine behind the `wtt` TUI, usable without the terminal interface
+//!
+//! ```no_run
+//! let worktrees = worktree_tui::list_worktrees(std::path::Path::new("."))?;
+//! for wt in &worktrees {
+//!     println!("{} {}", wt.path.display(), wt.status.summary());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+pub mod error;
+pub mod git;
+pub mod worktree;
+
+pub use error::WttError;
+pub use git::{git_command, set_git_path};
+pub use worktree::{list_worktrees, CommitInfo, PrInfo, Worktree, WorktreeStatus};
@@ -2,6 +2,9 @@
 //! Designed with Claude's visual aesthetic: warm tones, clean typography, intuitive interactions
 
 mod cache;
+mod config;
+mod history;
+mod shell;
 
 use anyhow::{Context, Result};
 use crossterm::{
@@ -16,7 +19,7 @@ use futures::StreamExt;
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
-    style::Style,
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{
         Block, BorderType, Borders, Cell, Clear, List, ListItem, ListState, Padding, Paragraph,
@@ -25,10 +28,15 @@ use ratatui::{
     Frame, Terminal,
 };
 use std::{
+    collections::HashMap,
     fs::File,
-    io::{self, Stdout, Write},
-    path::PathBuf,
+    io::{self, Stderr, Stdout, Write},
+    path::{Path, PathBuf},
     process::Command,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
 use tokio::sync::mpsc;
@@ -39,6 +47,14 @@ use tracing_subscriber::{
     EnvFilter,
 };
 use unicode_width::UnicodeWidthStr;
+use worktree_tui::{
+    git::{git_command, set_git_path},
+    worktree::{
+        canonical_path, discover_worktrees, fetch_all_worktrees, format_relative_time,
+        get_stash_counts, load_worktree_details, COMMIT_COUNT_CAP,
+    },
+    CommitInfo, PrInfo, Worktree, WorktreeStatus, WttError,
+};
 
 // ============================================================================
 // Claude Design System - Warm, approachable colors inspired by Claude's aesthetic
@@ -66,87 +82,107 @@ mod colors {
     pub const BORDER_ACTIVE: Color = CLAUDE_ORANGE;
     pub const BORDER_INACTIVE: Color = Color::Rgb(68, 64, 60);
     pub const SELECTION_BG: Color = Color::Rgb(34, 30, 26);
+
+    // Author initials, picked by a hash of the email
+    pub const AUTHOR_PALETTE: [Color; 8] = [
+        Color::Rgb(217, 119, 87),
+        Color::Rgb(134, 239, 172),
+        Color::Rgb(147, 197, 253),
+        Color::Rgb(196, 181, 253),
+        Color::Rgb(235, 200, 142),
+        Color::Rgb(244, 143, 177),
+        Color::Rgb(94, 234, 212),
+        Color::Rgb(253, 186, 116),
+    ];
 }
 
 // ============================================================================
-// Data Models
+// Icons
 // ============================================================================
 
-#[derive(Debug, Clone)]
-struct Worktree {
-    path: PathBuf,
-    branch: Option<String>,
-    commit: String,
-    commit_short: String,
-    commit_message: String,
-    commit_time: Option<i64>,
-    is_main: bool,
-    is_current: bool,
-    is_bare: bool,
-    is_detached: bool,
-    is_locked: bool,
-    lock_reason: Option<String>,
-    is_prunable: bool,
-    status: WorktreeStatus,
-    recent_commits: Vec<CommitInfo>,
-}
-
-#[derive(Debug, Clone)]
-struct CommitInfo {
-    hash: String,
-    message: String,
-    time_ago: String,
+/// Glyphs for markers in the table, header and branch list
+struct Icons {
+    current: &'static str,
+    main: &'static str,
+    locked: &'static str,
+    prunable: &'static str,
+    worktree: &'static str,
+    sort: &'static str,
+    branch_current: &'static str,
+    branch_remote: &'static str,
 }
 
-#[derive(Debug, Clone, Default)]
-struct WorktreeStatus {
-    modified: usize,
-    staged: usize,
-    untracked: usize,
-    ahead: usize,
-    behind: usize,
-}
+const NERD_ICONS: Icons = Icons {
+    current: "*",
+    main: "\u{f015}",
+    locked: "\u{f023}",
+    prunable: "\u{f071}",
+    worktree: "\u{f07b}",
+    sort: "\u{f160}",
+    branch_current: "\u{e0a0}",
+    branch_remote: "\u{f0c2}",
+};
 
-impl WorktreeStatus {
-    fn is_clean(&self) -> bool {
-        self.modified == 0 && self.staged == 0 && self.untracked == 0
-    }
+const ASCII_ICONS: Icons = Icons {
+    current: "*",
+    main: "M",
+    locked: "L",
+    prunable: "!",
+    worktree: " ",
+    sort: "sort:",
+    branch_current: "*",
+    branch_remote: "r",
+};
 
-    fn summary(&self) -> String {
-        if self.is_clean() && self.ahead == 0 && self.behind == 0 {
-            return String::from("clean");
-        }
+const EMOJI_ICONS: Icons = Icons {
+    current: "👉",
+    main: "🏠",
+    locked: "🔒",
+    prunable: "❗",
+    worktree: "📁",
+    sort: "🔃",
+    branch_current: "🌿",
+    branch_remote: "🌐",
+};
 
-        let mut parts = Vec::new();
-        if self.staged > 0 {
-            parts.push(format!("+{}", self.staged));
-        }
-        if self.modified > 0 {
-            parts.push(format!("~{}", self.modified));
-        }
-        if self.untracked > 0 {
-            parts.push(format!("?{}", self.untracked));
-        }
-        if self.ahead > 0 {
-            parts.push(format!("↑{}", self.ahead));
-        }
-        if self.behind > 0 {
-            parts.push(format!("↓{}", self.behind));
-        }
-        parts.join(" ")
+fn icons(set: config::IconSet) -> &'static Icons {
+    match set {
+        config::IconSet::Nerd => &NERD_ICONS,
+        config::IconSet::Ascii => &ASCII_ICONS,
+        config::IconSet::Emoji => &EMOJI_ICONS,
     }
 }
 
+// ============================================================================
+// Data Models
+// ============================================================================
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AppMode {
     Normal,
     Help,
     Create,
     Delete,
+    Commit,
+    NewBranch,
+    Note,
+    Import,
+    Command,
     Search,
+    Hint,
     BranchSelect,
+    UpstreamSelect,
     MergeSelect,
     MergeConfirm,
+    RepoSelect,
+    Health,
+    SyncReport,
+    PruneWizard,
+    BatchConfirm,
+    Diff,
+    Verify,
+    ExcludeConfirm,
+    BranchExists,
     Error,
 }
 
@@ -171,6 +207,320 @@ struct Branch {
     name: String,
     is_remote: bool,
     is_current: bool,
+    /// Commit the branch points at; empty where it isn't needed
+    commit: String,
+    /// Upstream of a local branch, e.g. `origin/feature`
+    upstream: Option<String>,
+    /// The upstream is at the same commit, so this entry stands for both
+    tracked: bool,
+}
+
+impl Branch {
+    /// Name to pick as an upstream: the remote branch a collapsed entry also stands for
+    fn upstream_name(&self) -> &str {
+        match &self.upstream {
+            Some(upstream) if self.tracked => upstream,
+            _ => &self.name,
+        }
+    }
+}
+
+/// How the worktree table is split into sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GroupBy {
+    None,
+    Status,
+    Merged,
+    Remote,
+}
+
+impl GroupBy {
+    fn next(&self) -> Self {
+        match self {
+            GroupBy::None => GroupBy::Status,
+            GroupBy::Status => GroupBy::Merged,
+            GroupBy::Merged => GroupBy::Remote,
+            GroupBy::Remote => GroupBy::None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            GroupBy::None => "none",
+            GroupBy::Status => "status",
+            GroupBy::Merged => "merged",
+            GroupBy::Remote => "remote",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "none" => Some(GroupBy::None),
+            "status" => Some(GroupBy::Status),
+            "merged" => Some(GroupBy::Merged),
+            "remote" => Some(GroupBy::Remote),
+            _ => None,
+        }
+    }
+}
+
+/// How worktree paths are shown in the details panel and picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PathDisplayMode {
+    Absolute,
+    /// Home directory abbreviated to `~`
+    Home,
+    /// Relative to the parent of the repo root, e.g. `repo-worktrees/feature`
+    Relative,
+}
+
+impl PathDisplayMode {
+    fn next(&self) -> Self {
+        match self {
+            PathDisplayMode::Absolute => PathDisplayMode::Home,
+            PathDisplayMode::Home => PathDisplayMode::Relative,
+            PathDisplayMode::Relative => PathDisplayMode::Absolute,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PathDisplayMode::Absolute => "absolute",
+            PathDisplayMode::Home => "home",
+            PathDisplayMode::Relative => "relative",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "absolute" => Some(PathDisplayMode::Absolute),
+            "home" => Some(PathDisplayMode::Home),
+            "relative" => Some(PathDisplayMode::Relative),
+            _ => None,
+        }
+    }
+}
+
+/// A row of the worktree table: a group header, or a position in `filtered_indices`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TableRow {
+    Header(String, usize),
+    Worktree(usize),
+}
+
+/// Actions that only apply to some worktrees, so their hints can be dimmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Create,
+    CdInto,
+    Delete,
+    Merge,
+    PullPush,
+}
+
+/// Follow-up the error dialog offers for errors it knows how to fix
+#[derive(Debug, Clone)]
+enum ErrorAction {
+    UnlockAndDelete,
+    InitRepo(PathBuf),
+}
+
+/// What was going on when the last error was reported, for the diagnostic copy
+#[derive(Debug, Clone, Default)]
+struct ErrorContext {
+    /// The failing git command line, when the error came from git
+    command: Option<String>,
+    /// Worktree the error happened in
+    worktree: Option<PathBuf>,
+}
+
+/// A problem found by the worktree health check
+#[derive(Debug, Clone)]
+struct HealthIssue {
+    /// Index into `worktrees` of the affected worktree
+    worktree_idx: usize,
+    problem: String,
+    suggestion: &'static str,
+    level: MessageLevel,
+}
+
+/// What a fast-forward of all worktrees did to one of them
+#[derive(Debug, Clone)]
+enum SyncOutcome {
+    FastForwarded,
+    UpToDate,
+    Diverged,
+    Dirty,
+    UpstreamGone,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+struct SyncResult {
+    path: PathBuf,
+    name: String,
+    outcome: SyncOutcome,
+}
+
+/// One prunable worktree in the step-by-step cleanup
+#[derive(Debug, Clone)]
+struct PruneStep {
+    path: PathBuf,
+    name: String,
+    /// Why git considers it prunable, when it says
+    reason: Option<String>,
+}
+
+/// Step-by-step cleanup of prunable worktrees: prune, repair or skip each one
+#[derive(Debug, Clone, Default)]
+struct PruneWizard {
+    steps: Vec<PruneStep>,
+    current: usize,
+    /// Asking where a moved worktree went
+    repairing: bool,
+    repair_input: String,
+    repair_cursor: usize,
+    /// Why the last prune or repair failed; kept in the wizard instead of the error popup
+    error: Option<String>,
+    pruned: usize,
+    repaired: usize,
+    skipped: usize,
+}
+
+/// An operation over many worktrees waiting for the user to review its targets
+#[derive(Debug, Clone)]
+struct BatchConfirm {
+    op: PendingOp,
+    title: String,
+    /// One line per affected worktree
+    affected: Vec<String>,
+}
+
+/// What the create dialog does with its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CreateMode {
+    /// New branch (named after the worktree) from a base branch
+    NewBranch,
+    /// Existing branch checked out into a new worktree directory
+    CheckoutExisting,
+    /// Detached HEAD at a commit-ish, no branch created
+    Detached,
+}
+
+impl CreateMode {
+    fn next(&self) -> Self {
+        match self {
+            CreateMode::NewBranch => CreateMode::CheckoutExisting,
+            CreateMode::CheckoutExisting => CreateMode::Detached,
+            CreateMode::Detached => CreateMode::NewBranch,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            CreateMode::NewBranch => "Create New Branch",
+            CreateMode::CheckoutExisting => "Checkout Existing",
+            CreateMode::Detached => "Detached at Commit",
+        }
+    }
+}
+
+/// How `perform_merge` merges the source branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeStrategy {
+    Default,
+    FastForwardOnly,
+    NoFastForward,
+    Squash,
+}
+
+impl MergeStrategy {
+    fn next(&self) -> Self {
+        match self {
+            MergeStrategy::Default => MergeStrategy::FastForwardOnly,
+            MergeStrategy::FastForwardOnly => MergeStrategy::NoFastForward,
+            MergeStrategy::NoFastForward => MergeStrategy::Squash,
+            MergeStrategy::Squash => MergeStrategy::Default,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            MergeStrategy::Default => "default",
+            MergeStrategy::FastForwardOnly => "ff-only",
+            MergeStrategy::NoFastForward => "no-ff",
+            MergeStrategy::Squash => "squash",
+        }
+    }
+
+    /// Extra `git merge` flag for this strategy
+    fn flag(&self) -> Option<&'static str> {
+        match self {
+            MergeStrategy::Default => None,
+            MergeStrategy::FastForwardOnly => Some("--ff-only"),
+            MergeStrategy::NoFastForward => Some("--no-ff"),
+            MergeStrategy::Squash => Some("--squash"),
+        }
+    }
+}
+
+/// Which commit messages the search matches against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SearchScope {
+    /// Only the latest commit of each worktree
+    Latest,
+    /// Every commit in the recent history
+    History,
+}
+
+impl SearchScope {
+    fn next(&self) -> Self {
+        match self {
+            SearchScope::Latest => SearchScope::History,
+            SearchScope::History => SearchScope::Latest,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SearchScope::Latest => "latest commit",
+            SearchScope::History => "recent history",
+        }
+    }
+}
+
+/// Which branches the base-branch picker shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BranchFilter {
+    Local,
+    All,
+    Remote,
+}
+
+impl BranchFilter {
+    fn next(&self) -> Self {
+        match self {
+            BranchFilter::Local => BranchFilter::All,
+            BranchFilter::All => BranchFilter::Remote,
+            BranchFilter::Remote => BranchFilter::Local,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            BranchFilter::Local => "local",
+            BranchFilter::All => "all",
+            BranchFilter::Remote => "remote",
+        }
+    }
+
+    fn matches(&self, branch: &Branch) -> bool {
+        match self {
+            BranchFilter::Local => !branch.is_remote,
+            BranchFilter::All => true,
+            BranchFilter::Remote => branch.is_remote || branch.tracked,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -178,6 +528,8 @@ enum SortOrder {
     Name,
     Status,
     Recent,
+    /// Most (and most recently) opened from wtt first
+    Frecency,
 }
 
 impl SortOrder {
@@ -185,7 +537,8 @@ impl SortOrder {
         match self {
             SortOrder::Name => SortOrder::Status,
             SortOrder::Status => SortOrder::Recent,
-            SortOrder::Recent => SortOrder::Name,
+            SortOrder::Recent => SortOrder::Frecency,
+            SortOrder::Frecency => SortOrder::Name,
         }
     }
 
@@ -194,6 +547,17 @@ impl SortOrder {
             SortOrder::Name => "name",
             SortOrder::Status => "status",
             SortOrder::Recent => "recent",
+            SortOrder::Frecency => "frecency",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "name" => Some(SortOrder::Name),
+            "status" => Some(SortOrder::Status),
+            "recent" => Some(SortOrder::Recent),
+            "frecency" => Some(SortOrder::Frecency),
+            _ => None,
         }
     }
 }
@@ -209,10 +573,69 @@ enum LoadingState {
     Loading,
 }
 
+/// Blocking git operation queued to run right after the next frame is drawn,
+/// so the spinner and progress message are on screen while it runs
+#[derive(Debug, Clone)]
+enum PendingOp {
+    Pull,
+    Push,
+    FetchAll,
+    FetchSelected,
+    Prune,
+    FastForwardMain,
+    /// `git pull --ff-only` in every worktree that has an upstream
+    SyncAll,
+    Delete,
+    UnlockAndDelete,
+    Commit,
+    Merge(usize, String),
+    /// Copy files and run commands from a creation template in a new worktree
+    PostCreate(PathBuf, config::CreateTemplate),
+    /// Track the given branch from the worktree's branch
+    SetUpstream(PathBuf, String),
+}
+
+impl PendingOp {
+    fn label(&self) -> String {
+        match self {
+            PendingOp::Pull => "Pulling...".into(),
+            PendingOp::Push => "Pushing...".into(),
+            PendingOp::FetchAll => "Fetching from remote...".into(),
+            PendingOp::FetchSelected => "Fetching upstream...".into(),
+            PendingOp::Prune => "Pruning stale worktrees...".into(),
+            PendingOp::FastForwardMain => "Fast-forwarding main...".into(),
+            PendingOp::SyncAll => "Fast-forwarding all worktrees...".into(),
+            PendingOp::Delete => "Deleting worktree...".into(),
+            PendingOp::UnlockAndDelete => "Unlocking and deleting worktree...".into(),
+            PendingOp::Commit => "Committing...".into(),
+            PendingOp::Merge(_, target) => format!("Merging into {}...", target),
+            PendingOp::PostCreate(_, template) => {
+                format!("Applying template {}...", template.name)
+            }
+            PendingOp::SetUpstream(_, upstream) => format!("Setting upstream to {}...", upstream),
+        }
+    }
+
+    /// Ops that change the worktree list, after which queued keys may hit the wrong row
+    fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            PendingOp::Delete | PendingOp::UnlockAndDelete | PendingOp::Merge(..)
+        )
+    }
+}
+
 /// Message sent from background refresh task
 #[derive(Debug)]
 enum AppUpdate {
-    WorktreesLoaded(Vec<Worktree>),
+    /// Worktrees loaded for the given repo root, and how long the scan took
+    WorktreesLoaded(PathBuf, Vec<Worktree>, Duration),
+    /// Pull requests (by head branch) loaded for the given repo root
+    PullRequestsLoaded(PathBuf, HashMap<String, PrInfo>),
+    /// A line of output from the verify command running in a worktree
+    VerifyOutput(PathBuf, String),
+    /// The verify command exited (code, None if killed by a signal), and how long it ran
+    VerifyDone(PathBuf, Option<i32>, Duration),
 }
 
 struct App {
@@ -223,6 +646,12 @@ struct App {
     should_quit: bool,
     cd_path: Option<PathBuf>, // Path to change to on exit (for shell integration)
 
+    // User settings
+    config: config::Config,
+    config_path: Option<PathBuf>,
+    // Set by `--read-only`; `is_read_only` also honors the config setting
+    read_only: bool,
+
     // Repository info
     repo_root: PathBuf,
     repo_name: String,
@@ -232,47 +661,209 @@ struct App {
     status_message: Option<StatusMessage>,
     sort_order: SortOrder,
     show_recent_commits: bool,
+    // List the details panel's recent history chronologically instead of newest first
+    history_oldest_first: bool,
+    show_readme_preview: bool,
+    show_full_hash: bool,
+    // Include gitignored files in status lookups (starts from `config.show_ignored`)
+    show_ignored: bool,
+    // Worktree selected before the current one, for `-` to swap back to
+    previous_selection: Option<PathBuf>,
+    // Only the worktree list, one line per worktree, without header, details or borders
+    mini_view: bool,
+    group_by: GroupBy,
+    path_display: PathDisplayMode,
+    // Selection/scroll of the table including group header rows
+    group_table_state: TableState,
+    // Upstream remote by local branch, for grouping by remote
+    branch_remotes: HashMap<String, String>,
+    // Transient key hint overlay, dismissed by the next keypress
+    show_key_hints: bool,
 
     // Loading state for async refresh
     loading_state: LoadingState,
+    // Unix timestamp of the cache the list came from; None once live data has loaded
+    cached_at: Option<u64>,
+    // How long the last full refresh took
+    last_refresh_duration: Option<Duration>,
+    // Stashes in the repo, including ones on branches without a worktree
+    stash_total: usize,
     spinner_frame: usize,
+    pending_op: Option<PendingOp>,
+    // Something the running queued op did failed, for the completion bell
+    op_failed: bool,
+    // The running background refresh was asked for with `r`, so it rings the bell
+    refresh_requested: bool,
+    // Shared with in-flight background tasks; set to abort them
+    background_cancel: Arc<AtomicBool>,
+
+    // Pull requests by head branch (from gh, if enabled)
+    pull_requests: HashMap<String, PrInfo>,
+    pull_requests_fetched_at: u64,
+
+    // Free-text notes by worktree path, persisted in the repo's cache
+    notes: HashMap<PathBuf, String>,
+    // How often each worktree was opened from wtt, persisted in the repo's cache
+    access: HashMap<PathBuf, cache::AccessStats>,
 
     // Create dialog
     create_input: String,
     create_cursor: usize,
+
+    // Commit dialog state
+    commit_input: String,
+    commit_cursor: usize,
+
+    // New-branch dialog state
+    branch_input: String,
+    branch_cursor: usize,
+
+    // Note dialog state
+    note_input: String,
+    note_cursor: usize,
+
+    // Import dialog state: an existing directory and the branch to check out there
+    import_path_input: String,
+    import_path_cursor: usize,
+    import_branch_input: String,
+    import_branch_cursor: usize,
+    import_branch_focused: bool,
+
+    // `:` command prompt state
+    command_input: String,
+    command_cursor: usize,
     available_branches: Vec<Branch>,
+    branch_filter: BranchFilter,
+    // Remote refs are only listed once the branch filter asks for them
+    remote_branches_loaded: bool,
     branch_list_state: ListState,
+    // Substring filter typed into the branch picker after `/`
+    branch_query: String,
+    branch_query_cursor: usize,
+    branch_query_focused: bool,
     create_from_branch: Option<String>,
-    create_checkout_existing: bool,
+    create_mode: CreateMode,
+    // Index into `config.templates` picked in the create dialog
+    create_template: Option<usize>,
+    // Create the worktree already locked (`git worktree add --lock`)
+    create_lock: bool,
+    // Set when the create dialog opens if new worktrees can't be written
+    create_dir_warning: Option<String>,
     merge_source_idx: Option<usize>,
     merge_target_branch: Option<String>,
+    // (commits to merge, fast-forward possible) for the pending merge, if known
+    merge_preview: Option<(usize, bool)>,
+    merge_strategy: MergeStrategy,
+    // Conflicted files left by the last failed merge (worktree path, files)
+    merge_conflicts: Option<(PathBuf, Vec<String>)>,
+    // Worktree to run `git mergetool` in; `run_app` suspends the TUI for it
+    mergetool_path: Option<PathBuf>,
+    // Branch merges target and unique commits are counted against, and how it was found
+    main_branch: String,
+    main_branch_source: &'static str,
+    // Worktree to open `$EDITOR` in, handed over the same way
+    editor_path: Option<PathBuf>,
+    // Leave the TUI to page through the log file on the next frame
+    log_requested: bool,
 
     // Delete dialog
     delete_confirm: bool,
 
+    // Repository switcher
+    repo_history: history::RepoHistory,
+    repo_list_state: ListState,
+
+    // Health check report
+    health_issues: Vec<HealthIssue>,
+    health_list_state: ListState,
+
+    // Fast-forward all report
+    sync_results: Vec<SyncResult>,
+    sync_list_state: ListState,
+
+    // Worktrees that share a branch or admin dir with another one (by path), which
+    // git normally prevents; recomputed on every refresh
+    integrity_issues: HashMap<PathBuf, String>,
+
+    // Prunable worktree cleanup, one worktree at a time
+    prune_wizard: Option<PruneWizard>,
+
+    // Batch operation awaiting review
+    batch_confirm: Option<BatchConfirm>,
+    batch_list_state: ListState,
+
+    // Diff popup: the whole branch compared to where it forked from main
+    diff_title: String,
+    diff_lines: Vec<String>,
+    diff_scroll: usize,
+    diff_page: usize,
+    // Merge-base with main by (worktree HEAD, main's HEAD), so moving either end
+    // computes it again
+    merge_bases: HashMap<(String, String), String>,
+
+    // Verify popup: `verify_command` output for one worktree, streamed while it runs
+    verify_path: Option<PathBuf>,
+    verify_title: String,
+    verify_output: Vec<String>,
+    // None while running, then the exit code and how long it took
+    verify_result: Option<(Option<i32>, Duration)>,
+    verify_scroll: usize,
+    verify_page: usize,
+    verify_cancel: Arc<AtomicBool>,
+    // Process id of the running verify command, 0 once it's gone or not started yet
+    verify_pid: Arc<AtomicU32>,
+
+    // Offer to add an in-repo worktrees directory to .git/info/exclude
+    exclude_pattern: String,
+    exclude_declined: bool,
+    // A cd-after-create is waiting for the exclude offer to be answered
+    quit_after_exclude: bool,
+
     // Error dialog
     error_message: String,
+    error_action: Option<ErrorAction>,
+    error_context: ErrorContext,
 
     // Search
     search_query: String,
     search_cursor: usize,
+    search_scope: SearchScope,
     filtered_indices: Vec<usize>,
 
+    // Quick jump (digits typed in quick succession form one number)
+    jump_buffer: String,
+    last_jump_key: Instant,
+
     // Cached data
     last_refresh: Instant,
 
     // Mouse support
     list_area: Option<Rect>,
+    // Display position of the row under the mouse and since when, summarized in the
+    // status bar once the mouse rests there for `HOVER_DELAY`
+    hovered_row: Option<usize>,
+    hovered_since: Instant,
+
+    // Jump hints: label for each visible row (by display position) and what's typed
+    hint_labels: Vec<(String, usize)>,
+    hint_input: String,
 }
 
 impl App {
     fn new() -> Result<Self> {
-        let repo_root = Self::find_git_root()?;
+        // Config comes first: it decides which git executable to run
+        let config_path = config::config_path();
+        let (config, config_error) = match config_path.as_deref() {
+            Some(path) => match config::load_config(path) {
+                Ok(config) => (config, None),
+                Err(e) => (config::Config::default(), Some(format!("{:#}", e))),
+            },
+            None => (config::Config::default(), None),
+        };
+        let git_error = apply_git_path(&config).err();
 
-        let repo_name = repo_root
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| "repository".to_string());
+        let repo_root = Self::find_git_root(None)?;
+        let repo_name = Self::repo_name_of(&repo_root);
 
         // Get the current worktree path (where the program was run from)
         let current_worktree_path = std::env::current_dir()
@@ -281,24 +872,13 @@ impl App {
             .unwrap_or_else(|| repo_root.clone());
 
         // Try to load from cache for instant startup
-        let (worktrees, loading_state) = if let Some(cached) = cache::load_cache(&repo_root) {
-            let is_fresh = cached.is_fresh();
-            let worktrees =
-                Self::worktrees_from_cache(cached.worktrees, &repo_root, &current_worktree_path);
-            if is_fresh {
-                info!(count = worktrees.len(), "Cache hit (fresh)");
-                (worktrees, LoadingState::Idle)
-            } else {
-                info!(
-                    count = worktrees.len(),
-                    "Cache hit (stale), triggering background refresh"
-                );
-                (worktrees, LoadingState::Loading)
-            }
-        } else {
-            info!("Cache miss, triggering background load");
-            (Vec::new(), LoadingState::Loading)
-        };
+        let (worktrees, loading_state, cached_at) =
+            Self::load_cached_worktrees(&repo_root, &current_worktree_path);
+
+        // Remember this repo for the repo switcher
+        let mut repo_history = history::load_history();
+        repo_history.record(&repo_root);
+        let _ = history::save_history(&repo_history);
 
         let mut app = Self {
             worktrees,
@@ -307,6 +887,10 @@ impl App {
             should_quit: false,
             cd_path: None,
 
+            config,
+            config_path,
+            read_only: false,
+
             repo_root,
             repo_name,
             current_worktree_path,
@@ -314,30 +898,120 @@ impl App {
             status_message: None,
             sort_order: SortOrder::Recent,
             show_recent_commits: true,
+            history_oldest_first: false,
+            show_readme_preview: false,
+            show_full_hash: false,
+            show_ignored: false,
+            previous_selection: None,
+            mini_view: false,
+            group_by: GroupBy::None,
+            path_display: PathDisplayMode::Absolute,
+            group_table_state: TableState::default(),
+            branch_remotes: HashMap::new(),
+            show_key_hints: false,
 
             loading_state,
+            cached_at,
+            last_refresh_duration: None,
+            stash_total: 0,
             spinner_frame: 0,
+            pending_op: None,
+            op_failed: false,
+            refresh_requested: false,
+            background_cancel: Arc::new(AtomicBool::new(false)),
+
+            pull_requests: HashMap::new(),
+            pull_requests_fetched_at: 0,
+            notes: HashMap::new(),
+            access: HashMap::new(),
 
             create_input: String::new(),
             create_cursor: 0,
+            commit_input: String::new(),
+            commit_cursor: 0,
+            branch_input: String::new(),
+            branch_cursor: 0,
+            note_input: String::new(),
+            note_cursor: 0,
+            import_path_input: String::new(),
+            import_path_cursor: 0,
+            import_branch_input: String::new(),
+            import_branch_cursor: 0,
+            import_branch_focused: false,
+            command_input: String::new(),
+            command_cursor: 0,
             available_branches: Vec::new(),
+            branch_filter: BranchFilter::Local,
+            remote_branches_loaded: false,
             branch_list_state: ListState::default(),
+            branch_query: String::new(),
+            branch_query_cursor: 0,
+            branch_query_focused: false,
             create_from_branch: None,
-            create_checkout_existing: false,
+            create_template: None,
+            create_lock: false,
+            create_dir_warning: None,
+            create_mode: CreateMode::NewBranch,
             merge_source_idx: None,
             merge_target_branch: None,
+            merge_preview: None,
+            merge_strategy: MergeStrategy::Default,
+            merge_conflicts: None,
+            mergetool_path: None,
+            main_branch: String::new(),
+            main_branch_source: "default",
+            editor_path: None,
+            log_requested: false,
 
             delete_confirm: false,
 
+            repo_history,
+            repo_list_state: ListState::default(),
+
+            health_issues: Vec::new(),
+            integrity_issues: HashMap::new(),
+            prune_wizard: None,
+            batch_confirm: None,
+            batch_list_state: ListState::default(),
+            diff_title: String::new(),
+            diff_lines: Vec::new(),
+            diff_scroll: 0,
+            diff_page: 10,
+            merge_bases: HashMap::new(),
+            verify_path: None,
+            verify_title: String::new(),
+            verify_output: Vec::new(),
+            verify_result: None,
+            verify_scroll: 0,
+            verify_page: 10,
+            verify_cancel: Arc::new(AtomicBool::new(false)),
+            verify_pid: Arc::new(AtomicU32::new(0)),
+            exclude_pattern: String::new(),
+            exclude_declined: false,
+            quit_after_exclude: false,
+            health_list_state: ListState::default(),
+            sync_results: Vec::new(),
+            sync_list_state: ListState::default(),
+
             error_message: String::new(),
+            error_action: None,
+            error_context: ErrorContext::default(),
 
             search_query: String::new(),
             search_cursor: 0,
+            search_scope: SearchScope::Latest,
             filtered_indices: Vec::new(),
 
+            jump_buffer: String::new(),
+            last_jump_key: Instant::now(),
+
             last_refresh: Instant::now(),
 
             list_area: None,
+            hovered_row: None,
+            hovered_since: Instant::now(),
+            hint_labels: Vec::new(),
+            hint_input: String::new(),
         };
 
         // Apply sorting to cached data
@@ -347,22 +1021,123 @@ impl App {
             app.table_state.select(Some(0));
         }
 
+        app.show_readme_preview = app.config.show_readme_preview;
+        app.show_full_hash = app.config.show_full_hash;
+        app.show_ignored = app.config.show_ignored;
+        app.restore_ui_state();
+        app.load_cached_pull_requests();
+        app.load_notes();
+        app.load_access();
+        app.load_stash_total();
+        app.update_main_branch();
+        // Cached worktrees are shown until the refresh lands, so check them too
+        app.check_integrity();
+
+        if let Some(error) = config_error {
+            info!(%error, "Failed to load config, using defaults");
+            app.set_status(&error, MessageLevel::Warning);
+        }
+        if let Some(error) = git_error {
+            app.set_status(&format!("{:#}", error), MessageLevel::Warning);
+        }
+
         Ok(app)
     }
 
+    fn repo_name_of(repo_root: &Path) -> String {
+        repo_root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "repository".to_string())
+    }
+
+    /// Load worktrees from the repo's cache, returning whether a background refresh is needed
+    /// and the cache's timestamp
+    fn load_cached_worktrees(
+        repo_root: &Path,
+        current_path: &Path,
+    ) -> (Vec<Worktree>, LoadingState, Option<u64>) {
+        if let Some(cached) = cache::load_cache(&repo_root.to_path_buf()) {
+            let is_fresh = cached.is_fresh();
+            let timestamp = cached.timestamp;
+            let age = cached.age_secs();
+            let worktrees = Self::worktrees_from_cache(cached.worktrees, repo_root, current_path);
+            if is_fresh {
+                info!(count = worktrees.len(), age, "Cache hit (fresh)");
+                (worktrees, LoadingState::Idle, Some(timestamp))
+            } else {
+                info!(
+                    count = worktrees.len(),
+                    age, "Cache hit (stale), triggering background refresh"
+                );
+                (worktrees, LoadingState::Loading, Some(timestamp))
+            }
+        } else {
+            info!("Cache miss, triggering background load");
+            (Vec::new(), LoadingState::Loading, None)
+        }
+    }
+
+    /// Switch the TUI over to a different repository, reloading its cached worktrees.
+    /// Returns true if a background refresh should be started for the new repo.
+    fn switch_repo(&mut self, path: &Path) -> Result<bool> {
+        let repo_root = Self::find_git_root(Some(path))?;
+        info!(repo = %repo_root.display(), "Switching repository");
+
+        let (worktrees, loading_state, cached_at) =
+            Self::load_cached_worktrees(&repo_root, &self.current_worktree_path);
+
+        self.save_ui_state();
+        self.repo_name = Self::repo_name_of(&repo_root);
+        self.repo_root = repo_root;
+        self.worktrees = worktrees;
+        self.loading_state = loading_state;
+        self.cached_at = cached_at;
+        self.search_query.clear();
+        self.search_cursor = 0;
+        self.apply_sort();
+        self.filtered_indices = (0..self.worktrees.len()).collect();
+        self.table_state.select(if self.worktrees.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+
+        self.repo_history.record(&self.repo_root);
+        let _ = history::save_history(&self.repo_history);
+        self.restore_ui_state();
+        self.pull_requests.clear();
+        self.pull_requests_fetched_at = 0;
+        self.merge_bases.clear();
+        self.previous_selection = None;
+        self.load_cached_pull_requests();
+        self.load_notes();
+        self.load_access();
+        self.load_stash_total();
+        self.update_main_branch();
+        self.check_integrity();
+
+        self.set_status(
+            &format!("Switched to {}", self.repo_name),
+            MessageLevel::Success,
+        );
+        Ok(self.loading_state == LoadingState::Loading)
+    }
+
     /// Convert cached worktrees back to Worktree structs
     fn worktrees_from_cache(
         cached: Vec<cache::CachedWorktree>,
-        repo_root: &PathBuf,
-        current_path: &PathBuf,
+        repo_root: &Path,
+        current_path: &Path,
     ) -> Vec<Worktree> {
         cached
             .into_iter()
             .map(|c| {
-                let is_main = c.path == *repo_root;
-                let is_current = current_path.starts_with(&c.path);
+                let path = canonical_path(&c.path);
+                let is_main = path == *repo_root;
+                let is_current = current_path.starts_with(&path);
                 Worktree {
-                    path: c.path,
+                    path,
                     branch: c.branch,
                     commit: c.commit,
                     commit_short: c.commit_short,
@@ -375,12 +1150,16 @@ impl App {
                     is_locked: c.is_locked,
                     lock_reason: c.lock_reason,
                     is_prunable: c.is_prunable,
+                    prune_reason: c.prune_reason,
                     status: WorktreeStatus {
                         modified: c.status.modified,
                         staged: c.status.staged,
                         untracked: c.status.untracked,
                         ahead: c.status.ahead,
                         behind: c.status.behind,
+                        upstream: c.status.upstream,
+                        upstream_gone: c.status.upstream_gone,
+                        ignored_paths: c.status.ignored_paths,
                     },
                     recent_commits: c
                         .recent_commits
@@ -388,9 +1167,19 @@ impl App {
                         .map(|ci| CommitInfo {
                             hash: ci.hash,
                             message: ci.message,
-                            time_ago: ci.time_ago,
+                            time: ci.time,
+                            author: ci.author,
+                            author_email: ci.author_email,
                         })
                         .collect(),
+                    pr: None,
+                    config: c.config,
+                    sparse_patterns: c.sparse_patterns,
+                    unique_commits: c.unique_commits,
+                    commit_count: c.commit_count,
+                    stash_count: c.stash_count,
+                    head_signed: c.head_signed,
+                    readme_preview: c.readme_preview,
                 }
             })
             .collect()
@@ -415,12 +1204,16 @@ impl App {
                 is_locked: w.is_locked,
                 lock_reason: w.lock_reason.clone(),
                 is_prunable: w.is_prunable,
+                prune_reason: w.prune_reason.clone(),
                 status: cache::CachedWorktreeStatus {
                     modified: w.status.modified,
                     staged: w.status.staged,
                     untracked: w.status.untracked,
                     ahead: w.status.ahead,
                     behind: w.status.behind,
+                    upstream: w.status.upstream.clone(),
+                    upstream_gone: w.status.upstream_gone,
+                    ignored_paths: w.status.ignored_paths.clone(),
                 },
                 recent_commits: w
                     .recent_commits
@@ -428,26 +1221,198 @@ impl App {
                     .map(|ci| cache::CachedCommitInfo {
                         hash: ci.hash.clone(),
                         message: ci.message.clone(),
-                        time_ago: ci.time_ago.clone(),
+                        time: ci.time,
+                        author: ci.author.clone(),
+                        author_email: ci.author_email.clone(),
                     })
                     .collect(),
+                config: w.config.clone(),
+                sparse_patterns: w.sparse_patterns,
+                unique_commits: w.unique_commits,
+                commit_count: w.commit_count,
+                stash_count: w.stash_count,
+                head_signed: w.head_signed,
+                readme_preview: w.readme_preview.clone(),
             })
             .collect();
 
-        let cache_data = cache::create_cache(self.repo_root.clone(), cached_worktrees);
+        let mut cache_data = cache::create_cache(
+            self.repo_root.clone(),
+            cached_worktrees,
+            self.current_ui_state(),
+        );
+        if self.config.show_pull_requests {
+            cache_data.pull_requests = Some(cache::CachedPullRequests {
+                timestamp: self.pull_requests_fetched_at,
+                by_branch: self
+                    .pull_requests
+                    .iter()
+                    .map(|(branch, pr)| {
+                        (
+                            branch.clone(),
+                            cache::CachedPrInfo {
+                                number: pr.number,
+                                state: pr.state.clone(),
+                            },
+                        )
+                    })
+                    .collect(),
+            });
+        }
+        cache_data.notes = self.notes.clone();
+        cache_data.access = self.access.clone();
         let _ = cache::save_cache(&cache_data);
     }
 
-    fn find_git_root() -> Result<PathBuf> {
-        // Get the common git directory with absolute path format
-        // This works correctly whether we're in the main worktree or a linked worktree
-        let output = Command::new("git")
-            .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
-            .output()
-            .context("Failed to execute git command")?;
-
+    /// Load the repo's worktree notes from its cache
+    fn load_notes(&mut self) {
+        self.notes = cache::load_cache(&self.repo_root)
+            .map(|c| c.notes)
+            .unwrap_or_default();
+    }
+
+    /// Load the repo's worktree access tracking from its cache
+    fn load_access(&mut self) {
+        self.access = cache::load_cache(&self.repo_root)
+            .map(|c| c.access)
+            .unwrap_or_default();
+    }
+
+    /// Count an access to a worktree for the frecency sort
+    fn record_access(&mut self, path: &Path) {
+        self.access.entry(path.to_path_buf()).or_default().touch();
+        let _ = cache::save_access(&self.repo_root, &self.access);
+    }
+
+    /// Note attached to a worktree, if any
+    fn note_for(&self, wt: &Worktree) -> Option<&str> {
+        self.notes.get(&wt.path).map(String::as_str)
+    }
+
+    /// Restore pull request info saved in the repo's cache
+    fn load_cached_pull_requests(&mut self) {
+        if !self.config.show_pull_requests {
+            return;
+        }
+        let Some(cached) = cache::load_cache(&self.repo_root).and_then(|c| c.pull_requests) else {
+            return;
+        };
+        self.pull_requests_fetched_at = cached.timestamp;
+        self.pull_requests = cached
+            .by_branch
+            .into_iter()
+            .map(|(branch, pr)| {
+                (
+                    branch,
+                    PrInfo {
+                        number: pr.number,
+                        state: pr.state,
+                    },
+                )
+            })
+            .collect();
+        self.apply_pull_requests();
+    }
+
+    /// Attach known pull requests to worktrees by branch name
+    fn apply_pull_requests(&mut self) {
+        for wt in &mut self.worktrees {
+            wt.pr = wt
+                .branch
+                .as_ref()
+                .and_then(|b| self.pull_requests.get(b))
+                .cloned();
+        }
+    }
+
+    /// Whether pull requests should be (re)fetched from gh
+    fn pull_requests_stale(&self) -> bool {
+        self.config.show_pull_requests
+            && cache::now_secs().saturating_sub(self.pull_requests_fetched_at) >= PR_CACHE_TTL_SECS
+    }
+
+    /// Snapshot of the UI state worth restoring, if enabled in config
+    fn current_ui_state(&self) -> Option<cache::CachedUiState> {
+        if !self.config.restore_ui_state {
+            return None;
+        }
+        Some(cache::CachedUiState {
+            search_query: self.search_query.clone(),
+            sort_order: self.sort_order.label().to_string(),
+            show_recent_commits: self.show_recent_commits,
+            group_by: self.group_by.label().to_string(),
+            path_display: self.path_display.label().to_string(),
+            mini_view: self.mini_view,
+            history_oldest_first: self.history_oldest_first,
+        })
+    }
+
+    /// Persist the UI state into the repo's cache (e.g. on quit)
+    fn save_ui_state(&self) {
+        if let Some(ui_state) = self.current_ui_state() {
+            let _ = cache::save_ui_state(&self.repo_root, ui_state);
+        }
+    }
+
+    /// Restore the UI state saved for this repo, if enabled in config
+    fn restore_ui_state(&mut self) {
+        if !self.config.restore_ui_state {
+            return;
+        }
+        let Some(ui_state) = cache::load_cache(&self.repo_root).and_then(|c| c.ui_state) else {
+            return;
+        };
+
+        if let Some(order) = SortOrder::from_label(&ui_state.sort_order) {
+            self.sort_order = order;
+        }
+        if let Some(group_by) = GroupBy::from_label(&ui_state.group_by) {
+            self.group_by = group_by;
+        }
+        if let Some(path_display) = PathDisplayMode::from_label(&ui_state.path_display) {
+            self.path_display = path_display;
+        }
+        self.apply_sort();
+        self.show_recent_commits = ui_state.show_recent_commits;
+        self.mini_view = ui_state.mini_view;
+        self.history_oldest_first = ui_state.history_oldest_first;
+
+        if !ui_state.search_query.is_empty() {
+            self.search_query = ui_state.search_query;
+            self.search_cursor = self.search_query.len();
+            self.update_search_filter();
+            // A restored search that matches nothing would just look like an empty repo.
+            // Without cached worktrees it is kept for the refresh to filter
+            if self.filtered_indices.is_empty() && !self.worktrees.is_empty() {
+                self.search_query.clear();
+                self.search_cursor = 0;
+                self.filtered_indices = (0..self.worktrees.len()).collect();
+                self.select_first();
+            }
+        }
+    }
+
+    /// Find the main repo root for `dir` (or the process working directory if None)
+    fn find_git_root(dir: Option<&Path>) -> Result<PathBuf> {
+        // Get the common git directory with absolute path format
+        // This works correctly whether we're in the main worktree or a linked worktree
+        let mut cmd = git_command();
+        if let Some(dir) = dir {
+            cmd.current_dir(dir);
+        }
+        let output = cmd
+            .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+            .output()
+            .context("Failed to execute git command")?;
+
         if !output.status.success() {
-            anyhow::bail!("Not in a git repository");
+            let dir = match dir {
+                Some(dir) => dir.to_path_buf(),
+                None => std::env::current_dir().context(
+                    "The current directory no longer exists (was its worktree removed?)",
+                )?,
+            };
+            return Err(WttError::NotARepo(dir).into());
         }
 
         let common_dir = String::from_utf8(output.stdout)
@@ -479,37 +1444,26 @@ impl App {
     }
 
     fn refresh_worktrees(&mut self) -> Result<()> {
-        let output = Command::new("git")
-            .current_dir(&self.repo_root)
-            .args(["worktree", "list", "--porcelain"])
-            .output()
-            .context("Failed to list worktrees")?;
-
-        if !output.status.success() {
-            anyhow::bail!("git worktree list failed");
-        }
-
-        let content = String::from_utf8(output.stdout)?;
-        self.worktrees =
-            Self::parse_worktree_list(&content, &self.repo_root, &self.current_worktree_path)?;
+        let start = Instant::now();
+        self.worktrees = discover_worktrees(&self.repo_root, &self.current_worktree_path)?;
         self.last_refresh = Instant::now();
+        self.update_main_branch();
 
         // Fetch additional status for each worktree
+        let stashes = get_stash_counts(&self.repo_root);
         for worktree in &mut self.worktrees {
-            if !worktree.is_bare {
-                let status = Self::get_worktree_status(&worktree.path);
-                worktree.status.staged = status.0;
-                worktree.status.modified = status.1;
-                worktree.status.untracked = status.2;
-                worktree.status.ahead = status.3;
-                worktree.status.behind = status.4;
-
-                let log_info = Self::get_worktree_log(&worktree.path, 10);
-                worktree.commit_message = log_info.0;
-                worktree.commit_time = log_info.1;
-                worktree.recent_commits = log_info.2;
-            }
+            load_worktree_details(
+                worktree,
+                Some(&self.main_branch),
+                self.show_ignored,
+                &stashes,
+            );
         }
+        self.recheck_merge_conflicts();
+        self.check_integrity();
+        self.stash_total = stashes.values().sum();
+
+        self.apply_pull_requests();
 
         // Apply sorting
         self.apply_sort();
@@ -525,248 +1479,364 @@ impl App {
         self.save_to_cache();
 
         self.loading_state = LoadingState::Idle;
-        self.set_status("Refreshed worktree list", MessageLevel::Info);
+        self.cached_at = None;
+        self.record_refresh_duration(start.elapsed());
+        self.set_status(&self.refresh_summary(), MessageLevel::Info);
         Ok(())
     }
 
-    fn apply_sort(&mut self) {
-        match self.sort_order {
-            SortOrder::Name => {
-                self.worktrees.sort_by(|a, b| {
-                    if a.is_main {
-                        return std::cmp::Ordering::Less;
-                    }
-                    if b.is_main {
-                        return std::cmp::Ordering::Greater;
-                    }
-                    a.branch.cmp(&b.branch)
-                });
-            }
-            SortOrder::Status => {
-                self.worktrees.sort_by(|a, b| {
-                    if a.is_main {
-                        return std::cmp::Ordering::Less;
-                    }
-                    if b.is_main {
-                        return std::cmp::Ordering::Greater;
-                    }
-                    let a_dirty = !a.status.is_clean();
-                    let b_dirty = !b.status.is_clean();
-                    b_dirty.cmp(&a_dirty).then_with(|| a.branch.cmp(&b.branch))
-                });
-            }
-            SortOrder::Recent => {
-                self.worktrees.sort_by(|a, b| {
-                    if a.is_main {
-                        return std::cmp::Ordering::Less;
-                    }
-                    if b.is_main {
-                        return std::cmp::Ordering::Greater;
-                    }
-                    b.commit_time.cmp(&a.commit_time)
-                });
-            }
+    /// Count the repo's stashes for the header (one cheap `git stash list`)
+    fn load_stash_total(&mut self) {
+        self.stash_total = get_stash_counts(&self.repo_root).values().sum();
+    }
+
+    fn record_refresh_duration(&mut self, elapsed: Duration) {
+        info!(
+            count = self.worktrees.len(),
+            elapsed_ms = elapsed.as_millis() as u64,
+            "Refresh finished"
+        );
+        self.last_refresh_duration = Some(elapsed);
+    }
+
+    /// Status line after a refresh, e.g. "Refreshed 24 worktrees in 1.3s"
+    fn refresh_summary(&self) -> String {
+        match self.last_refresh_duration {
+            Some(elapsed) => format!(
+                "Refreshed {} worktrees in {:.1}s",
+                self.worktrees.len(),
+                elapsed.as_secs_f64()
+            ),
+            None => "Refreshed worktree list".to_string(),
         }
     }
 
-    fn parse_worktree_list(
-        content: &str,
-        repo_root: &PathBuf,
-        current_path: &PathBuf,
-    ) -> Result<Vec<Worktree>> {
-        let mut worktrees = Vec::new();
-        let mut current: Option<Worktree> = None;
+    /// Branch checked out in the main worktree, the base for unique-commit counts
+    fn main_worktree_branch(&self) -> Option<String> {
+        self.worktrees
+            .iter()
+            .find(|wt| wt.is_main)
+            .and_then(|wt| wt.branch.clone())
+    }
 
-        for line in content.lines() {
-            if line.starts_with("worktree ") {
-                if let Some(wt) = current.take() {
-                    worktrees.push(wt);
-                }
-                let path = PathBuf::from(line.strip_prefix("worktree ").unwrap());
-                let is_main = path == *repo_root;
-                // Check if this worktree contains the current working directory
-                let is_current = current_path.starts_with(&path);
-                current = Some(Worktree {
-                    path,
-                    branch: None,
-                    commit: String::new(),
-                    commit_short: String::new(),
-                    commit_message: String::new(),
-                    commit_time: None,
-                    is_main,
-                    is_current,
-                    is_bare: false,
-                    is_detached: false,
-                    is_locked: false,
-                    lock_reason: None,
-                    is_prunable: false,
-                    status: WorktreeStatus::default(),
-                    recent_commits: Vec::new(),
-                });
-            } else if let Some(ref mut wt) = current {
-                if line.starts_with("HEAD ") {
-                    wt.commit = line.strip_prefix("HEAD ").unwrap().to_string();
-                    wt.commit_short = wt.commit.chars().take(7).collect();
-                } else if line.starts_with("branch ") {
-                    let branch = line.strip_prefix("branch ").unwrap();
-                    wt.branch = Some(
-                        branch
-                            .strip_prefix("refs/heads/")
-                            .unwrap_or(branch)
-                            .to_string(),
-                    );
-                } else if line == "bare" {
-                    wt.is_bare = true;
-                } else if line == "detached" {
-                    wt.is_detached = true;
-                } else if line == "locked" {
-                    wt.is_locked = true;
-                } else if line.starts_with("locked ") {
-                    wt.is_locked = true;
-                    wt.lock_reason = Some(line.strip_prefix("locked ").unwrap().to_string());
-                } else if line == "prunable" {
-                    wt.is_prunable = true;
-                }
-            }
+    /// Re-fetch status and commits for just the selected worktree
+    fn refresh_selected_worktree(&mut self) {
+        let Some(idx) = self.selected_index() else {
+            return;
+        };
+
+        let stashes = get_stash_counts(&self.repo_root);
+        let worktree = &mut self.worktrees[idx];
+        load_worktree_details(
+            worktree,
+            Some(&self.main_branch),
+            self.show_ignored,
+            &stashes,
+        );
+        let name = worktree
+            .branch
+            .clone()
+            .unwrap_or_else(|| worktree.commit_short.clone());
+        let path = worktree.path.clone();
+        self.recheck_merge_conflicts();
+
+        // New status or commits can move the worktree under the current sort
+        self.apply_sort();
+        if self.search_query.is_empty() {
+            self.filtered_indices = (0..self.worktrees.len()).collect();
+        } else {
+            self.update_search_filter();
         }
+        if let Some(idx) = self.worktrees.iter().position(|wt| wt.path == path) {
+            self.select_worktree(idx);
+        }
+
+        self.save_to_cache();
+        self.set_status(&format!("Refreshed {}", name), MessageLevel::Info);
+    }
 
-        if let Some(wt) = current {
-            worktrees.push(wt);
+    fn apply_sort(&mut self) {
+        // Rows move, so whatever the mouse pointed at is no longer under it
+        self.hovered_row = None;
+        if self.group_by == GroupBy::Remote {
+            self.load_branch_remotes();
         }
 
-        Ok(worktrees)
+        let order = self.sort_order;
+        let now = cache::now_secs();
+        let frecency = |wt: &Worktree| {
+            self.access
+                .get(&wt.path)
+                .map_or(0, |stats| stats.frecency(now))
+        };
+        let mut worktrees = std::mem::take(&mut self.worktrees);
+        worktrees.sort_by(|a, b| {
+            // Groups stay contiguous; within one, the main worktree comes first
+            self.group_key(a)
+                .cmp(&self.group_key(b))
+                .then_with(|| b.is_main.cmp(&a.is_main))
+                .then_with(|| match order {
+                    SortOrder::Name => std::cmp::Ordering::Equal,
+                    SortOrder::Status => {
+                        let a_dirty = !a.status.is_clean();
+                        let b_dirty = !b.status.is_clean();
+                        b_dirty.cmp(&a_dirty)
+                    }
+                    SortOrder::Recent => b.commit_time.cmp(&a.commit_time),
+                    SortOrder::Frecency => frecency(b).cmp(&frecency(a)),
+                })
+                // Deterministic tiebreak so equal rows don't reshuffle between refreshes
+                .then_with(|| a.branch.cmp(&b.branch))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        self.worktrees = worktrees;
     }
 
-    fn get_worktree_status(path: &PathBuf) -> (usize, usize, usize, usize, usize) {
-        let mut staged = 0;
-        let mut modified = 0;
-        let mut untracked = 0;
-        let mut ahead = 0;
-        let mut behind = 0;
+    /// Group rank and header label of a worktree under the active grouping
+    fn group_key(&self, wt: &Worktree) -> (u8, String) {
+        match self.group_by {
+            GroupBy::None => (0, String::new()),
+            GroupBy::Status if wt.status.is_clean() => (1, "Clean".into()),
+            GroupBy::Status => (0, "Dirty".into()),
+            GroupBy::Merged => match wt.unique_commits {
+                _ if wt.is_main => (0, "Main".into()),
+                Some(0) => (2, "Merged".into()),
+                Some(_) => (1, "Unmerged".into()),
+                None => (3, "Other".into()),
+            },
+            GroupBy::Remote => match wt.branch.as_ref().and_then(|b| self.branch_remotes.get(b)) {
+                Some(remote) => (0, remote.clone()),
+                None => (1, "No remote".into()),
+            },
+        }
+    }
 
-        if let Ok(output) = Command::new("git")
-            .current_dir(path)
-            .args(["status", "--porcelain=v1", "--branch"])
+    /// Read each local branch's upstream remote (`branch.<name>.remote`)
+    fn load_branch_remotes(&mut self) {
+        let Ok(output) = git_command()
+            .current_dir(&self.repo_root)
+            .args(["config", "--get-regexp", r"^branch\..*\.remote$"])
             .output()
-        {
-            if output.status.success() {
-                let content = String::from_utf8_lossy(&output.stdout);
-                for line in content.lines() {
-                    if line.starts_with("##") {
-                        // Parse ahead/behind from branch line: ## main...origin/main [ahead 1, behind 2]
-                        if let Some(pos) = line.find('[') {
-                            let info = &line[pos + 1..line.len() - 1];
-                            for part in info.split(',') {
-                                let part = part.trim();
-                                if part.starts_with("ahead ") {
-                                    ahead = part["ahead ".len()..].parse().unwrap_or(0);
-                                } else if part.starts_with("behind ") {
-                                    behind = part["behind ".len()..].parse().unwrap_or(0);
-                                }
-                            }
-                        }
-                        continue;
-                    }
-                    if line.len() < 2 {
-                        continue;
-                    }
-                    let index = line.chars().next().unwrap();
-                    let worktree = line.chars().nth(1).unwrap();
-                    if index != ' ' && index != '?' {
-                        staged += 1;
-                    }
-                    if worktree == 'M' || worktree == 'D' {
-                        modified += 1;
-                    }
-                    if index == '?' {
-                        untracked += 1;
-                    }
+        else {
+            return;
+        };
+        self.branch_remotes = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (key, remote) = line.split_once(' ')?;
+                let branch = key.strip_prefix("branch.")?.strip_suffix(".remote")?;
+                Some((branch.to_string(), remote.to_string()))
+            })
+            .collect();
+    }
+
+    /// Layout of the worktree table, with a header before each group when grouping
+    fn table_rows(&self) -> Vec<TableRow> {
+        let mut rows = Vec::new();
+        let mut current: Option<String> = None;
+        for (pos, &idx) in self.filtered_indices.iter().enumerate() {
+            if self.group_by != GroupBy::None {
+                let label = self.group_key(&self.worktrees[idx]).1;
+                if current.as_ref() != Some(&label) {
+                    let count = self.filtered_indices[pos..]
+                        .iter()
+                        .take_while(|&&i| self.group_key(&self.worktrees[i]).1 == label)
+                        .count();
+                    rows.push(TableRow::Header(label.clone(), count));
+                    current = Some(label);
                 }
             }
+            rows.push(TableRow::Worktree(pos));
         }
-        (staged, modified, untracked, ahead, behind)
+        rows
     }
 
-    fn get_worktree_log(path: &PathBuf, count: usize) -> (String, Option<i64>, Vec<CommitInfo>) {
-        let output = Command::new("git")
-            .current_dir(path)
-            .args(["log", &format!("-{}", count), "--format=%h|%s|%ct|%cr"])
-            .output();
+    /// Switch to the next grouping, keeping the selected worktree selected
+    fn cycle_group(&mut self) {
+        self.set_group_by(self.group_by.next());
+    }
+
+    /// Group by `group_by`, keeping the selected worktree selected
+    fn set_group_by(&mut self, group_by: GroupBy) {
+        self.group_by = group_by;
+        let selected = self.selected_worktree().map(|wt| wt.path.clone());
+        self.apply_sort();
+        if self.search_query.is_empty() {
+            self.filtered_indices = (0..self.worktrees.len()).collect();
+        } else {
+            self.update_search_filter();
+        }
+        if let Some(idx) = selected.and_then(|p| self.worktrees.iter().position(|wt| wt.path == p))
+        {
+            self.select_worktree(idx);
+        }
+        let message = match self.group_by {
+            GroupBy::None => "Grouping off".to_string(),
+            group_by => format!("Grouped by {}", group_by.label()),
+        };
+        self.set_status(&message, MessageLevel::Info);
+    }
 
-        let mut current_msg = String::new();
-        let mut current_time = None;
-        let mut recent = Vec::new();
+    /// Switch to the next path display mode
+    fn cycle_path_display(&mut self) {
+        self.path_display = self.path_display.next();
+        self.set_status(
+            &format!("Showing {} paths", self.path_display.label()),
+            MessageLevel::Info,
+        );
+    }
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let content = String::from_utf8_lossy(&output.stdout);
-                for (i, line) in content.lines().enumerate() {
-                    let parts: Vec<&str> = line.split('|').collect();
-                    if parts.len() >= 4 {
-                        if i == 0 {
-                            current_msg = parts[1].chars().take(60).collect();
-                            current_time = parts[2].parse().ok();
-                        }
-                        recent.push(CommitInfo {
-                            hash: parts[0].to_string(),
-                            message: parts[1].chars().take(50).collect(),
-                            time_ago: parts[3].to_string(),
-                        });
-                    }
-                }
-            }
+    /// A worktree path in the current display mode; paths outside the home directory
+    /// or the repo's parent stay absolute
+    fn format_path(&self, path: &Path) -> PathBuf {
+        match self.path_display {
+            PathDisplayMode::Absolute => path.to_path_buf(),
+            PathDisplayMode::Home => dirs::home_dir()
+                .and_then(|home| {
+                    path.strip_prefix(home)
+                        .ok()
+                        .map(|rest| Path::new("~").join(rest))
+                })
+                .unwrap_or_else(|| path.to_path_buf()),
+            PathDisplayMode::Relative => self
+                .repo_root
+                .parent()
+                .and_then(|parent| path.strip_prefix(parent).ok())
+                .filter(|rest| !rest.as_os_str().is_empty())
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| path.to_path_buf()),
         }
-        (current_msg, current_time, recent)
     }
 
     fn refresh_branches(&mut self) -> Result<()> {
         let mut branches = Vec::new();
 
-        let output = Command::new("git")
+        // Name last, since `|` is allowed in branch names
+        let output = git_command()
             .current_dir(&self.repo_root)
-            .args(["branch", "--format=%(refname:short)|%(HEAD)"])
+            .args([
+                "branch",
+                "--format=%(HEAD)|%(objectname)|%(upstream:short)|%(refname:short)",
+            ])
             .output()?;
 
         if output.status.success() {
             for line in String::from_utf8_lossy(&output.stdout).lines() {
-                let parts: Vec<&str> = line.split('|').collect();
-                if parts.len() >= 2 {
+                let parts: Vec<&str> = line.splitn(4, '|').collect();
+                if parts.len() == 4 {
                     branches.push(Branch {
-                        name: parts[0].to_string(),
+                        name: parts[3].to_string(),
                         is_remote: false,
-                        is_current: parts[1] == "*",
+                        is_current: parts[0] == "*",
+                        commit: parts[1].to_string(),
+                        upstream: Some(parts[2].to_string()).filter(|u| !u.is_empty()),
+                        tracked: false,
                     });
                 }
             }
         }
 
-        let output = Command::new("git")
+        self.available_branches = branches;
+        self.sort_branches();
+        self.remote_branches_loaded = false;
+        if self.branch_filter != BranchFilter::Local {
+            self.load_remote_branches()?;
+        }
+        Ok(())
+    }
+
+    /// Append remote branches to `available_branches`. Deferred until the picker
+    /// shows remotes, since repos can have thousands of remote refs.
+    fn load_remote_branches(&mut self) -> Result<()> {
+        if self.remote_branches_loaded {
+            return Ok(());
+        }
+
+        let output = git_command()
             .current_dir(&self.repo_root)
-            .args(["branch", "-r", "--format=%(refname:short)"])
+            .args(["branch", "-r", "--format=%(objectname)|%(refname:short)"])
             .output()?;
 
         if output.status.success() {
             for line in String::from_utf8_lossy(&output.stdout).lines() {
-                let name = line.trim();
-                if !name.contains("HEAD") {
-                    branches.push(Branch {
-                        name: name.to_string(),
-                        is_remote: true,
-                        is_current: false,
-                    });
+                let Some((commit, name)) = line.trim().split_once('|') else {
+                    continue;
+                };
+                if name.contains("HEAD") {
+                    continue;
+                }
+                // A remote branch that is some local branch's upstream, at the same
+                // commit, would only look like a duplicate of it
+                let tracking = self.available_branches.iter_mut().find(|b| {
+                    !b.is_remote && b.upstream.as_deref() == Some(name) && b.commit == commit
+                });
+                if let Some(local) = tracking {
+                    local.tracked = true;
+                    continue;
                 }
+                self.available_branches.push(Branch {
+                    name: name.to_string(),
+                    is_remote: true,
+                    is_current: false,
+                    commit: commit.to_string(),
+                    upstream: None,
+                    tracked: false,
+                });
             }
         }
 
-        self.available_branches = branches;
+        self.remote_branches_loaded = true;
+        self.sort_branches();
         Ok(())
     }
 
-    fn selected_worktree(&self) -> Option<&Worktree> {
+    /// The current branch first, then local branches, then remote ones, each alphabetically
+    fn sort_branches(&mut self) {
+        self.available_branches.sort_by(|a, b| {
+            (!a.is_current, a.is_remote, &a.name).cmp(&(!b.is_current, b.is_remote, &b.name))
+        });
+    }
+
+    /// Where the app was launched from, relative to the current worktree's root.
+    /// None for other worktrees or when launched from the root itself.
+    fn current_subpath(&self, worktree: &Worktree) -> Option<PathBuf> {
+        if !worktree.is_current {
+            return None;
+        }
+        self.current_worktree_path
+            .strip_prefix(&worktree.path)
+            .ok()
+            .filter(|sub| !sub.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+    }
+
+    /// Branches shown in the base-branch picker under the current filter and query
+    fn visible_branches(&self) -> Vec<&Branch> {
+        let query = self.branch_query.to_lowercase();
+        self.available_branches
+            .iter()
+            .filter(|b| self.branch_filter.matches(b))
+            .filter(|b| query.is_empty() || b.name.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    fn clear_branch_query(&mut self) {
+        self.branch_query.clear();
+        self.branch_query_cursor = 0;
+        self.branch_query_focused = false;
+    }
+
+    /// Index into `worktrees` of the selected row
+    fn selected_index(&self) -> Option<usize> {
         self.table_state
             .selected()
             .and_then(|i| self.filtered_indices.get(i))
-            .and_then(|&idx| self.worktrees.get(idx))
+            .copied()
+    }
+
+    fn selected_worktree(&self) -> Option<&Worktree> {
+        self.selected_index()
+            .and_then(|idx| self.worktrees.get(idx))
     }
 
     fn set_status(&mut self, text: &str, level: MessageLevel) {
@@ -778,10 +1848,116 @@ impl App {
         // If it's an error, also show it in a popup
         if level == MessageLevel::Error {
             self.error_message = text.to_string();
+            self.error_action = None;
+            self.error_context = ErrorContext {
+                command: None,
+                worktree: self.selected_worktree().map(|wt| wt.path.clone()),
+            };
             self.mode = AppMode::Error;
         }
     }
 
+    /// Report a failed git command, keeping its command line for the diagnostic copy
+    fn report_git_failure(&mut self, message: &str, args: &[&str], dir: &Path) {
+        self.op_failed = true;
+        self.set_status(message, MessageLevel::Error);
+        self.error_context = ErrorContext {
+            command: Some(format!("git {}", args.join(" "))),
+            worktree: Some(dir.to_path_buf()),
+        };
+    }
+
+    /// Error report for pasting into a bug report: the error plus where and what failed
+    fn error_diagnostics(&self) -> String {
+        let mut report = format!(
+            "worktree-tui {} ({}/{})\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        let git_version = git_command()
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+        if let Some(version) = git_version {
+            report.push_str(&format!("Git: {}\n", version));
+        }
+        report.push_str(&format!("Error: {}\n", self.error_message));
+        if let Some(command) = &self.error_context.command {
+            report.push_str(&format!("Command: {}\n", command));
+        }
+        if let Some(worktree) = &self.error_context.worktree {
+            report.push_str(&format!("Worktree: {}\n", worktree.display()));
+        }
+        report.push_str(&format!("Repository: {}\n", self.repo_root.display()));
+        report
+    }
+
+    /// Whether mutating actions are refused, from `--read-only` or the config
+    fn is_read_only(&self) -> bool {
+        self.read_only || self.config.read_only
+    }
+
+    /// Whether `action` would do anything for the current selection
+    fn action_available(&self, action: Action) -> bool {
+        if action != Action::CdInto && self.is_read_only() {
+            return false;
+        }
+        // Everything but creating needs a selected worktree
+        let wt = self.selected_worktree();
+        match action {
+            Action::Create => !self.repo_is_empty(),
+            Action::CdInto => wt.is_some_and(|wt| wt.path.exists()),
+            Action::Delete => wt.is_some_and(|wt| !wt.is_main),
+            // The main worktree is where the others get merged into
+            Action::Merge => wt.is_some_and(|wt| {
+                !wt.is_main && wt.branch.is_some() && !wt.branch_is_lossy() && !wt.is_unborn()
+            }),
+            Action::PullPush => {
+                wt.is_some_and(|wt| wt.branch.is_some() && wt.path.exists() && !wt.is_unborn())
+            }
+        }
+    }
+
+    /// The repository has no commits yet, so there is nothing to base a worktree on
+    fn repo_is_empty(&self) -> bool {
+        self.worktrees.iter().any(|wt| wt.is_main && wt.is_unborn())
+    }
+
+    /// Show an error in the popup, offering a fix for the kinds we can act on
+    fn report_error(&mut self, err: &anyhow::Error) {
+        self.op_failed = true;
+        self.set_status(&format!("{:#}", err), MessageLevel::Error);
+        self.error_action = match err.downcast_ref::<WttError>() {
+            Some(WttError::WorktreeLocked { .. }) => Some(ErrorAction::UnlockAndDelete),
+            Some(WttError::NotARepo(path)) => Some(ErrorAction::InitRepo(path.clone())),
+            _ => None,
+        };
+        if let Some(WttError::GitCommandFailed { command, .. }) = err.downcast_ref::<WttError>() {
+            self.error_context.command = Some(format!("git {}", command));
+        }
+    }
+
+    /// Create a repository with `git init` and switch to it.
+    /// Returns true if a background refresh should be started.
+    fn init_repo(&mut self, path: &Path) -> Result<bool> {
+        if self.is_read_only() {
+            self.set_status("Read-only mode", MessageLevel::Warning);
+            return Ok(false);
+        }
+        let output = git_command()
+            .current_dir(path)
+            .arg("init")
+            .output()
+            .context("Failed to run git init")?;
+        if !output.status.success() {
+            return Err(WttError::git_failed("init", &output).into());
+        }
+        self.switch_repo(path)
+    }
+
     fn clear_old_status(&mut self) {
         if let Some(ref msg) = self.status_message {
             if msg.timestamp.elapsed() > Duration::from_secs(5) {
@@ -790,48 +1966,207 @@ impl App {
         }
     }
 
-    fn move_selection(&mut self, delta: i32) {
-        let len = self.filtered_indices.len();
-        if len == 0 {
-            return;
+    /// Display position of the worktree row at a screen cell, if there is one
+    fn worktree_row_at(&self, column: u16, row: u16) -> Option<usize> {
+        let area = self.list_area?;
+        let (top, chrome) = self.list_chrome();
+        if column < area.x
+            || column >= area.x + area.width
+            || row < area.y + top
+            || row >= (area.y + area.height).saturating_sub(chrome - top)
+        {
+            return None;
         }
-
-        let current = self.table_state.selected().unwrap_or(0);
-        let new = if delta > 0 {
-            (current + delta as usize).min(len - 1)
+        let offset = if self.group_by == GroupBy::None {
+            self.table_state.offset()
         } else {
-            current.saturating_sub((-delta) as usize)
+            self.group_table_state.offset()
         };
-        self.table_state.select(Some(new));
+        match self
+            .table_rows()
+            .get(offset + (row - area.y - top) as usize)
+        {
+            Some(TableRow::Worktree(pos)) => Some(*pos),
+            _ => None,
+        }
     }
 
-    fn select_first(&mut self) {
-        if !self.filtered_indices.is_empty() {
-            self.table_state.select(Some(0));
+    /// Lines of the worktree list taken by chrome: (above the rows, in total).
+    /// Borders, column header and its margin, or nothing in the mini view.
+    fn list_chrome(&self) -> (u16, u16) {
+        if self.mini_view {
+            (0, 0)
+        } else {
+            (3, 4)
         }
     }
 
-    fn select_last(&mut self) {
-        if !self.filtered_indices.is_empty() {
-            self.table_state
-                .select(Some(self.filtered_indices.len() - 1));
+    /// Track the row under the mouse, restarting the hover delay when it changes
+    fn set_hovered_row(&mut self, row: Option<usize>) {
+        if row != self.hovered_row {
+            self.hovered_row = row;
+            self.hovered_since = Instant::now();
         }
     }
 
-    fn update_search_filter(&mut self) {
-        let query = self.search_query.to_lowercase();
-        self.filtered_indices = self
-            .worktrees
-            .iter()
-            .enumerate()
-            .filter(|(_, wt)| {
-                wt.path.to_string_lossy().to_lowercase().contains(&query)
-                    || wt
-                        .branch
-                        .as_ref()
-                        .map(|b| b.to_lowercase().contains(&query))
-                        .unwrap_or(false)
-                    || wt.commit_message.to_lowercase().contains(&query)
+    /// One-line summary of the hovered worktree: branch, status and last commit.
+    /// Status messages take precedence until they expire
+    fn hover_summary(&self) -> Option<String> {
+        if self.status_message.is_some() || self.hovered_since.elapsed() < HOVER_DELAY {
+            return None;
+        }
+        let idx = *self.filtered_indices.get(self.hovered_row?)?;
+        let wt = self.worktrees.get(idx)?;
+        let branch = wt.branch.as_deref().unwrap_or(&wt.commit_short);
+        if wt.is_unborn() {
+            return Some(format!(
+                "{} · {} · (no commits yet)",
+                branch,
+                wt.status.summary()
+            ));
+        }
+        let age = wt
+            .commit_time
+            .map(|time| {
+                format!(
+                    " ({})",
+                    format_relative_time(time, cache::now_secs() as i64)
+                )
+            })
+            .unwrap_or_default();
+        Some(format!(
+            "{} · {} · {} {}{}",
+            branch,
+            wt.status.summary(),
+            wt.commit_short,
+            wt.commit_message,
+            age
+        ))
+    }
+
+    /// Label every visible row so it can be jumped to with a letter or two
+    fn start_hint_mode(&mut self) {
+        let Some(area) = self.list_area else {
+            return;
+        };
+        let offset = if self.group_by == GroupBy::None {
+            self.table_state.offset()
+        } else {
+            self.group_table_state.offset()
+        };
+        let visible = area.height.saturating_sub(self.list_chrome().1) as usize;
+        let positions: Vec<usize> = self
+            .table_rows()
+            .into_iter()
+            .skip(offset)
+            .take(visible)
+            .filter_map(|row| match row {
+                TableRow::Worktree(pos) => Some(pos),
+                TableRow::Header(..) => None,
+            })
+            .collect();
+        if positions.is_empty() {
+            return;
+        }
+        self.hint_labels = hint_labels(positions.len())
+            .into_iter()
+            .zip(positions)
+            .collect();
+        self.hint_input.clear();
+        self.mode = AppMode::Hint;
+    }
+
+    fn move_selection(&mut self, delta: i32) {
+        let len = self.filtered_indices.len();
+        if len == 0 {
+            return;
+        }
+
+        let current = self.table_state.selected().unwrap_or(0);
+        let new = if delta > 0 {
+            (current + delta as usize).min(len - 1)
+        } else {
+            current.saturating_sub((-delta) as usize)
+        };
+        self.table_state.select(Some(new));
+    }
+
+    fn select_first(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.table_state.select(Some(0));
+        }
+    }
+
+    fn select_last(&mut self) {
+        if !self.filtered_indices.is_empty() {
+            self.table_state
+                .select(Some(self.filtered_indices.len() - 1));
+        }
+    }
+
+    /// Feed a digit into the quick-jump buffer and select the resulting row.
+    /// Digits typed within `JUMP_TIMEOUT` of each other are combined, so `1` `2`
+    /// jumps to row 12. If the combined number is out of range, the digit starts
+    /// a new number instead.
+    fn push_jump_digit(&mut self, digit: char) {
+        if self.last_jump_key.elapsed() > JUMP_TIMEOUT {
+            self.jump_buffer.clear();
+        }
+        self.last_jump_key = Instant::now();
+
+        self.jump_buffer.push(digit);
+        let len = self.filtered_indices.len();
+        let in_range = |buf: &str| matches!(buf.parse::<usize>(), Ok(n) if n >= 1 && n <= len);
+
+        if !in_range(&self.jump_buffer) {
+            self.jump_buffer = digit.to_string();
+            if !in_range(&self.jump_buffer) {
+                self.jump_buffer.clear();
+                return;
+            }
+        }
+
+        if let Ok(n) = self.jump_buffer.parse::<usize>() {
+            self.table_state.select(Some(n - 1));
+        }
+    }
+
+    fn update_search_filter(&mut self) {
+        self.hovered_row = None;
+        let query = self.search_query.to_lowercase();
+        // `is:gone` keeps worktrees whose upstream was deleted; the rest is matched as text
+        let gone_only = query.split_whitespace().any(|term| term == GONE_FILTER);
+        let query = if gone_only {
+            query
+                .split_whitespace()
+                .filter(|term| *term != GONE_FILTER)
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else {
+            query
+        };
+        self.filtered_indices = self
+            .worktrees
+            .iter()
+            .enumerate()
+            .filter(|(_, wt)| !gone_only || wt.status.upstream_gone)
+            .filter(|(_, wt)| {
+                wt.path.to_string_lossy().to_lowercase().contains(&query)
+                    || wt
+                        .branch
+                        .as_ref()
+                        .map(|b| b.to_lowercase().contains(&query))
+                        .unwrap_or(false)
+                    || wt.commit_message.to_lowercase().contains(&query)
+                    || (self.search_scope == SearchScope::History
+                        && wt
+                            .recent_commits
+                            .iter()
+                            .any(|c| c.message.to_lowercase().contains(&query)))
+                    || self
+                        .notes
+                        .get(&wt.path)
+                        .is_some_and(|n| n.to_lowercase().contains(&query))
             })
             .map(|(i, _)| i)
             .collect();
@@ -849,24 +2184,197 @@ impl App {
     // ===== Actions =====
 
     fn get_worktrees_dir(&self) -> PathBuf {
+        if let Some(dir) = self.config.worktrees_dir.as_deref() {
+            let dir = dir.replace("{repo}", &self.repo_name);
+            let dir = match dir.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()
+                    .map(|home| home.join(rest))
+                    .unwrap_or_else(|| PathBuf::from(&dir)),
+                None => PathBuf::from(&dir),
+            };
+            return self.repo_root.join(dir);
+        }
         // repo_root is now guaranteed to be absolute from find_git_root()
         let parent = self.repo_root.parent().unwrap_or(&self.repo_root);
         parent.join(format!("{}-worktrees", self.repo_name))
     }
 
-    fn create_worktree(&mut self) -> Result<()> {
+    /// When the worktrees directory sits inside the main worktree and nothing ignores
+    /// it yet, ask to exclude it so it doesn't show up as untracked there
+    fn offer_worktrees_dir_exclude(&mut self) {
+        if self.exclude_declined {
+            return;
+        }
+        let worktrees_dir = self.get_worktrees_dir();
+        let Ok(relative) = worktrees_dir.strip_prefix(&self.repo_root) else {
+            return;
+        };
+        if relative.as_os_str().is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            return;
+        }
+        let ignored = git_command()
+            .current_dir(&self.repo_root)
+            .args(["check-ignore", "-q"])
+            .arg(relative)
+            .status()
+            .is_ok_and(|status| status.success());
+        if ignored {
+            return;
+        }
+        self.exclude_pattern = format!("/{}/", relative.to_string_lossy().replace('\\', "/"));
+        self.mode = AppMode::ExcludeConfirm;
+    }
+
+    /// Append `exclude_pattern` to the repo's `.git/info/exclude`
+    fn exclude_worktrees_dir(&mut self) {
+        let pattern = std::mem::take(&mut self.exclude_pattern);
+        let result = git_command()
+            .current_dir(&self.repo_root)
+            .args([
+                "rev-parse",
+                "--path-format=absolute",
+                "--git-path",
+                "info/exclude",
+            ])
+            .output()
+            .context("Failed to locate .git/info/exclude")
+            .and_then(|output| {
+                if !output.status.success() {
+                    return Err(WttError::git_failed("rev-parse --git-path", &output).into());
+                }
+                let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let mut contents = std::fs::read_to_string(&path).unwrap_or_default();
+                if !contents.is_empty() && !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                contents.push_str(&format!("# Worktrees created by wtt\n{}\n", pattern));
+                std::fs::write(&path, contents)?;
+                Ok(())
+            });
+        match result {
+            Ok(()) => self.set_status(
+                &format!("Added {} to .git/info/exclude", pattern),
+                MessageLevel::Success,
+            ),
+            Err(e) => self.set_status(
+                &format!("Failed to update .git/info/exclude: {}", e),
+                MessageLevel::Error,
+            ),
+        }
+    }
+
+    /// Why new worktrees can't be created in the worktrees directory, if they can't.
+    /// Probes with a real file, since permission bits alone miss ownership and ACLs.
+    fn check_worktrees_dir_writable(&self) -> Option<String> {
+        let worktrees_dir = self.get_worktrees_dir();
+        // The directory is created on first use, so its parent has to be writable then
+        let dir = if worktrees_dir.exists() {
+            worktrees_dir
+        } else {
+            worktrees_dir.parent()?.to_path_buf()
+        };
+        let probe = dir.join(format!(".wtt-write-test-{}", std::process::id()));
+        match std::fs::File::create(&probe) {
+            Ok(_) => {
+                let _ = std::fs::remove_file(&probe);
+                None
+            }
+            Err(e) => Some(format!("{} is not writable: {}", dir.display(), e)),
+        }
+    }
+
+    /// Where the create dialog's input would put the new worktree, if that path is taken
+    fn create_target_conflict(&self) -> Option<PathBuf> {
         let name = self.create_input.trim();
+        // Detached worktrees are named after the resolved commit, not the input
+        if name.is_empty() || self.create_mode == CreateMode::Detached {
+            return None;
+        }
+        let path = self.get_worktrees_dir().join(name);
+        path.exists().then_some(path)
+    }
+
+    /// Resolve a commit-ish to its full commit hash
+    fn resolve_commit(&self, commitish: &str) -> Option<String> {
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", commitish),
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn create_worktree(&mut self) -> Result<()> {
+        let name = self.create_input.trim().to_string();
         if name.is_empty() {
             self.set_status("Worktree name cannot be empty", MessageLevel::Error);
             return Ok(());
         }
 
         // When checking out existing branch, a branch must be selected
-        if self.create_checkout_existing && self.create_from_branch.is_none() {
+        if self.create_mode == CreateMode::CheckoutExisting && self.create_from_branch.is_none() {
             self.set_status("Select a branch to checkout (Tab)", MessageLevel::Error);
             return Ok(());
         }
 
+        // Detached worktrees take a commit-ish as input and are named after the commit
+        let detached_commit = if self.create_mode == CreateMode::Detached {
+            match self.resolve_commit(&name) {
+                Some(commit) => Some(commit),
+                None => {
+                    self.set_status(
+                        &format!("'{}' does not resolve to a commit", name),
+                        MessageLevel::Error,
+                    );
+                    return Ok(());
+                }
+            }
+        } else {
+            None
+        };
+        let name = match detached_commit {
+            Some(ref commit) => format!("detached-{}", &commit[..7.min(commit.len())]),
+            None => name,
+        };
+
+        // `worktree add -b` refuses an existing branch; offer to check it out instead
+        let branch_exists = self
+            .available_branches
+            .iter()
+            .any(|b| !b.is_remote && b.name == name);
+        if self.create_mode == CreateMode::NewBranch && branch_exists {
+            let checked_out = self
+                .worktrees
+                .iter()
+                .find(|wt| wt.branch.as_deref() == Some(name.as_str()));
+            if let Some(wt) = checked_out {
+                let message = format!(
+                    "Branch '{}' already exists and is checked out at {}",
+                    name,
+                    wt.path.display()
+                );
+                self.set_status(&message, MessageLevel::Error);
+            } else {
+                self.mode = AppMode::BranchExists;
+            }
+            return Ok(());
+        }
+
         // Create worktrees in PROJECT-worktrees/ directory
         let worktrees_dir = self.get_worktrees_dir();
 
@@ -881,25 +2389,44 @@ impl App {
             }
         }
 
-        let worktree_path = worktrees_dir.join(name);
+        let worktree_path = worktrees_dir.join(&name);
+        let from_branch = self.create_from_branch.clone();
+        let Some(worktree_path_str) = worktree_path.to_str() else {
+            self.set_status(
+                &format!(
+                    "Worktree path is not valid UTF-8: {}",
+                    worktree_path.display()
+                ),
+                MessageLevel::Error,
+            );
+            return Ok(());
+        };
 
         let mut args = vec!["worktree", "add"];
+        if self.create_lock {
+            args.extend(["--lock", "--reason", "locked on creation"]);
+        }
 
-        if self.create_checkout_existing {
+        if let Some(ref commit) = detached_commit {
+            // Detached HEAD: git worktree add --detach <path> <commit>
+            args.push("--detach");
+            args.push(worktree_path_str);
+            args.push(commit);
+        } else if self.create_mode == CreateMode::CheckoutExisting {
             // Checkout existing branch: git worktree add <path> <existing-branch>
-            args.push(worktree_path.to_str().unwrap());
-            args.push(self.create_from_branch.as_ref().unwrap());
+            args.push(worktree_path_str);
+            args.push(from_branch.as_deref().unwrap());
         } else {
             // Create new branch: git worktree add -b <new-branch-name> <path> [<base-branch>]
             args.push("-b");
-            args.push(name);
-            args.push(worktree_path.to_str().unwrap());
-            if let Some(ref branch) = self.create_from_branch {
+            args.push(&name);
+            args.push(worktree_path_str);
+            if let Some(ref branch) = from_branch {
                 args.push(branch);
             }
         }
 
-        let output = Command::new("git")
+        let output = git_command()
             .current_dir(&self.repo_root)
             .args(&args)
             .output()?;
@@ -915,28 +2442,159 @@ impl App {
             self.create_input.clear();
             self.create_cursor = 0;
             self.create_from_branch = None;
-            self.create_checkout_existing = false;
+            self.create_mode = CreateMode::NewBranch;
+            self.create_lock = false;
             // get index of newly created worktree and select it
             // Assumes worktree was created successfully
-            if let Some(pos) = self
-                .worktrees
-                .iter()
-                .position(|wt| wt.path == worktree_path)
-            {
+            let created_path = canonical_path(&worktree_path);
+            if let Some(pos) = self.worktrees.iter().position(|wt| wt.path == created_path) {
                 if let Some(filtered_pos) = self.filtered_indices.iter().position(|&idx| idx == pos)
                 {
                     self.table_state.select(Some(filtered_pos));
                 }
             }
+            let template = self
+                .create_template
+                .take()
+                .and_then(|idx| self.config.templates.get(idx).cloned());
+            let template =
+                template.filter(|t| !t.copy_files.is_empty() || !t.post_create.is_empty());
+            if self.config.cd_after_create {
+                // Queued ops would never run once we quit, so set up the worktree first
+                if let Some(template) = template {
+                    self.run_post_create(&created_path, &template)?;
+                }
+                self.record_access(&created_path);
+                self.cd_path = Some(created_path);
+                // Quitting would swallow the offer, so hold off until it's answered
+                self.offer_worktrees_dir_exclude();
+                if self.mode == AppMode::ExcludeConfirm {
+                    self.quit_after_exclude = true;
+                } else {
+                    self.should_quit = true;
+                }
+            } else {
+                if let Some(template) = template {
+                    self.queue_op(PendingOp::PostCreate(created_path, template));
+                }
+                self.offer_worktrees_dir_exclude();
+            }
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
-            self.set_status(&format!("Failed: {}", error.trim()), MessageLevel::Error);
+            let repo_root = self.repo_root.clone();
+            self.report_git_failure(&format!("Failed: {}", error.trim()), &args, &repo_root);
             // Don't reset mode - keep error dialog open
         }
         Ok(())
     }
 
-    fn delete_worktree(&mut self) -> Result<()> {
+    /// Switch the create dialog to the next template (or back to none), swapping the
+    /// previous template's name prefix and base branch for the new one's
+    fn cycle_create_template(&mut self) {
+        if self.config.templates.is_empty() {
+            self.set_status(
+                "No templates configured (see `templates` in the config)",
+                MessageLevel::Warning,
+            );
+            return;
+        }
+
+        if let Some(previous) = self
+            .create_template
+            .and_then(|idx| self.config.templates.get(idx))
+        {
+            if let Some(rest) = self.create_input.strip_prefix(&previous.branch_prefix) {
+                self.create_input = rest.to_string();
+            }
+            if previous.base_branch.is_some() {
+                self.create_from_branch = None;
+            }
+        }
+
+        self.create_template = match self.create_template {
+            None => Some(0),
+            Some(idx) if idx + 1 < self.config.templates.len() => Some(idx + 1),
+            Some(_) => None,
+        };
+        if let Some(template) = self
+            .create_template
+            .and_then(|idx| self.config.templates.get(idx))
+        {
+            self.create_input = format!("{}{}", template.branch_prefix, self.create_input);
+            if template.base_branch.is_some() {
+                self.create_from_branch = template.base_branch.clone();
+            }
+            self.create_mode = CreateMode::NewBranch;
+        }
+        self.create_cursor = self.create_input.len();
+    }
+
+    /// Apply a creation template to a new worktree: copy its files over from the
+    /// main worktree, then run its commands there, stopping at the first failure
+    fn run_post_create(&mut self, path: &Path, template: &config::CreateTemplate) -> Result<()> {
+        let mut problems = Vec::new();
+        for file in &template.copy_files {
+            let source = self.repo_root.join(file);
+            let target = path.join(file);
+            if !source.is_file() {
+                problems.push(format!("{} not found in the main worktree", file));
+                continue;
+            }
+            let copied = match target.parent() {
+                Some(parent) => std::fs::create_dir_all(parent),
+                None => Ok(()),
+            }
+            .and_then(|_| std::fs::copy(&source, &target));
+            if let Err(e) = copied {
+                problems.push(format!("copying {}: {}", file, e));
+            }
+        }
+
+        let mut failed_command = None;
+        for command in &template.post_create {
+            let output = Command::new("sh")
+                .args(["-c", command])
+                .current_dir(path)
+                .output()?;
+            if !output.status.success() {
+                let error = String::from_utf8_lossy(&output.stderr);
+                let last_line = error.trim().lines().last().unwrap_or_default().to_string();
+                failed_command = Some((command.clone(), last_line));
+                break;
+            }
+        }
+
+        self.refresh_worktrees()?;
+        if let Some((command, error)) = failed_command {
+            self.op_failed = true;
+            self.set_status(
+                &format!(
+                    "Template {}: `{}` failed: {}",
+                    template.name, command, error
+                ),
+                MessageLevel::Error,
+            );
+            self.error_context = ErrorContext {
+                command: Some(command),
+                worktree: Some(path.to_path_buf()),
+            };
+        } else if !problems.is_empty() {
+            self.set_status(
+                &format!("Template {}: {}", template.name, problems.join(", ")),
+                MessageLevel::Warning,
+            );
+        } else {
+            self.set_status(
+                &format!("Applied template {}", template.name),
+                MessageLevel::Success,
+            );
+        }
+        Ok(())
+    }
+
+    /// Remove the selected worktree. A locked worktree is only removed when
+    /// `unlock` is set, in which case it is unlocked first.
+    fn delete_worktree(&mut self, unlock: bool) -> Result<()> {
         if let Some(wt) = self.selected_worktree().cloned() {
             if wt.is_main {
                 self.set_status("Cannot delete main worktree", MessageLevel::Error);
@@ -946,29 +2604,70 @@ impl App {
             let path = wt.path.to_string_lossy().to_string();
             let force = !wt.status.is_clean();
 
+            if wt.is_locked {
+                if !unlock {
+                    return Err(WttError::WorktreeLocked {
+                        path: wt.path,
+                        reason: wt.lock_reason,
+                    }
+                    .into());
+                }
+
+                let output = git_command()
+                    .current_dir(&self.repo_root)
+                    .args(["worktree", "unlock", &path])
+                    .output()?;
+                if !output.status.success() {
+                    return Err(WttError::git_failed("worktree unlock", &output).into());
+                }
+            }
+
             let mut args = vec!["worktree", "remove"];
             if force {
                 args.push("--force");
             }
             args.push(&path);
 
-            let output = Command::new("git")
+            let output = git_command()
                 .current_dir(&self.repo_root)
                 .args(&args)
                 .output()?;
 
             if output.status.success() {
-                self.set_status(
-                    &format!("Deleted worktree: {}", wt.branch.unwrap_or(path)),
-                    MessageLevel::Success,
-                );
+                // Removing the worktree we were started in leaves the process without a
+                // working directory; move to the repo root so later commands still run
+                if std::env::current_dir().is_err() {
+                    let _ = std::env::set_current_dir(&self.repo_root);
+                }
+                // A new worktree at the same path shouldn't inherit the old note
+                self.notes.remove(&wt.path);
+                let name = wt.branch.unwrap_or(path);
+                if wt.is_current {
+                    // Send the shell somewhere that still exists on exit
+                    let main_path = self
+                        .worktrees
+                        .iter()
+                        .find(|w| w.is_main)
+                        .map(|w| w.path.clone())
+                        .unwrap_or_else(|| self.repo_root.clone());
+                    self.cd_path = Some(main_path);
+                    self.set_status(
+                        &format!("Deleted worktree: {} (cd to main on exit)", name),
+                        MessageLevel::Success,
+                    );
+                } else {
+                    self.set_status(
+                        &format!("Deleted worktree: {}", name),
+                        MessageLevel::Success,
+                    );
+                }
                 self.refresh_worktrees()?;
                 // Only clear mode on success
                 self.mode = AppMode::Normal;
                 self.delete_confirm = false;
             } else {
                 let error = String::from_utf8_lossy(&output.stderr);
-                self.set_status(&format!("Failed: {}", error.trim()), MessageLevel::Error);
+                self.report_git_failure(&format!("Failed: {}", error.trim()), &args, &wt.path);
                 // Don't reset mode - keep error dialog open
             }
         }
@@ -983,41 +2682,267 @@ impl App {
         }
     }
 
-    fn copy_text_to_clipboard(&mut self, text: &str) {
-        #[cfg(target_os = "macos")]
-        let result = Command::new("pbcopy")
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .and_then(|mut child| {
-                use std::io::Write;
-                if let Some(ref mut stdin) = child.stdin {
-                    stdin.write_all(text.as_bytes())?;
-                }
-                child.wait()
-            });
+    /// Copy a command that reproduces the selected worktree from scratch: clone the
+    /// repo, then add the worktree at the same place relative to the main one
+    fn copy_setup_command(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if wt.is_bare {
+            return;
+        }
+        let git_output = |args: &[&str]| {
+            git_command()
+                .current_dir(&wt.path)
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|out| !out.is_empty())
+        };
 
-        #[cfg(target_os = "linux")]
-        let result = Command::new("xclip")
-            .args(["-selection", "clipboard"])
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .and_then(|mut child| {
-                use std::io::Write;
-                if let Some(ref mut stdin) = child.stdin {
-                    stdin.write_all(text.as_bytes())?;
-                }
-                child.wait()
+        // The branch's own remote, then origin, then whatever remote there is
+        let remote = wt
+            .branch
+            .as_ref()
+            .and_then(|b| git_output(&["config", "--get", &format!("branch.{}.remote", b)]))
+            .filter(|r| r != ".")
+            .or_else(|| {
+                let remotes = git_output(&["remote"])?;
+                let remotes: Vec<&str> = remotes.lines().collect();
+                remotes
+                    .iter()
+                    .find(|r| **r == "origin")
+                    .or(remotes.first())
+                    .map(|r| r.to_string())
             });
+        let Some(url) = remote
+            .as_ref()
+            .and_then(|r| git_output(&["remote", "get-url", r]))
+        else {
+            self.set_status(
+                "No remote to clone from (add one with git remote add)",
+                MessageLevel::Warning,
+            );
+            return;
+        };
 
-        #[cfg(target_os = "windows")]
-        let result = Command::new("clip")
-            .stdin(std::process::Stdio::piped())
-            .spawn()
-            .and_then(|mut child| {
-                use std::io::Write;
-                if let Some(ref mut stdin) = child.stdin {
-                    stdin.write_all(text.as_bytes())?;
-                }
+        // Same layout as here, so relative paths in scripts keep working
+        let relative = match wt.path.strip_prefix(&self.repo_root) {
+            Ok(rest) => rest.to_path_buf(),
+            Err(_) => match self.repo_root.parent().map(|p| wt.path.strip_prefix(p)) {
+                Some(Ok(rest)) => Path::new("..").join(rest),
+                _ => Path::new("..")
+                    .join(format!("{}-worktrees", self.repo_name))
+                    .join(wt.path.file_name().unwrap_or_default()),
+            },
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let checkout = match &wt.branch {
+            Some(branch) if !wt.is_main => format!(
+                " && git worktree add {} {}",
+                shell_quote(&relative),
+                shell_quote(branch)
+            ),
+            Some(branch) => format!(" && git checkout {}", shell_quote(branch)),
+            None => format!(
+                " && git worktree add --detach {} {}",
+                shell_quote(&relative),
+                wt.commit
+            ),
+        };
+        let command = format!(
+            "git clone {} {} && cd {}{}",
+            shell_quote(&url),
+            shell_quote(&self.repo_name),
+            shell_quote(&self.repo_name),
+            checkout
+        );
+        if !self.copy_text_to_clipboard(&command) {
+            return;
+        }
+
+        // A branch that was never pushed won't exist in the fresh clone
+        let pushed = match &wt.branch {
+            Some(branch) => git_output(&[
+                "rev-parse",
+                "--abbrev-ref",
+                &format!("{}@{{upstream}}", branch),
+            ])
+            .is_some(),
+            None => true,
+        };
+        if pushed {
+            self.set_status(
+                &format!("Copied setup command: {}", command),
+                MessageLevel::Success,
+            );
+        } else {
+            self.set_status(
+                "Copied setup command, but the branch has no upstream: push it first",
+                MessageLevel::Warning,
+            );
+        }
+    }
+
+    /// Copy the full message (summary and body) of the selected worktree's HEAD commit
+    fn copy_commit_message_to_clipboard(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        // The body isn't kept around, so ask git for it now
+        let message = git_command()
+            .current_dir(&wt.path)
+            .args(["log", "-1", "--format=%B", "HEAD"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim_end().to_string())
+            .unwrap_or_else(|| wt.commit_message.clone());
+
+        if message.trim().is_empty() {
+            self.set_status("HEAD commit has no message", MessageLevel::Warning);
+            return;
+        }
+        if self.copy_text_to_clipboard(&message) {
+            let summary = message.lines().next().unwrap_or_default();
+            self.set_status(
+                &format!("Copied commit message: {}", summary),
+                MessageLevel::Success,
+            );
+        }
+    }
+
+    /// Show everything the selected worktree changed since it forked from main
+    fn open_merge_base_diff(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if wt.is_bare {
+            return;
+        }
+        if wt.is_unborn() {
+            self.set_status("No commits yet, nothing to diff", MessageLevel::Info);
+            return;
+        }
+        let main_branch = self.main_branch.clone();
+        let main_head = git_command()
+            .current_dir(&wt.path)
+            .args(["rev-parse", "--verify", "--quiet", main_branch.as_str()])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+        let key = main_head.map(|main_head| (wt.commit.clone(), main_head));
+        let merge_base = match key.as_ref().and_then(|key| self.merge_bases.get(key)) {
+            Some(base) => base.clone(),
+            None => {
+                let args = ["merge-base", main_branch.as_str(), "HEAD"];
+                let output = match git_command().current_dir(&wt.path).args(args).output() {
+                    Ok(output) => output,
+                    Err(e) => {
+                        self.set_status(
+                            &format!("Failed to run git merge-base: {}", e),
+                            MessageLevel::Error,
+                        );
+                        return;
+                    }
+                };
+                if !output.status.success() {
+                    let error = String::from_utf8_lossy(&output.stderr);
+                    let message = if error.trim().is_empty() {
+                        format!("No common ancestor with {}", main_branch)
+                    } else {
+                        format!("Failed: {}", error.trim())
+                    };
+                    self.report_git_failure(&message, &args, &wt.path);
+                    return;
+                }
+                let base = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if let Some(key) = key {
+                    self.merge_bases.insert(key, base.clone());
+                }
+                base
+            }
+        };
+
+        let range = format!("{}...HEAD", merge_base);
+        let args = ["diff", range.as_str()];
+        let output = match git_command().current_dir(&wt.path).args(args).output() {
+            Ok(output) => output,
+            Err(e) => {
+                self.set_status(
+                    &format!("Failed to run git diff: {}", e),
+                    MessageLevel::Error,
+                );
+                return;
+            }
+        };
+        if !output.status.success() {
+            let error = String::from_utf8_lossy(&output.stderr);
+            self.report_git_failure(&format!("Failed: {}", error.trim()), &args, &wt.path);
+            return;
+        }
+
+        let diff = String::from_utf8_lossy(&output.stdout);
+        if diff.trim().is_empty() {
+            self.set_status(
+                &format!("No changes since branching from {}", main_branch),
+                MessageLevel::Info,
+            );
+            return;
+        }
+        self.diff_lines = diff
+            .lines()
+            .map(|line| line.replace('\t', "    "))
+            .collect();
+        self.diff_title = format!(
+            "{} vs {} ({})",
+            wt.branch.as_deref().unwrap_or("HEAD"),
+            main_branch,
+            &merge_base[..7.min(merge_base.len())]
+        );
+        self.diff_scroll = 0;
+        self.mode = AppMode::Diff;
+    }
+
+    /// Returns false (after reporting the failure) if the clipboard tool failed
+    fn copy_text_to_clipboard(&mut self, text: &str) -> bool {
+        #[cfg(target_os = "macos")]
+        let result = Command::new("pbcopy")
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                use std::io::Write;
+                if let Some(ref mut stdin) = child.stdin {
+                    stdin.write_all(text.as_bytes())?;
+                }
+                child.wait()
+            });
+
+        #[cfg(target_os = "linux")]
+        let result = Command::new("xclip")
+            .args(["-selection", "clipboard"])
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                use std::io::Write;
+                if let Some(ref mut stdin) = child.stdin {
+                    stdin.write_all(text.as_bytes())?;
+                }
+                child.wait()
+            });
+
+        #[cfg(target_os = "windows")]
+        let result = Command::new("clip")
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                use std::io::Write;
+                if let Some(ref mut stdin) = child.stdin {
+                    stdin.write_all(text.as_bytes())?;
+                }
                 child.wait()
             });
 
@@ -1028,13 +2953,77 @@ impl App {
         ));
 
         match result {
-            Ok(_) => {}
-            Err(_) => self.set_status("Failed to copy to clipboard", MessageLevel::Error),
+            Ok(_) => true,
+            Err(_) => {
+                self.set_status("Failed to copy to clipboard", MessageLevel::Error);
+                false
+            }
         }
     }
 
-    fn open_in_file_manager(&mut self) {
+    fn show_selected_path(&mut self) {
         if let Some(wt) = self.selected_worktree() {
+            self.set_status(
+                &format!("Path: {}", wt.path.to_string_lossy()),
+                MessageLevel::Info,
+            );
+        }
+    }
+
+    /// Quit and hand the selected worktree to the shell integration
+    fn cd_into_selected(&mut self) {
+        if let Some(path) = self.selected_worktree().map(|wt| wt.path.clone()) {
+            self.record_access(&path);
+            self.cd_path = Some(path);
+            self.should_quit = true;
+        }
+    }
+
+    /// Open a new terminal window in the selected worktree
+    fn open_in_terminal(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+
+        #[cfg(target_os = "macos")]
+        let result = Command::new("open")
+            .args(["-a", "Terminal"])
+            .arg(&wt.path)
+            .spawn();
+
+        #[cfg(target_os = "linux")]
+        let result = Command::new(
+            std::env::var("TERMINAL").unwrap_or_else(|_| "x-terminal-emulator".to_string()),
+        )
+        .current_dir(&wt.path)
+        .spawn();
+
+        #[cfg(target_os = "windows")]
+        let result = Command::new("cmd")
+            .args(["/c", "start", "cmd"])
+            .current_dir(&wt.path)
+            .spawn();
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+        let result: Result<std::process::Child, std::io::Error> = Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Terminal not supported",
+        ));
+
+        match result {
+            Ok(_) => {
+                self.record_access(&wt.path);
+                self.set_status("Opened in a new terminal", MessageLevel::Success);
+            }
+            Err(e) => self.set_status(
+                &format!("Failed to open terminal: {}", e),
+                MessageLevel::Error,
+            ),
+        }
+    }
+
+    fn open_in_file_manager(&mut self) {
+        if let Some(wt) = self.selected_worktree().cloned() {
             let path = wt.path.to_string_lossy().to_string();
 
             #[cfg(target_os = "macos")]
@@ -1053,18 +3042,65 @@ impl App {
             ));
 
             match result {
-                Ok(_) => self.set_status("Opened in file manager", MessageLevel::Success),
+                Ok(_) => {
+                    self.record_access(&wt.path);
+                    self.set_status("Opened in file manager", MessageLevel::Success);
+                }
                 Err(_) => self.set_status("Failed to open file manager", MessageLevel::Error),
             }
         }
     }
 
+    /// Open the selected worktree in tmux: the configured layout template, or a new window
+    fn open_in_tmux(&mut self) {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        let path = wt.path.to_string_lossy().to_string();
+        let branch = wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone());
+
+        let result = match &self.config.tmux_template {
+            Some(template) => {
+                let command = template
+                    .replace("{path}", &shell_quote(&path))
+                    .replace("{branch}", &shell_quote(&branch))
+                    .replace("{repo}", &shell_quote(&self.repo_name));
+                Command::new("sh").args(["-c", &command]).output()
+            }
+            None if std::env::var_os("TMUX").is_some() => Command::new("tmux")
+                .args(["new-window", "-c", &path, "-n", &branch])
+                .output(),
+            None => {
+                self.set_status(
+                    "Not inside tmux (set tmux_template to start a session)",
+                    MessageLevel::Warning,
+                );
+                return;
+            }
+        };
+
+        match result {
+            Ok(output) if output.status.success() => {
+                self.record_access(&wt.path);
+                self.set_status(&format!("Opened {} in tmux", branch), MessageLevel::Success);
+            }
+            Ok(output) => {
+                let error = String::from_utf8_lossy(&output.stderr);
+                self.set_status(
+                    &format!("tmux failed: {}", error.trim()),
+                    MessageLevel::Error,
+                );
+            }
+            Err(e) => self.set_status(&format!("Failed to run tmux: {}", e), MessageLevel::Error),
+        }
+    }
+
     fn toggle_lock(&mut self) -> Result<()> {
         if let Some(wt) = self.selected_worktree().cloned() {
             let path = wt.path.to_string_lossy().to_string();
             let action = if wt.is_locked { "unlock" } else { "lock" };
 
-            let output = Command::new("git")
+            let output = git_command()
                 .current_dir(&self.repo_root)
                 .args(["worktree", action, &path])
                 .output()?;
@@ -1081,7 +3117,11 @@ impl App {
                 self.refresh_worktrees()?;
             } else {
                 let error = String::from_utf8_lossy(&output.stderr);
-                self.set_status(&format!("Failed: {}", error.trim()), MessageLevel::Error);
+                self.report_git_failure(
+                    &format!("Failed: {}", error.trim()),
+                    &["worktree", action, &path],
+                    &wt.path,
+                );
             }
         }
         Ok(())
@@ -1090,7 +3130,7 @@ impl App {
     fn fetch_all(&mut self) -> Result<()> {
         self.set_status("Fetching from remote...", MessageLevel::Info);
 
-        let output = Command::new("git")
+        let output = git_command()
             .current_dir(&self.repo_root)
             .args(["fetch", "--all", "--prune"])
             .output()?;
@@ -1099,1172 +3139,5391 @@ impl App {
             self.set_status("Fetched latest from remote", MessageLevel::Success);
             self.refresh_worktrees()?;
         } else {
-            self.set_status("Fetch failed", MessageLevel::Error);
+            let repo_root = self.repo_root.clone();
+            self.report_git_failure("Fetch failed", &["fetch", "--all", "--prune"], &repo_root);
         }
         Ok(())
     }
 
-    fn pull_current(&mut self) -> Result<()> {
-        if let Some(wt) = self.selected_worktree().cloned() {
-            self.set_status("Pulling...", MessageLevel::Info);
+    /// Pick a branch for the selected worktree's branch to track, remote ones first
+    fn open_upstream_picker(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        let Some(branch) = wt.branch.clone() else {
+            self.set_status(
+                "Detached HEAD has no branch to track",
+                MessageLevel::Warning,
+            );
+            return;
+        };
+        if wt.branch_is_lossy() {
+            self.set_status(
+                "Branch name is not valid UTF-8, set its upstream with git directly",
+                MessageLevel::Warning,
+            );
+            return;
+        }
+        let current = wt.status.upstream.clone();
+
+        self.branch_filter = BranchFilter::Remote;
+        self.clear_branch_query();
+        if let Err(e) = self.refresh_branches() {
+            self.report_error(&e);
+            return;
+        }
+        // Start on the current upstream, or the remote branch of the same name
+        let visible = self.visible_branches();
+        let preselect = visible
+            .iter()
+            .position(|b| current.as_deref() == Some(b.upstream_name()))
+            .or_else(|| {
+                visible.iter().position(|b| {
+                    b.upstream_name()
+                        .split_once('/')
+                        .is_some_and(|(_, name)| name == branch)
+                })
+            });
+        self.branch_list_state.select(Some(preselect.unwrap_or(0)));
+        self.mode = AppMode::UpstreamSelect;
+    }
+
+    fn set_upstream(&mut self, path: &Path, upstream: &str) -> Result<()> {
+        let flag = format!("--set-upstream-to={}", upstream);
+        let args = ["branch", flag.as_str()];
+        let output = git_command().current_dir(path).args(args).output()?;
+
+        if output.status.success() {
+            self.refresh_worktrees()?;
+            let status = self
+                .worktrees
+                .iter()
+                .find(|wt| wt.path == path)
+                .map(|wt| wt.status.clone())
+                .unwrap_or_default();
+            self.set_status(
+                &format!(
+                    "Upstream set to {} (↑{} ↓{})",
+                    status.upstream.as_deref().unwrap_or(upstream),
+                    status.ahead,
+                    status.behind
+                ),
+                MessageLevel::Success,
+            );
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            self.report_git_failure(&format!("Failed: {}", error.trim()), &args, path);
+        }
+        Ok(())
+    }
+
+    /// Fetch only the selected branch from its upstream, then refresh that worktree
+    fn fetch_selected(&mut self) -> Result<()> {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return Ok(());
+        };
+        let Some(branch) = wt.branch.clone() else {
+            return Ok(());
+        };
+        if wt.branch_is_lossy() {
+            self.set_status(
+                "Branch name is not valid UTF-8, fetch it with git directly",
+                MessageLevel::Warning,
+            );
+            return Ok(());
+        }
 
-            let output = Command::new("git")
+        let config_value = |key: String| {
+            git_command()
                 .current_dir(&wt.path)
-                .args(["pull"])
-                .output()?;
+                .args(["config", "--get", &key])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        let remote = config_value(format!("branch.{}.remote", branch));
+        let merge = config_value(format!("branch.{}.merge", branch));
+        let (Some(remote), Some(merge)) = (remote, merge) else {
+            self.set_status(
+                &format!("{} has no upstream branch configured", branch),
+                MessageLevel::Warning,
+            );
+            return Ok(());
+        };
+        let upstream = merge
+            .strip_prefix("refs/heads/")
+            .unwrap_or(&merge)
+            .to_string();
 
-            if output.status.success() {
-                self.set_status(
-                    &format!("Pulled {}", wt.branch.unwrap_or_else(|| "worktree".into())),
-                    MessageLevel::Success,
-                );
-                self.refresh_worktrees()?;
-            } else {
-                let error = String::from_utf8_lossy(&output.stderr);
-                self.set_status(
-                    &format!("Pull failed: {}", error.trim()),
-                    MessageLevel::Error,
-                );
-            }
+        let args = ["fetch", remote.as_str(), upstream.as_str()];
+        let output = git_command().current_dir(&wt.path).args(args).output()?;
+
+        if output.status.success() {
+            self.refresh_selected_worktree();
+            self.set_status(
+                &format!("Fetched {}/{}", remote, upstream),
+                MessageLevel::Success,
+            );
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            self.report_git_failure(
+                &format!("Fetch of {}/{} failed: {}", remote, upstream, error.trim()),
+                &args,
+                &wt.path,
+            );
         }
         Ok(())
     }
 
-    fn push_current(&mut self) -> Result<()> {
+    fn pull_current(&mut self) -> Result<()> {
         if let Some(wt) = self.selected_worktree().cloned() {
-            self.set_status("Pushing...", MessageLevel::Info);
+            if wt.status.upstream_gone {
+                // git's own error ("no such ref was fetched") doesn't say what happened
+                self.set_status(
+                    &format!(
+                        "{} was deleted on the remote, nothing to pull (x to delete this worktree)",
+                        wt.status
+                            .upstream
+                            .as_deref()
+                            .unwrap_or("The upstream branch")
+                    ),
+                    MessageLevel::Warning,
+                );
+                return Ok(());
+            }
+            self.set_status("Pulling...", MessageLevel::Info);
 
-            let output = Command::new("git")
+            let output = git_command()
                 .current_dir(&wt.path)
-                .args(["push"])
+                .args(["pull"])
                 .output()?;
 
             if output.status.success() {
                 self.set_status(
-                    &format!("Pushed {}", wt.branch.unwrap_or_else(|| "worktree".into())),
+                    &format!("Pulled {}", wt.branch.unwrap_or_else(|| "worktree".into())),
                     MessageLevel::Success,
                 );
                 self.refresh_worktrees()?;
             } else {
                 let error = String::from_utf8_lossy(&output.stderr);
-                self.set_status(
-                    &format!("Push failed: {}", error.trim()),
-                    MessageLevel::Error,
+                self.report_git_failure(
+                    &format!("Pull failed: {}", error.trim()),
+                    &["pull"],
+                    &wt.path,
                 );
             }
         }
         Ok(())
     }
 
-    fn prune_worktrees(&mut self) -> Result<()> {
-        self.set_status("Pruning stale worktrees...", MessageLevel::Info);
+    /// Open the commit dialog for the selected worktree, if it has staged changes
+    fn open_commit_dialog(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        if wt.status.staged == 0 {
+            let name = wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone());
+            self.set_status(
+                &format!("Nothing staged in {}", name),
+                MessageLevel::Warning,
+            );
+            return;
+        }
+        self.commit_input.clear();
+        self.commit_cursor = 0;
+        self.mode = AppMode::Commit;
+    }
 
-        let output = Command::new("git")
+    /// Commit the staged changes of the selected worktree with the dialog's message
+    fn commit_staged(&mut self) -> Result<()> {
+        self.mode = AppMode::Normal;
+        let message = self.commit_input.trim().to_string();
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return Ok(());
+        };
+
+        let output = git_command()
+            .current_dir(&wt.path)
+            .args(["commit", "-m", &message])
+            .output()
+            .context("Failed to run git commit")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let error = if stderr.trim().is_empty() {
+                stdout
+            } else {
+                stderr
+            };
+            self.report_git_failure(
+                &format!("Commit failed: {}", error.trim()),
+                &["commit", "-m", &message],
+                &wt.path,
+            );
+            return Ok(());
+        }
+
+        self.commit_input.clear();
+        self.commit_cursor = 0;
+        self.refresh_selected_worktree();
+        let name = wt.branch.unwrap_or_else(|| "worktree".into());
+        self.set_status(&format!("Committed to {}", name), MessageLevel::Success);
+        Ok(())
+    }
+
+    /// Open the note dialog, prefilled with the selected worktree's current note
+    fn open_note_dialog(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        self.note_input = self.note_for(wt).unwrap_or_default().to_string();
+        self.note_cursor = self.note_input.len();
+        self.mode = AppMode::Note;
+    }
+
+    /// Save the dialog's note for the selected worktree (an empty note removes it)
+    fn save_note(&mut self) {
+        self.mode = AppMode::Normal;
+        let Some(path) = self.selected_worktree().map(|wt| wt.path.clone()) else {
+            return;
+        };
+        let note = self.note_input.trim().to_string();
+        self.note_input.clear();
+        self.note_cursor = 0;
+
+        let removed = note.is_empty();
+        if removed {
+            if self.notes.remove(&path).is_none() {
+                return;
+            }
+        } else {
+            self.notes.insert(path, note);
+        }
+
+        if let Err(e) = cache::save_notes(&self.repo_root, &self.notes) {
+            self.report_error(
+                &anyhow::Error::from(WttError::Cache(e)).context("Failed to save note"),
+            );
+        } else if removed {
+            self.set_status("Note removed", MessageLevel::Info);
+        } else {
+            self.set_status("Note saved", MessageLevel::Success);
+        }
+    }
+
+    /// Open the new-branch dialog for the selected worktree's HEAD
+    fn open_new_branch_dialog(&mut self) {
+        if self.selected_worktree().is_none_or(|wt| wt.is_bare) {
+            return;
+        }
+        self.branch_input.clear();
+        self.branch_cursor = 0;
+        self.mode = AppMode::NewBranch;
+    }
+
+    /// Create a branch at the selected worktree's HEAD without adding a worktree
+    fn create_branch_here(&mut self) -> Result<()> {
+        let name = self.branch_input.trim().to_string();
+        if name.is_empty() {
+            self.set_status("Branch name cannot be empty", MessageLevel::Warning);
+            return Ok(());
+        }
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return Ok(());
+        };
+
+        let output = git_command()
             .current_dir(&self.repo_root)
-            .args(["worktree", "prune"])
+            .args(["branch", &name, &wt.commit])
             .output()?;
 
         if output.status.success() {
-            self.set_status("Pruned stale worktrees", MessageLevel::Success);
-            self.refresh_worktrees()?;
+            self.mode = AppMode::Normal;
+            self.branch_input.clear();
+            self.branch_cursor = 0;
+            let _ = self.refresh_branches();
+            self.set_status(
+                &format!("Created branch {} at {}", name, wt.commit_short),
+                MessageLevel::Success,
+            );
         } else {
-            self.set_status("Prune failed", MessageLevel::Error);
+            let error = String::from_utf8_lossy(&output.stderr);
+            let repo_root = self.repo_root.clone();
+            self.report_git_failure(
+                &format!("Failed: {}", error.trim()),
+                &["branch", &name, &wt.commit],
+                &repo_root,
+            );
         }
         Ok(())
     }
 
-    fn perform_merge(&mut self, source_idx: usize, target_branch: String) -> Result<()> {
-        let source_wt = &self.worktrees[source_idx];
-        let source_branch = match &source_wt.branch {
-            Some(b) => b.clone(),
-            None => return Ok(()), // Should be handled by caller
+    /// Open the dialog for adopting an existing directory as a worktree
+    fn open_import_dialog(&mut self) {
+        self.import_path_input.clear();
+        self.import_path_cursor = 0;
+        self.import_branch_input.clear();
+        self.import_branch_cursor = 0;
+        self.import_branch_focused = false;
+        self.mode = AppMode::Import;
+    }
+
+    fn close_import_dialog(&mut self) {
+        self.mode = AppMode::Normal;
+        self.import_path_input.clear();
+        self.import_path_cursor = 0;
+        self.import_branch_input.clear();
+        self.import_branch_cursor = 0;
+        self.import_branch_focused = false;
+    }
+
+    /// The import dialog's path, with `~` expanded and relative paths taken from the
+    /// repo's parent directory (where `<repo>-worktrees` lives)
+    fn import_target_path(&self) -> PathBuf {
+        let input = self.import_path_input.trim();
+        let path = match input.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()
+                .map(|home| home.join(rest))
+                .unwrap_or_else(|| PathBuf::from(input)),
+            None => PathBuf::from(input),
         };
+        if path.is_absolute() {
+            return path;
+        }
+        self.repo_root
+            .parent()
+            .unwrap_or(&self.repo_root)
+            .join(path)
+    }
 
-        if source_branch == target_branch {
-            self.set_status("Cannot merge branch into itself", MessageLevel::Error);
+    /// Register the import dialog's directory as a worktree: an empty directory gets
+    /// the branch checked out into it, an orphaned worktree directory is repaired
+    fn import_worktree(&mut self) -> Result<()> {
+        if self.import_path_input.trim().is_empty() {
+            self.set_status("Path cannot be empty", MessageLevel::Warning);
             return Ok(());
         }
+        let path = self.import_target_path();
+        if !path.is_dir() {
+            self.set_status(
+                &format!("{} is not an existing directory", path.display()),
+                MessageLevel::Error,
+            );
+            return Ok(());
+        }
+        let path = canonical_path(&path);
+        if self.worktrees.iter().any(|wt| wt.path == path) {
+            self.set_status(
+                &format!("{} is already a worktree", path.display()),
+                MessageLevel::Warning,
+            );
+            return Ok(());
+        }
+        let Some(path_str) = path.to_str().map(str::to_string) else {
+            self.set_status(
+                &format!("Path is not valid UTF-8: {}", path.display()),
+                MessageLevel::Error,
+            );
+            return Ok(());
+        };
 
-        // Find a worktree where target_branch is checked out
-        let target_wt_path = self
-            .worktrees
-            .iter()
-            .find(|wt| wt.branch.as_ref() == Some(&target_branch))
-            .map(|wt| wt.path.clone());
-
-        let merge_path = match target_wt_path {
-            Some(path) => path,
-            None => {
-                // If not found, we could potentially try to merge in the main repo
-                // if the main repo can switch to that branch.
-                // For now, let's just support merging into active worktrees.
+        let dot_git = path.join(".git");
+        let (args, success) = if dot_git.is_dir() {
+            self.set_status(
+                &format!(
+                    "{} is a standalone clone, not a worktree of this repo",
+                    path.display()
+                ),
+                MessageLevel::Error,
+            );
+            return Ok(());
+        } else if dot_git.is_file() {
+            // Orphaned worktree: its admin dir still exists but the links are broken
+            let gitdir = std::fs::read_to_string(&dot_git)
+                .ok()
+                .and_then(|content| {
+                    content
+                        .trim()
+                        .strip_prefix("gitdir: ")
+                        .map(|dir| path.join(dir))
+                })
+                .filter(|dir| dir.exists());
+            if gitdir.is_none() {
                 self.set_status(
-                    &format!("Branch {} is not active in any worktree", target_branch),
+                    &format!(
+                        "{} points to pruned worktree data; move it aside and create the worktree again",
+                        path.display()
+                    ),
                     MessageLevel::Error,
                 );
                 return Ok(());
             }
+            (
+                vec!["worktree".to_string(), "repair".to_string(), path_str],
+                format!("Repaired worktree at {}", path.display()),
+            )
+        } else if std::fs::read_dir(&path)?.next().is_some() {
+            self.set_status(
+                &format!("{} is not empty and not a git worktree", path.display()),
+                MessageLevel::Error,
+            );
+            return Ok(());
+        } else {
+            let branch = self.import_branch_input.trim().to_string();
+            if branch.is_empty() {
+                self.set_status(
+                    "Branch cannot be empty for an empty directory",
+                    MessageLevel::Warning,
+                );
+                return Ok(());
+            }
+            let success = format!("Registered {} on {}", path.display(), branch);
+            (
+                vec!["worktree".to_string(), "add".to_string(), path_str, branch],
+                success,
+            )
         };
 
-        self.set_status(
-            &format!("Merging {} into {}...", source_branch, target_branch),
-            MessageLevel::Info,
-        );
-
-        let output = Command::new("git")
-            .current_dir(&merge_path)
-            .args(["merge", &source_branch, "--no-edit"])
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args(&args)
             .output()?;
 
         if output.status.success() {
-            self.set_status(
-                &format!("Merged {} into {}", source_branch, target_branch),
-                MessageLevel::Success,
-            );
+            self.close_import_dialog();
             self.refresh_worktrees()?;
+            if let Some(idx) = self.worktrees.iter().position(|wt| wt.path == path) {
+                self.select_worktree(idx);
+            }
+            self.set_status(&success, MessageLevel::Success);
         } else {
             let error = String::from_utf8_lossy(&output.stderr);
-            if error.contains("CONFLICT") || error.contains("conflict") {
+            let repo_root = self.repo_root.clone();
+            self.report_git_failure(
+                &format!("Import failed: {}", error.trim()),
+                &args,
+                &repo_root,
+            );
+        }
+        Ok(())
+    }
+
+    fn push_current(&mut self) -> Result<()> {
+        if let Some(wt) = self.selected_worktree().cloned() {
+            self.set_status("Pushing...", MessageLevel::Info);
+
+            let output = git_command()
+                .current_dir(&wt.path)
+                .args(["push"])
+                .output()?;
+
+            if output.status.success() {
                 self.set_status(
-                    &format!("Conflict! Resolve in: {}", merge_path.display()),
-                    MessageLevel::Warning,
+                    &format!("Pushed {}", wt.branch.unwrap_or_else(|| "worktree".into())),
+                    MessageLevel::Success,
                 );
+                self.refresh_worktrees()?;
             } else {
-                self.set_status(
-                    &format!("Merge failed: {}", error.trim()),
-                    MessageLevel::Error,
+                let error = String::from_utf8_lossy(&output.stderr);
+                self.report_git_failure(
+                    &format!("Push failed: {}", error.trim()),
+                    &["push"],
+                    &wt.path,
                 );
             }
         }
         Ok(())
     }
 
-    fn get_main_branch_name(&self) -> String {
-        // Try to detect the main branch name
-        let output = Command::new("git")
-            .current_dir(&self.repo_root)
-            .args(["symbolic-ref", "refs/remotes/origin/HEAD", "--short"])
-            .output();
+    /// Enable (cone mode) or disable sparse-checkout for the selected worktree
+    fn toggle_sparse_checkout(&mut self) -> Result<()> {
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return Ok(());
+        };
+        let name = wt.branch.clone().unwrap_or_else(|| "worktree".into());
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let branch = String::from_utf8_lossy(&output.stdout)
-                    .trim()
-                    .strip_prefix("origin/")
-                    .unwrap_or("main")
-                    .to_string();
-                return branch;
-            }
+        let enable = wt.sparse_patterns.is_none();
+        if enable && !wt.status.is_clean() {
+            self.set_status(
+                "Commit or stash changes before enabling sparse-checkout",
+                MessageLevel::Warning,
+            );
+            return Ok(());
         }
 
-        // Fallback: check if main or master exists
-        let output = Command::new("git")
-            .current_dir(&self.repo_root)
-            .args(["rev-parse", "--verify", "main"])
-            .output();
+        let args: &[&str] = if enable {
+            &["sparse-checkout", "init", "--cone"]
+        } else {
+            &["sparse-checkout", "disable"]
+        };
+        let output = git_command().current_dir(&wt.path).args(args).output()?;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                return "main".to_string();
+        if output.status.success() {
+            self.refresh_worktrees()?;
+            if enable {
+                self.set_status(
+                    &format!(
+                        "Enabled sparse-checkout for {} (add dirs with git sparse-checkout add)",
+                        name
+                    ),
+                    MessageLevel::Success,
+                );
+            } else {
+                self.set_status(
+                    &format!("Disabled sparse-checkout for {}", name),
+                    MessageLevel::Success,
+                );
             }
+        } else {
+            let error = String::from_utf8_lossy(&output.stderr);
+            self.report_git_failure(
+                &format!("Sparse-checkout failed: {}", error.trim()),
+                args,
+                &wt.path,
+            );
         }
-
-        "master".to_string()
+        Ok(())
     }
 
-    fn refresh_merge_branches(&mut self) {
-        let mut branches = Vec::new();
-        let mut seen = std::collections::HashSet::new();
+    /// Fast-forward the main worktree's branch to its upstream, regardless of selection
+    fn fast_forward_main(&mut self) -> Result<()> {
+        let Some(main_wt) = self.worktrees.iter().find(|wt| wt.is_main).cloned() else {
+            self.set_status("No main worktree found", MessageLevel::Error);
+            return Ok(());
+        };
+        let Some(branch) = main_wt.branch.clone() else {
+            self.set_status(
+                "Main worktree is detached, nothing to fast-forward",
+                MessageLevel::Warning,
+            );
+            return Ok(());
+        };
 
-        for wt in &self.worktrees {
-            if let Some(ref name) = wt.branch {
-                if seen.insert(name.clone()) {
-                    branches.push(Branch {
-                        name: name.clone(),
-                        is_remote: false,
-                        is_current: wt.is_main,
-                    });
-                }
-            }
+        self.set_status(
+            &format!("Fast-forwarding {}...", branch),
+            MessageLevel::Info,
+        );
+
+        if main_wt.status.upstream.is_none() {
+            self.set_status(
+                &format!("{} has no upstream branch configured", branch),
+                MessageLevel::Warning,
+            );
+            return Ok(());
         }
 
-        // Sort active branches: main/master first, then alphabetically
-        branches.sort_by(|a, b| {
-            let a_is_main = a.name == "main" || a.name == "master";
-            let b_is_main = b.name == "main" || b.name == "master";
-            if a_is_main && !b_is_main {
-                std::cmp::Ordering::Less
-            } else if !a_is_main && b_is_main {
-                std::cmp::Ordering::Greater
-            } else {
-                a.name.cmp(&b.name)
+        match sync_worktree(&main_wt) {
+            Ok(SyncOutcome::FastForwarded) => {
+                self.refresh_worktrees()?;
+                self.set_status(
+                    &format!("Fast-forwarded {} to upstream", branch),
+                    MessageLevel::Success,
+                );
             }
-        });
-
-        self.available_branches = branches;
+            Ok(SyncOutcome::UpToDate) => self.set_status(
+                &format!("{} is already up to date", branch),
+                MessageLevel::Info,
+            ),
+            Ok(SyncOutcome::Diverged) => self.set_status(
+                &format!(
+                    "{} has diverged from its upstream, fast-forward not possible",
+                    branch
+                ),
+                MessageLevel::Warning,
+            ),
+            Ok(SyncOutcome::Dirty) => self.set_status(
+                &format!("{} has uncommitted changes, not fast-forwarding", branch),
+                MessageLevel::Warning,
+            ),
+            Ok(SyncOutcome::UpstreamGone) => self.set_status(
+                &format!("The upstream branch of {} is gone", branch),
+                MessageLevel::Warning,
+            ),
+            Ok(SyncOutcome::Failed(reason)) => self.report_git_failure(
+                &format!("Fast-forward failed: {}", reason),
+                &["pull", "--ff-only"],
+                &main_wt.path,
+            ),
+            Err(e) => self.report_error(&e),
+        }
+        Ok(())
     }
 
-    fn cycle_sort(&mut self) {
-        self.sort_order = self.sort_order.next();
-        // keep selection on the same worktree if possible
-        // get currently selected worktrees name
-        let selected_wt_name = self.selected_worktree().and_then(|wt| wt.branch.clone());
-        self.apply_sort();
-        self.filtered_indices = (0..self.worktrees.len()).collect();
-        // restore selection
-        if let Some(name) = selected_wt_name {
-            if let Some(pos) = self
-                .worktrees
-                .iter()
-                .position(|wt| wt.branch.as_ref() == Some(&name))
-            {
-                if let Some(filtered_pos) = self.filtered_indices.iter().position(|&idx| idx == pos)
-                {
-                    self.table_state.select(Some(filtered_pos));
-                }
-            }
-        }
-        if !self.search_query.is_empty() {
-            self.update_search_filter();
+    /// Fast-forward every worktree with an upstream, reviewing them first when many would
+    /// be touched
+    fn request_sync_all(&mut self) {
+        let affected: Vec<String> = self
+            .worktrees
+            .iter()
+            .filter(|wt| wt.status.upstream.is_some() && wt.path.exists())
+            .map(|wt| {
+                format!(
+                    "{}  {}",
+                    wt.branch.as_deref().unwrap_or(&wt.commit_short),
+                    wt.path.display()
+                )
+            })
+            .collect();
+        if affected.is_empty() {
+            self.set_status(
+                "No worktree has an upstream branch to fast-forward to",
+                MessageLevel::Warning,
+            );
+            return;
         }
-        self.set_status(
-            &format!("Sorted by {}", self.sort_order.label()),
-            MessageLevel::Info,
-        );
+        self.review_batch(PendingOp::SyncAll, "Fast-Forward All Worktrees", affected);
     }
-}
-
-// ============================================================================
-// Event Handling
-// ============================================================================
 
-fn handle_mouse_event(app: &mut App, mouse: crossterm::event::MouseEvent) -> Result<()> {
-    if app.mode != AppMode::Normal {
-        return Ok(());
-    }
-
-    match mouse.kind {
-        MouseEventKind::Down(MouseButton::Left) => {
-            if let Some(area) = app.list_area {
-                if mouse.column >= area.x
-                    && mouse.column < area.x + area.width
-                    && mouse.row >= area.y
-                    && mouse.row < area.y + area.height
-                {
-                    let row_offset = mouse.row.saturating_sub(area.y + 3);
-                    let clicked_index = row_offset as usize;
-
-                    if clicked_index < app.filtered_indices.len() {
-                        app.table_state.select(Some(clicked_index));
-                    }
-                }
-            }
+    /// Fast-forward every worktree with an upstream, skipping dirty and diverged
+    /// ones, then show what happened to each
+    fn sync_all_worktrees(&mut self) -> Result<()> {
+        let targets: Vec<Worktree> = self
+            .worktrees
+            .iter()
+            .filter(|wt| wt.status.upstream.is_some() && wt.path.exists())
+            .cloned()
+            .collect();
+        if targets.is_empty() {
+            self.set_status(
+                "No worktree has an upstream branch to fast-forward to",
+                MessageLevel::Warning,
+            );
+            return Ok(());
         }
-        MouseEventKind::ScrollDown => app.move_selection(1),
-        MouseEventKind::ScrollUp => app.move_selection(-1),
-        _ => {}
-    }
-    Ok(())
-}
-
-fn handle_normal_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
-    match key {
-        // Quit
-        KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
-
-        // Navigation
-        KeyCode::Char('j') | KeyCode::Down => app.move_selection(1),
-        KeyCode::Char('k') | KeyCode::Up => app.move_selection(-1),
-        KeyCode::Char('g') => app.select_first(),
-        KeyCode::Char('G') => app.select_last(),
-        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => app.move_selection(5),
-        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => app.move_selection(-5),
-        KeyCode::Home => app.select_first(),
-        KeyCode::End => app.select_last(),
-        KeyCode::PageDown => app.move_selection(10),
-        KeyCode::PageUp => app.move_selection(-10),
 
-        // Quick jump 1-9
-        KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
-            let idx = c.to_digit(10).unwrap() as usize - 1;
-            if idx < app.filtered_indices.len() {
-                app.table_state.select(Some(idx));
-            }
+        let mut results = Vec::new();
+        for wt in &targets {
+            let name = wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone());
+            self.set_status(&format!("Fast-forwarding {}...", name), MessageLevel::Info);
+            results.push(SyncResult {
+                path: wt.path.clone(),
+                name,
+                outcome: sync_worktree(wt).unwrap_or_else(|e| SyncOutcome::Failed(e.to_string())),
+            });
         }
 
-        KeyCode::Tab => {}
+        let count = |f: fn(&SyncOutcome) -> bool| results.iter().filter(|r| f(&r.outcome)).count();
+        let updated = count(|o| matches!(o, SyncOutcome::FastForwarded));
+        let current = count(|o| matches!(o, SyncOutcome::UpToDate));
+        let failed = count(|o| matches!(o, SyncOutcome::Failed(_)));
+        let skipped = results.len() - updated - current - failed;
 
-        // Actions
-        KeyCode::Char('n') | KeyCode::Char('N') => {
-            let prefill_current = matches!(key, KeyCode::Char('N'));
-            app.mode = AppMode::Create;
-            app.create_input.clear();
-            app.create_cursor = 0;
-            app.create_from_branch = if prefill_current {
-                app.worktrees
-                    .iter()
-                    .find(|wt| wt.is_current)
-                    .and_then(|wt| wt.branch.clone())
-            } else {
-                None
-            };
-            app.create_checkout_existing = false;
-            let _ = app.refresh_branches();
+        self.refresh_worktrees()?;
+        let mut summary = format!("{} fast-forwarded, {} up to date", updated, current);
+        if skipped > 0 {
+            summary.push_str(&format!(", {} skipped", skipped));
         }
-        KeyCode::Char('x') | KeyCode::Delete => {
-            if app.selected_worktree().is_some() {
-                app.mode = AppMode::Delete;
-                app.delete_confirm = false;
-            }
-        }
-        KeyCode::Enter | KeyCode::Char('o') => {
-            if let Some(wt) = app.selected_worktree() {
-                app.set_status(
-                    &format!("Path: {}", wt.path.to_string_lossy()),
-                    MessageLevel::Info,
-                );
-            }
+        if failed > 0 {
+            summary.push_str(&format!(", {} failed", failed));
+            self.op_failed = true;
         }
+        let level = if failed > 0 {
+            MessageLevel::Error
+        } else if skipped > 0 {
+            MessageLevel::Warning
+        } else {
+            MessageLevel::Success
+        };
+        self.set_status(&summary, level);
+        self.sync_results = results;
+        self.sync_list_state.select(Some(0));
+        self.mode = AppMode::SyncReport;
+        Ok(())
+    }
 
-        // Change directory to selected worktree (for shell integration)
-        KeyCode::Char(' ') => {
-            if let Some(wt) = app.selected_worktree() {
-                app.cd_path = Some(wt.path.clone());
-                app.should_quit = true;
-            }
+    /// Queue `op`, listing every affected worktree for confirmation first if there are
+    /// more than `confirm_batch_above`
+    fn review_batch(&mut self, op: PendingOp, title: &str, affected: Vec<String>) {
+        if affected.len() <= self.config.confirm_batch_above {
+            self.queue_op(op);
+            return;
         }
+        self.open_batch_review(op, title, affected);
+    }
 
-        // New features
-        KeyCode::Char('y') => app.copy_path_to_clipboard(),
-        KeyCode::Char('O') => app.open_in_file_manager(),
-        KeyCode::Char('p') => {
-            let _ = app.pull_current();
-        }
-        KeyCode::Char('P') => {
-            let _ = app.push_current();
-        }
-        KeyCode::Char('s') => app.cycle_sort(),
-        KeyCode::Char('t') => app.show_recent_commits = !app.show_recent_commits,
-        KeyCode::Char('L') => {
-            let _ = app.toggle_lock();
-        }
-        KeyCode::Char('r') | KeyCode::Char('R') => {
-            let _ = app.refresh_worktrees();
-        }
-        KeyCode::Char('F') => {
-            let _ = app.fetch_all();
-        }
-        KeyCode::Char('X') => {
-            let _ = app.prune_worktrees();
-        }
-        KeyCode::Char('m') => {
-            if let Some(wt) = app.selected_worktree() {
-                if wt.is_main && wt.branch.as_deref() == Some(&app.get_main_branch_name()) {
-                    // It's the main branch in the main worktree,
-                    // we can allow merging from it if the user wants to merge into something else.
-                }
+    /// List every affected worktree for confirmation before queueing `op`
+    fn open_batch_review(&mut self, op: PendingOp, title: &str, affected: Vec<String>) {
+        self.batch_confirm = Some(BatchConfirm {
+            op,
+            title: title.to_string(),
+            affected,
+        });
+        self.batch_list_state.select(Some(0));
+        self.mode = AppMode::BatchConfirm;
+    }
 
-                if wt.branch.is_none() {
-                    app.set_status("Cannot merge detached HEAD", MessageLevel::Error);
-                } else {
-                    let idx = app.table_state.selected().unwrap();
-                    app.merge_source_idx = Some(app.filtered_indices[idx]);
-                    app.mode = AppMode::MergeSelect;
-                    app.refresh_merge_branches();
-                    app.branch_list_state.select(Some(0));
-                }
+    /// Prune stale worktrees, reviewing them first when many would go
+    fn request_prune(&mut self) {
+        let entries = match self.prune_preview() {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.report_error(&e);
+                return;
             }
+        };
+        if entries.is_empty() {
+            self.set_status("Nothing to prune", MessageLevel::Info);
+            return;
         }
+        let affected = entries
+            .iter()
+            .map(|(admin, reason)| format!("{}  ({})", self.describe_admin_dir(admin), reason))
+            .collect();
+        // Always reviewed: this is exactly what git is about to remove
+        self.open_batch_review(PendingOp::Prune, "Prune Stale Worktrees", affected);
+    }
 
-        KeyCode::Char('/') => {
-            app.mode = AppMode::Search;
-            app.search_query.clear();
-            app.search_cursor = 0;
+    /// Go through prunable worktrees one by one instead of pruning them all at once
+    fn open_prune_wizard(&mut self) {
+        let steps: Vec<PruneStep> = self
+            .worktrees
+            .iter()
+            .filter(|wt| wt.is_prunable && !wt.is_locked)
+            .map(|wt| PruneStep {
+                path: wt.path.clone(),
+                name: wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone()),
+                reason: wt.prune_reason.clone(),
+            })
+            .collect();
+        if steps.is_empty() {
+            self.set_status("No prunable worktrees", MessageLevel::Info);
+            return;
         }
-        KeyCode::Char('?') => app.mode = AppMode::Help,
+        self.prune_wizard = Some(PruneWizard {
+            steps,
+            ..Default::default()
+        });
+        self.mode = AppMode::PruneWizard;
+    }
 
-        _ => {}
+    fn prune_wizard_step(&self) -> Option<&PruneStep> {
+        let wizard = self.prune_wizard.as_ref()?;
+        wizard.steps.get(wizard.current)
     }
-    Ok(())
-}
 
-fn handle_help_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::Enter => {
-            app.mode = AppMode::Normal;
+    /// Drop the current worktree's metadata, like `X` does for all of them
+    fn prune_wizard_prune(&mut self) -> Result<()> {
+        let Some(path) = self.prune_wizard_step().map(|step| step.path.clone()) else {
+            return Ok(());
+        };
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args(["worktree", "remove"])
+            .arg(&path)
+            .output()?;
+        let Some(wizard) = self.prune_wizard.as_mut() else {
+            return Ok(());
+        };
+        if output.status.success() {
+            wizard.pruned += 1;
+            self.advance_prune_wizard()?;
+        } else {
+            wizard.error = Some(String::from_utf8_lossy(&output.stderr).trim().to_string());
         }
-        _ => {}
+        Ok(())
     }
-    Ok(())
-}
 
-fn handle_error_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
-            app.mode = AppMode::Normal;
-            app.error_message.clear();
+    /// Reconnect the current worktree with the repository. One whose directory is
+    /// gone was probably moved, so that asks for the new location first.
+    fn prune_wizard_repair(&mut self) -> Result<()> {
+        let Some(path) = self.prune_wizard_step().map(|step| step.path.clone()) else {
+            return Ok(());
+        };
+        let Some(wizard) = self.prune_wizard.as_mut() else {
+            return Ok(());
+        };
+        let target = if wizard.repairing {
+            PathBuf::from(wizard.repair_input.trim())
+        } else if path.exists() {
+            path
+        } else {
+            wizard.repairing = true;
+            wizard.repair_input = path.display().to_string();
+            wizard.repair_cursor = wizard.repair_input.len();
+            return Ok(());
+        };
+        if !target.is_dir() {
+            wizard.error = Some(format!("{} is not a directory", target.display()));
+            return Ok(());
         }
-        KeyCode::Char('y') => {
-            let error = app.error_message.clone();
-            app.copy_text_to_clipboard(&error);
-            app.mode = AppMode::Normal;
-            app.error_message.clear();
-            app.set_status("Error copied to clipboard", MessageLevel::Success);
+
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args(["worktree", "repair"])
+            .arg(&target)
+            .output()?;
+        let Some(wizard) = self.prune_wizard.as_mut() else {
+            return Ok(());
+        };
+        if output.status.success() {
+            wizard.repaired += 1;
+            self.advance_prune_wizard()?;
+        } else {
+            wizard.error = Some(String::from_utf8_lossy(&output.stderr).trim().to_string());
         }
-        _ => {}
+        Ok(())
     }
-    Ok(())
-}
 
-fn handle_create_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
-    match key {
-        KeyCode::Esc => {
-            app.mode = AppMode::Normal;
-            app.create_input.clear();
-            app.create_checkout_existing = false;
-        }
-        KeyCode::Enter => app.create_worktree()?,
-        KeyCode::BackTab => {
-            app.create_checkout_existing = !app.create_checkout_existing;
-        }
-        KeyCode::Tab => {
-            app.mode = AppMode::BranchSelect;
-            app.branch_list_state.select(Some(0));
-        }
-        KeyCode::Backspace => {
-            if app.create_cursor > 0 {
-                app.create_input.remove(app.create_cursor - 1);
-                app.create_cursor -= 1;
-            }
-        }
-        KeyCode::Left => app.create_cursor = app.create_cursor.saturating_sub(1),
-        KeyCode::Right => app.create_cursor = (app.create_cursor + 1).min(app.create_input.len()),
-        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
-            app.create_input.clear();
-            app.create_cursor = 0;
-        }
-        KeyCode::Char(c) => {
-            app.create_input.insert(app.create_cursor, c);
-            app.create_cursor += 1;
+    /// Move on to the next prunable worktree, finishing after the last one
+    fn advance_prune_wizard(&mut self) -> Result<()> {
+        let Some(wizard) = self.prune_wizard.as_mut() else {
+            return Ok(());
+        };
+        wizard.current += 1;
+        wizard.repairing = false;
+        wizard.error = None;
+        if wizard.current >= wizard.steps.len() {
+            self.finish_prune_wizard()?;
         }
-        _ => {}
+        Ok(())
     }
-    Ok(())
-}
 
-fn handle_delete_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
-            app.mode = AppMode::Normal;
-            app.delete_confirm = false;
+    /// Close the wizard and report what it did; unvisited worktrees count as skipped
+    fn finish_prune_wizard(&mut self) -> Result<()> {
+        self.mode = AppMode::Normal;
+        let Some(wizard) = self.prune_wizard.take() else {
+            return Ok(());
+        };
+        let skipped = wizard.skipped + wizard.steps.len().saturating_sub(wizard.current);
+        if wizard.pruned + wizard.repaired > 0 {
+            self.refresh_worktrees()?;
         }
-        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.delete_worktree()?,
-        _ => {}
+        self.set_status(
+            &format!(
+                "Pruned {}, repaired {}, skipped {}",
+                wizard.pruned, wizard.repaired, skipped
+            ),
+            if skipped > 0 {
+                MessageLevel::Info
+            } else {
+                MessageLevel::Success
+            },
+        );
+        Ok(())
     }
-    Ok(())
-}
 
-fn handle_search_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
-    match key {
-        KeyCode::Esc => {
-            app.mode = AppMode::Normal;
-            app.search_query.clear();
-            app.filtered_indices = (0..app.worktrees.len()).collect();
-        }
-        KeyCode::Enter => app.mode = AppMode::Normal,
-        KeyCode::Backspace => {
-            if app.search_cursor > 0 {
-                app.search_query.remove(app.search_cursor - 1);
-                app.search_cursor -= 1;
-                app.update_search_filter();
-            }
-        }
-        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
-            app.search_query.clear();
-            app.search_cursor = 0;
-            app.update_search_filter();
-        }
-        KeyCode::Char(c) => {
-            app.search_query.insert(app.search_cursor, c);
-            app.search_cursor += 1;
-            app.update_search_filter();
+    /// What `git worktree prune` would remove, as (admin dir, reason) pairs
+    fn prune_preview(&self) -> Result<Vec<(String, String)>> {
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args(["worktree", "prune", "--dry-run", "-v"])
+            .output()
+            .context("Failed to run git worktree prune")?;
+        if !output.status.success() {
+            return Err(WttError::git_failed("worktree prune --dry-run", &output).into());
         }
-        _ => {}
+        Ok(parse_prune_output(&output))
     }
-    Ok(())
-}
 
-fn handle_branch_select_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc => app.mode = AppMode::Create,
-        KeyCode::Enter => {
-            if let Some(idx) = app.branch_list_state.selected() {
-                if let Some(branch) = app.available_branches.get(idx) {
-                    app.create_from_branch = Some(branch.name.clone());
-                }
-            }
-            app.mode = AppMode::Create;
-        }
-        KeyCode::Char('j') | KeyCode::Down => {
-            let len = app.available_branches.len();
-            if len > 0 {
-                let current = app.branch_list_state.selected().unwrap_or(0);
-                app.branch_list_state.select(Some((current + 1) % len));
-            }
+    /// Branch and path of the worktree behind an admin dir such as `worktrees/foo`,
+    /// falling back to the admin dir itself once git has lost track of it
+    fn describe_admin_dir(&self, admin: &str) -> String {
+        let common_dir = git_command()
+            .current_dir(&self.repo_root)
+            .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| PathBuf::from(String::from_utf8_lossy(&o.stdout).trim()));
+        // The admin dir's `gitdir` file holds the path of the worktree's `.git` file
+        let path = common_dir
+            .and_then(|dir| std::fs::read_to_string(dir.join(admin).join("gitdir")).ok())
+            .and_then(|gitdir| PathBuf::from(gitdir.trim()).parent().map(Path::to_path_buf));
+        let worktree = path
+            .as_ref()
+            .and_then(|path| self.worktrees.iter().find(|wt| wt.path == *path));
+        match (worktree, path) {
+            (Some(wt), _) => format!(
+                "{}  {}",
+                wt.branch.as_deref().unwrap_or(&wt.commit_short),
+                wt.path.display()
+            ),
+            (None, Some(path)) => path.display().to_string(),
+            (None, None) => admin.to_string(),
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            let len = app.available_branches.len();
-            if len > 0 {
-                let current = app.branch_list_state.selected().unwrap_or(0);
-                app.branch_list_state.select(Some(if current == 0 {
-                    len - 1
-                } else {
-                    current - 1
-                }));
+    }
+
+    fn prune_worktrees(&mut self) -> Result<()> {
+        self.set_status("Pruning stale worktrees...", MessageLevel::Info);
+
+        // git never prunes locked worktrees, even when their directory is gone
+        let locked_skipped = self
+            .worktrees
+            .iter()
+            .filter(|wt| wt.is_locked && !wt.path.exists())
+            .count();
+
+        let args = ["worktree", "prune", "-v"];
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args(args)
+            .output()?;
+
+        if output.status.success() {
+            let pruned: Vec<String> = parse_prune_output(&output)
+                .into_iter()
+                .map(|(admin, _)| {
+                    admin
+                        .strip_prefix("worktrees/")
+                        .unwrap_or(&admin)
+                        .to_string()
+                })
+                .collect();
+            let summary = if pruned.is_empty() {
+                "Nothing to prune".to_string()
+            } else {
+                format!(
+                    "Pruned {} stale worktree{}: {}",
+                    pruned.len(),
+                    if pruned.len() == 1 { "" } else { "s" },
+                    pruned.join(", ")
+                )
+            };
+            if locked_skipped > 0 {
+                self.set_status(
+                    &format!("{} (skipped {} locked)", summary, locked_skipped),
+                    MessageLevel::Warning,
+                );
+            } else {
+                self.set_status(&summary, MessageLevel::Success);
             }
+            self.refresh_worktrees()?;
+        } else {
+            let repo_root = self.repo_root.clone();
+            self.report_git_failure("Prune failed", &args, &repo_root);
         }
-        _ => {}
+        Ok(())
     }
-    Ok(())
-}
 
-fn handle_merge_select_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc => {
-            app.mode = AppMode::Normal;
-            app.merge_source_idx = None;
-            app.merge_target_branch = None;
+    fn perform_merge(&mut self, source_idx: usize, target_branch: String) -> Result<()> {
+        let source_wt = &self.worktrees[source_idx];
+        let source_branch = match &source_wt.branch {
+            Some(b) => b.clone(),
+            None => return Ok(()), // Should be handled by caller
+        };
+
+        if source_branch == target_branch {
+            self.set_status("Cannot merge branch into itself", MessageLevel::Error);
+            return Ok(());
         }
-        KeyCode::Enter => {
-            let target_branch = if let Some(idx) = app.branch_list_state.selected() {
-                app.available_branches.get(idx).map(|b| b.name.clone())
-            } else {
-                None
-            };
 
-            if target_branch.is_some() {
-                app.merge_target_branch = target_branch;
-                app.mode = AppMode::MergeConfirm;
+        // Find a worktree where target_branch is checked out
+        let target_wt_path = self
+            .worktrees
+            .iter()
+            .find(|wt| wt.branch.as_ref() == Some(&target_branch))
+            .map(|wt| wt.path.clone());
+
+        let merge_path = match target_wt_path {
+            Some(path) => path,
+            None => {
+                // If not found, we could potentially try to merge in the main repo
+                // if the main repo can switch to that branch.
+                // For now, let's just support merging into active worktrees.
+                self.set_status(
+                    &format!("Branch {} is not active in any worktree", target_branch),
+                    MessageLevel::Error,
+                );
+                return Ok(());
             }
+        };
+
+        self.set_status(
+            &format!("Merging {} into {}...", source_branch, target_branch),
+            MessageLevel::Info,
+        );
+
+        let mut args = vec!["merge", "--no-edit"];
+        args.extend(self.merge_strategy.flag());
+        args.push(&source_branch);
+        let mut output = git_command()
+            .current_dir(&merge_path)
+            .args(&args)
+            .output()?;
+
+        // --squash only stages the result; commit it with git's prepared message
+        if output.status.success() && self.merge_strategy == MergeStrategy::Squash {
+            args = vec!["commit", "--no-edit"];
+            output = git_command()
+                .current_dir(&merge_path)
+                .args(&args)
+                .output()?;
         }
-        KeyCode::Char('j') | KeyCode::Down => {
-            let len = app.available_branches.len();
-            if len > 0 {
-                let current = app.branch_list_state.selected().unwrap_or(0);
-                app.branch_list_state.select(Some((current + 1) % len));
+
+        self.merge_conflicts = None;
+        if output.status.success() {
+            self.set_status(
+                &format!("Merged {} into {}", source_branch, target_branch),
+                MessageLevel::Success,
+            );
+            self.refresh_worktrees()?;
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            // e.g. "nothing to commit" after a no-op squash only goes to stdout
+            let error = if stderr.trim().is_empty() {
+                stdout
+            } else {
+                stderr
+            };
+            // git reports conflicts on stdout, so ask the index instead of parsing output
+            let conflicts = Self::get_conflicted_files(&merge_path);
+            if !conflicts.is_empty() {
+                self.set_status(
+                    &format!(
+                        "Conflict in {} file{}! Resolve in: {} (M for mergetool)",
+                        conflicts.len(),
+                        if conflicts.len() == 1 { "" } else { "s" },
+                        merge_path.display()
+                    ),
+                    MessageLevel::Warning,
+                );
+                self.merge_conflicts = Some((merge_path, conflicts));
+                self.refresh_worktrees()?;
+            } else {
+                self.report_git_failure(
+                    &format!("Merge failed: {}", error.trim()),
+                    &args,
+                    &merge_path,
+                );
             }
         }
-        KeyCode::Char('k') | KeyCode::Up => {
-            let len = app.available_branches.len();
-            if len > 0 {
-                let current = app.branch_list_state.selected().unwrap_or(0);
-                app.branch_list_state.select(Some(if current == 0 {
-                    len - 1
-                } else {
-                    current - 1
-                }));
+        Ok(())
+    }
+
+    /// Count commits `source` would bring into `target` and whether the merge
+    /// is a fast-forward (target has nothing source lacks)
+    fn get_merge_preview(&self, source: &str, target: &str) -> Option<(usize, bool)> {
+        let output = git_command()
+            .current_dir(&self.repo_root)
+            .args([
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("{}...{}", target, source),
+            ])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let counts = String::from_utf8_lossy(&output.stdout);
+        let mut parts = counts.split_whitespace();
+        let target_only: usize = parts.next()?.parse().ok()?;
+        let source_only: usize = parts.next()?.parse().ok()?;
+        Some((source_only, target_only == 0))
+    }
+
+    /// Commits behind upstream before a worktree is reported as far behind
+    const HEALTH_BEHIND_THRESHOLD: usize = 50;
+
+    /// Check every worktree for common problems and open the report (if any)
+    fn run_health_check(&mut self) {
+        let mut issues = Vec::new();
+        for (idx, wt) in self.worktrees.iter().enumerate() {
+            let mut push = |problem: String, suggestion, level| {
+                issues.push(HealthIssue {
+                    worktree_idx: idx,
+                    problem,
+                    suggestion,
+                    level,
+                })
+            };
+            let name = wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone());
+            let missing = !wt.path.exists();
+
+            if missing && wt.is_locked {
+                push(
+                    format!("{}: locked but its directory is gone", name),
+                    "unlock (L), then prune (X)",
+                    MessageLevel::Error,
+                );
+            } else if missing || wt.is_prunable {
+                push(
+                    format!("{}: directory is missing", name),
+                    "prune stale worktrees (X), or go through them with W",
+                    MessageLevel::Error,
+                );
+            } else if Self::gitdir_link_broken(&wt.path) {
+                push(
+                    format!("{}: .git points to a missing admin directory", name),
+                    "run `git worktree repair`",
+                    MessageLevel::Error,
+                );
+            }
+
+            if let Some(issue) = self.integrity_issues.get(&wt.path) {
+                push(
+                    format!("{}: {}", name, issue),
+                    "remove the duplicate, then run `git worktree repair`",
+                    MessageLevel::Error,
+                );
+            }
+            if wt.is_detached && !wt.is_bare {
+                push(
+                    format!("{}: detached HEAD", name),
+                    "create a branch here (b)",
+                    MessageLevel::Warning,
+                );
+            }
+            if wt.status.ahead > 0 && wt.status.behind > 0 {
+                push(
+                    format!(
+                        "{}: diverged from upstream (↑{} ↓{})",
+                        name, wt.status.ahead, wt.status.behind
+                    ),
+                    "rebase or merge, then push",
+                    MessageLevel::Warning,
+                );
+            } else if wt.status.behind >= Self::HEALTH_BEHIND_THRESHOLD {
+                push(
+                    format!("{}: {} commits behind upstream", name, wt.status.behind),
+                    "pull (p)",
+                    MessageLevel::Warning,
+                );
             }
         }
-        _ => {}
+
+        if issues.is_empty() {
+            self.set_status("Health check: no issues found", MessageLevel::Success);
+            return;
+        }
+        self.health_issues = issues;
+        self.health_list_state.select(Some(0));
+        self.mode = AppMode::Health;
     }
-    Ok(())
-}
 
-fn handle_merge_confirm_mode(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
-            app.mode = AppMode::Normal;
-            app.merge_source_idx = None;
-            app.merge_target_branch = None;
+    /// Flag worktrees with the same branch checked out, or whose `.git` files point to
+    /// the same admin dir; both mean the worktree metadata is corrupted
+    fn check_integrity(&mut self) {
+        let mut by_branch: HashMap<&str, Vec<&Path>> = HashMap::new();
+        let mut by_gitdir: HashMap<PathBuf, Vec<&Path>> = HashMap::new();
+        for wt in &self.worktrees {
+            if let Some(branch) = wt.branch.as_deref().filter(|_| !wt.is_bare) {
+                by_branch.entry(branch).or_default().push(&wt.path);
+            }
+            if let Some(gitdir) = Self::gitdir_of(&wt.path) {
+                by_gitdir.entry(gitdir).or_default().push(&wt.path);
+            }
         }
-        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
-            if let (Some(source_idx), Some(target)) =
-                (app.merge_source_idx, app.merge_target_branch.take())
-            {
-                app.perform_merge(source_idx, target)?;
+
+        let mut issues = HashMap::new();
+        for (branch, paths) in by_branch.into_iter().filter(|(_, p)| p.len() > 1) {
+            for path in &paths {
+                issues.insert(
+                    path.to_path_buf(),
+                    format!(
+                        "{} is also checked out in {} other worktree(s)",
+                        branch,
+                        paths.len() - 1
+                    ),
+                );
             }
-            app.mode = AppMode::Normal;
-            app.merge_source_idx = None;
-            app.merge_target_branch = None;
         }
-        _ => {}
+        for (gitdir, paths) in by_gitdir.into_iter().filter(|(_, p)| p.len() > 1) {
+            for path in &paths {
+                issues.insert(
+                    path.to_path_buf(),
+                    format!(
+                        "shares its admin dir {} with another worktree",
+                        gitdir.display()
+                    ),
+                );
+            }
+        }
+        self.integrity_issues = issues;
     }
-    Ok(())
-}
 
-// ============================================================================
-// UI Rendering
-// ============================================================================
+    /// Admin dir named by a linked worktree's `.git` file
+    fn gitdir_of(path: &Path) -> Option<PathBuf> {
+        let content = std::fs::read_to_string(path.join(".git")).ok()?;
+        let gitdir = content.trim().strip_prefix("gitdir: ")?;
+        Some(canonical_path(&path.join(gitdir)))
+    }
 
-fn ui(frame: &mut Frame, app: &mut App) {
-    let size = frame.area();
+    /// A linked worktree's `.git` file names its admin dir; true if that dir is gone
+    fn gitdir_link_broken(path: &Path) -> bool {
+        let Ok(content) = std::fs::read_to_string(path.join(".git")) else {
+            // The main worktree has a .git directory, not a file
+            return false;
+        };
+        match content.trim().strip_prefix("gitdir: ") {
+            Some(gitdir) => !path.join(gitdir).exists(),
+            None => false,
+        }
+    }
 
-    let main_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(10),
-            Constraint::Length(3),
-        ])
-        .split(size);
+    /// Remember the worktree that was selected before an event moved the selection
+    fn note_selection_change(&mut self, before: Option<PathBuf>) {
+        let after = self.selected_worktree().map(|wt| &wt.path);
+        if before.is_some() && before.as_ref() != after {
+            self.previous_selection = before;
+        }
+    }
 
-    render_header(frame, app, main_chunks[0]);
-    render_content(frame, app, main_chunks[1]);
-    render_status_bar(frame, app, main_chunks[2]);
+    /// Jump back to the previously selected worktree, like `cd -`
+    fn swap_to_previous_selection(&mut self) {
+        let Some(path) = self.previous_selection.clone() else {
+            self.set_status("No previous selection", MessageLevel::Info);
+            return;
+        };
+        match self.worktrees.iter().position(|wt| wt.path == path) {
+            Some(idx) => self.select_worktree(idx),
+            None => {
+                self.previous_selection = None;
+                self.set_status("Previous worktree is gone", MessageLevel::Warning);
+            }
+        }
+    }
 
-    match app.mode {
-        AppMode::Help => render_help_dialog(frame),
-        AppMode::Create => render_create_dialog(frame, app),
-        AppMode::Delete => render_delete_dialog(frame, app),
-        AppMode::BranchSelect => {
-            render_create_dialog(frame, app);
-            render_branch_select_dialog(frame, app, "Select Base Branch");
+    /// Select a worktree by index, clearing the search if it hides the row
+    fn select_worktree(&mut self, idx: usize) {
+        if !self.filtered_indices.contains(&idx) {
+            self.search_query.clear();
+            self.search_cursor = 0;
+            self.filtered_indices = (0..self.worktrees.len()).collect();
         }
-        AppMode::MergeSelect => {
-            render_merge_dialog(frame, app);
+        if let Some(pos) = self.filtered_indices.iter().position(|&i| i == idx) {
+            self.table_state.select(Some(pos));
         }
-        AppMode::MergeConfirm => {
-            render_merge_confirm_dialog(frame, app);
+    }
+
+    /// Files with unresolved merge conflicts in a worktree
+    fn get_conflicted_files(path: &Path) -> Vec<String> {
+        git_command()
+            .current_dir(path)
+            .args(["diff", "--name-only", "--diff-filter=U"])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Drop remembered merge conflicts once they have been resolved or aborted
+    fn recheck_merge_conflicts(&mut self) {
+        if let Some((path, files)) = &mut self.merge_conflicts {
+            *files = Self::get_conflicted_files(path);
+            if files.is_empty() {
+                self.merge_conflicts = None;
+            }
         }
-        AppMode::Search => render_search_bar(frame, app),
-        AppMode::Error => render_error_dialog(frame, app),
-        _ => {}
     }
-}
 
-fn render_header(frame: &mut Frame, app: &App, area: Rect) {
-    let title_block = Block::default()
-        // .borders(Borders::BOTTOM)
-        // .border_type(BorderType::LightDoubleDashed)
-        // .border_style(Style::default().fg(colors::BORDER_INACTIVE))
-        .padding(Padding::horizontal(1));
+    /// Ask `run_app` to hand the terminal to `git mergetool` for the selected
+    /// worktree's conflicts, if a merge tool is configured
+    fn open_mergetool(&mut self) {
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        if self.conflicts_for(wt).is_none() {
+            self.set_status("No merge conflicts in this worktree", MessageLevel::Info);
+            return;
+        }
+        let path = wt.path.clone();
 
-    let inner = title_block.inner(area);
-    frame.render_widget(title_block, area);
+        let configured = git_command()
+            .current_dir(&path)
+            .args(["config", "--get", "merge.tool"])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !configured {
+            self.set_status(
+                "No merge tool configured (git config merge.tool <tool>)",
+                MessageLevel::Warning,
+            );
+            return;
+        }
+        self.mergetool_path = Some(path);
+    }
 
-    let header_layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(inner);
+    /// Conflicted files for a worktree, if the last merge left it conflicted
+    fn conflicts_for(&self, worktree: &Worktree) -> Option<&[String]> {
+        self.merge_conflicts
+            .as_ref()
+            .filter(|(path, _)| *path == worktree.path)
+            .map(|(_, files)| files.as_slice())
+    }
 
-    let logo = Line::from(vec![
-        Span::styled("  ", Style::default().fg(colors::CLAUDE_ORANGE)),
-        Span::styled("Worktree TUI", Style::default().fg(colors::CLAUDE_CREAM)),
-        Span::raw(" "),
-        Span::styled(":: ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    /// Work out the repo's main branch, and where that answer came from: the
+    /// `main_branch` setting, origin/HEAD, `init.defaultBranch`, a conventional name,
+    /// or whatever the main worktree has checked out
+    fn detect_main_branch(&self) -> (String, &'static str) {
+        if let Some(branch) = self.config.main_branch.as_deref().map(str::trim) {
+            if !branch.is_empty() {
+                return (branch.to_string(), "config");
+            }
+        }
+
+        let git_output = |args: &[&str]| {
+            git_command()
+                .current_dir(&self.repo_root)
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|out| !out.is_empty())
+        };
+        let branch_exists = |branch: &str| {
+            git_output(&[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("refs/heads/{}", branch),
+            ])
+            .is_some()
+        };
+
+        if let Some(branch) = git_output(&["symbolic-ref", "refs/remotes/origin/HEAD", "--short"])
+            .and_then(|b| b.strip_prefix("origin/").map(str::to_string))
+        {
+            return (branch, "origin/HEAD");
+        }
+        if let Some(branch) =
+            git_output(&["config", "--get", "init.defaultBranch"]).filter(|b| branch_exists(b))
+        {
+            return (branch, "init.defaultBranch");
+        }
+        for branch in ["main", "master", "develop", "trunk"] {
+            if branch_exists(branch) {
+                return (branch.to_string(), "guessed");
+            }
+        }
+        match self.main_worktree_branch() {
+            Some(branch) => (branch, "main worktree"),
+            None => ("main".to_string(), "default"),
+        }
+    }
+
+    fn update_main_branch(&mut self) {
+        let (branch, source) = self.detect_main_branch();
+        if branch != self.main_branch {
+            info!(%branch, source, "Detected main branch");
+        }
+        self.main_branch = branch;
+        self.main_branch_source = source;
+    }
+
+    fn refresh_merge_branches(&mut self) {
+        let mut branches = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        for wt in &self.worktrees {
+            if let Some(ref name) = wt.branch {
+                if seen.insert(name.clone()) {
+                    branches.push(Branch {
+                        name: name.clone(),
+                        is_remote: false,
+                        is_current: wt.is_main,
+                        commit: wt.commit.clone(),
+                        upstream: wt.status.upstream.clone(),
+                        tracked: false,
+                    });
+                }
+            }
+        }
+
+        // Sort active branches: the main branch first, then alphabetically
+        branches.sort_by(|a, b| {
+            let a_is_main = a.name == self.main_branch;
+            let b_is_main = b.name == self.main_branch;
+            if a_is_main && !b_is_main {
+                std::cmp::Ordering::Less
+            } else if !a_is_main && b_is_main {
+                std::cmp::Ordering::Greater
+            } else {
+                a.name.cmp(&b.name)
+            }
+        });
+
+        self.available_branches = branches;
+    }
+
+    /// Re-read the config file; an invalid config keeps the previous values
+    fn reload_config(&mut self) {
+        let Some(path) = self.config_path.clone() else {
+            self.set_status("No config directory available", MessageLevel::Warning);
+            return;
+        };
+
+        match config::load_config(&path) {
+            Ok(config) => {
+                info!(path = %path.display(), "Config reloaded");
+                let git_error = apply_git_path(&config).err();
+                self.show_readme_preview = config.show_readme_preview;
+                self.show_full_hash = config.show_full_hash;
+                self.show_ignored = config.show_ignored;
+                self.config = config;
+                self.update_main_branch();
+                match git_error {
+                    Some(e) => self.set_status(&format!("{:#}", e), MessageLevel::Warning),
+                    None => self.set_status("Config reloaded", MessageLevel::Success),
+                }
+            }
+            Err(e) => {
+                self.set_status(
+                    &format!("{:#} (keeping previous config)", e),
+                    MessageLevel::Error,
+                );
+            }
+        }
+    }
+
+    /// Start a background refresh of all worktrees
+    fn start_background_refresh(&mut self, tx: &mpsc::UnboundedSender<AppUpdate>) {
+        self.loading_state = LoadingState::Loading;
+        self.refresh_requested = false;
+        self.update_main_branch();
+        spawn_refresh_task(
+            tx.clone(),
+            self.repo_root.clone(),
+            self.current_worktree_path.clone(),
+            self.main_branch.clone(),
+            self.config.parallel_fetch_limit(),
+            self.show_ignored,
+            self.background_cancel.clone(),
+        );
+    }
+
+    /// Run `verify_command` in the selected worktree, streaming its output into a popup.
+    /// While a run is still going, this just shows it again.
+    fn start_verify(&mut self, tx: &mpsc::UnboundedSender<AppUpdate>) {
+        if self.verify_path.is_some() && self.verify_result.is_none() {
+            self.mode = AppMode::Verify;
+            return;
+        }
+        let Some(command) = self
+            .config
+            .verify_command
+            .clone()
+            .filter(|c| !c.trim().is_empty())
+        else {
+            self.set_status(
+                "No verify_command configured (e.g. \"cargo test\")",
+                MessageLevel::Warning,
+            );
+            return;
+        };
+        let Some(wt) = self.selected_worktree() else {
+            return;
+        };
+        if !wt.path.exists() {
+            self.set_status("Worktree directory is missing", MessageLevel::Warning);
+            return;
+        }
+        let path = wt.path.clone();
+        self.verify_title = wt.branch.clone().unwrap_or_else(|| wt.commit_short.clone());
+        self.verify_output = vec![format!("$ {}", command)];
+        self.verify_result = None;
+        self.verify_scroll = 0;
+        self.verify_path = Some(path.clone());
+        self.verify_cancel = Arc::new(AtomicBool::new(false));
+        self.verify_pid = Arc::new(AtomicU32::new(0));
+        spawn_verify_task(
+            tx.clone(),
+            path,
+            command,
+            self.verify_cancel.clone(),
+            self.verify_pid.clone(),
+        );
+        self.mode = AppMode::Verify;
+    }
+
+    /// Stop a running verify command; its output so far stays in the popup
+    fn cancel_verify(&mut self) {
+        if self.verify_path.is_none() || self.verify_result.is_some() {
+            return;
+        }
+        self.verify_cancel.store(true, Ordering::Relaxed);
+        // Right away, rather than when the command next prints something
+        let pid = self.verify_pid.swap(0, Ordering::Relaxed);
+        if pid != 0 {
+            kill_process_group(pid);
+        }
+        self.verify_output.push("[stopped]".into());
+        self.verify_result = Some((None, Duration::ZERO));
+    }
+
+    /// Start a background pull request lookup
+    fn start_pull_request_lookup(&mut self, tx: &mpsc::UnboundedSender<AppUpdate>) {
+        self.pull_requests_fetched_at = cache::now_secs();
+        spawn_pull_request_task(
+            tx.clone(),
+            self.repo_root.clone(),
+            self.background_cancel.clone(),
+        );
+    }
+
+    /// Abort in-flight background tasks; returns false if nothing was running.
+    /// Cancelled tasks drop their results, so the current data stays as is.
+    fn cancel_background_tasks(&mut self) -> bool {
+        if self.loading_state != LoadingState::Loading {
+            return false;
+        }
+        self.background_cancel.store(true, Ordering::Relaxed);
+        // Fresh flag so tasks started from now on are unaffected
+        self.background_cancel = Arc::new(AtomicBool::new(false));
+        self.loading_state = LoadingState::Idle;
+        self.set_status("Cancelled background refresh", MessageLevel::Warning);
+        true
+    }
+
+    /// Queue a blocking operation; `run_app` draws the busy state before running it
+    fn queue_op(&mut self, op: PendingOp) {
+        // Every queued op changes the repository
+        if self.is_read_only() {
+            self.set_status("Read-only mode", MessageLevel::Warning);
+            return;
+        }
+        self.set_status(&op.label(), MessageLevel::Info);
+        self.pending_op = Some(op);
+    }
+
+    /// Run a queued blocking operation with the spinner showing
+    fn run_pending_op(&mut self, op: PendingOp) {
+        let previous_state = self.loading_state;
+        self.loading_state = LoadingState::Loading;
+        self.op_failed = false;
+
+        let result = match op {
+            PendingOp::Pull => self.pull_current(),
+            PendingOp::Push => self.push_current(),
+            PendingOp::FetchAll => self.fetch_all(),
+            PendingOp::FetchSelected => self.fetch_selected(),
+            PendingOp::Prune => self.prune_worktrees(),
+            PendingOp::FastForwardMain => self.fast_forward_main(),
+            PendingOp::SyncAll => self.sync_all_worktrees(),
+            PendingOp::Delete => self.delete_worktree(false),
+            PendingOp::UnlockAndDelete => self.delete_worktree(true),
+            PendingOp::Commit => self.commit_staged(),
+            PendingOp::Merge(source_idx, target) => self.perform_merge(source_idx, target),
+            PendingOp::PostCreate(path, template) => self.run_post_create(&path, &template),
+            PendingOp::SetUpstream(path, upstream) => self.set_upstream(&path, &upstream),
+        };
+
+        // A background refresh may still be in flight; leave its spinner running
+        self.loading_state = previous_state;
+        let failed = result.is_err() || self.op_failed;
+        if let Err(e) = result {
+            self.report_error(&e);
+        }
+        self.ring_bell(failed);
+    }
+
+    /// Ring the terminal bell for a finished operation, if `bell_on_complete` asks for it
+    fn ring_bell(&self, failed: bool) {
+        let ring = match self.config.bell_on_complete {
+            config::BellOn::Off => false,
+            config::BellOn::Error => failed,
+            config::BellOn::All => true,
+        };
+        if ring {
+            let mut stdout = io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+        }
+    }
+
+    fn cycle_sort(&mut self) {
+        self.set_sort_order(self.sort_order.next());
+    }
+
+    /// Sort by `order`, keeping the selected worktree selected
+    fn set_sort_order(&mut self, order: SortOrder) {
+        self.sort_order = order;
+        // keep selection on the same worktree if possible
+        // get currently selected worktrees name
+        let selected_wt_name = self.selected_worktree().and_then(|wt| wt.branch.clone());
+        self.apply_sort();
+        self.filtered_indices = (0..self.worktrees.len()).collect();
+        // restore selection
+        if let Some(name) = selected_wt_name {
+            if let Some(pos) = self
+                .worktrees
+                .iter()
+                .position(|wt| wt.branch.as_ref() == Some(&name))
+            {
+                if let Some(filtered_pos) = self.filtered_indices.iter().position(|&idx| idx == pos)
+                {
+                    self.table_state.select(Some(filtered_pos));
+                }
+            }
+        }
+        if !self.search_query.is_empty() {
+            self.update_search_filter();
+        }
+        self.set_status(
+            &format!("Sorted by {}", self.sort_order.label()),
+            MessageLevel::Info,
+        );
+    }
+}
+
+// ============================================================================
+// Event Handling
+// ============================================================================
+
+fn handle_mouse_event(app: &mut App, mouse: crossterm::event::MouseEvent) -> Result<()> {
+    if app.mode != AppMode::Normal {
+        app.set_hovered_row(None);
+        return Ok(());
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(pos) = app.worktree_row_at(mouse.column, mouse.row) {
+                app.table_state.select(Some(pos));
+            }
+        }
+        // Only the row is tracked; the summary is built when drawing, so moving
+        // within a row or over the same row again costs nothing extra
+        MouseEventKind::Moved => {
+            let row = app.worktree_row_at(mouse.column, mouse.row);
+            app.set_hovered_row(row);
+        }
+        MouseEventKind::ScrollDown => app.move_selection(1),
+        MouseEventKind::ScrollUp => app.move_selection(-1),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_hint_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc => app.mode = AppMode::Normal,
+        KeyCode::Backspace => {
+            app.hint_input.pop();
+        }
+        KeyCode::Char(c) => {
+            app.hint_input.push(c.to_ascii_lowercase());
+            let target = app
+                .hint_labels
+                .iter()
+                .find(|(label, _)| *label == app.hint_input)
+                .map(|&(_, pos)| pos);
+            if let Some(pos) = target {
+                app.table_state.select(Some(pos));
+                app.mode = AppMode::Normal;
+            } else if !app
+                .hint_labels
+                .iter()
+                .any(|(label, _)| label.starts_with(&app.hint_input))
+            {
+                app.set_status(
+                    &format!("No row labeled '{}'", app.hint_input),
+                    MessageLevel::Warning,
+                );
+                app.mode = AppMode::Normal;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Normal-mode keys that change the repository, refused in read-only mode
+fn is_mutating_key(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::Delete
+            | KeyCode::Char(
+                'n' | 'N'
+                    | 'x'
+                    | 'p'
+                    | 'P'
+                    | 'f'
+                    | 'F'
+                    | 'U'
+                    | 'A'
+                    | 'X'
+                    | 'W'
+                    | 'm'
+                    | 'M'
+                    | 'L'
+                    | 'S'
+                    | 'c'
+                    | 'b'
+                    | 'i'
+                    | 'B'
+            )
+    )
+}
+
+fn handle_normal_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    if app.is_read_only() && !modifiers.contains(KeyModifiers::CONTROL) && is_mutating_key(key) {
+        app.set_status("Read-only mode", MessageLevel::Warning);
+        return Ok(());
+    }
+
+    match key {
+        // Quit
+        KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
+
+        // Navigation
+        KeyCode::Char('j') | KeyCode::Down => app.move_selection(1),
+        KeyCode::Char('k') | KeyCode::Up => app.move_selection(-1),
+        KeyCode::Char('g') => app.select_first(),
+        KeyCode::Char('G') => app.select_last(),
+        KeyCode::Char('J') => app.start_hint_mode(),
+        KeyCode::Char('-') => app.swap_to_previous_selection(),
+        KeyCode::Char('V') => app.mini_view = !app.mini_view,
+        KeyCode::Char('B') if app.action_available(Action::PullPush) => app.open_upstream_picker(),
+        KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => app.move_selection(5),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => app.move_selection(-5),
+        KeyCode::Home => app.select_first(),
+        KeyCode::End => app.select_last(),
+        KeyCode::PageDown => app.move_selection(10),
+        KeyCode::PageUp => app.move_selection(-10),
+
+        // Quick jump (multi-digit numbers when typed quickly)
+        KeyCode::Char(c) if c.is_ascii_digit() => app.push_jump_digit(c),
+
+        KeyCode::Tab => {}
+
+        // Actions
+        KeyCode::Char('n') | KeyCode::Char('N') if app.repo_is_empty() => app.set_status(
+            "No commits yet, make the first commit before adding worktrees",
+            MessageLevel::Warning,
+        ),
+        KeyCode::Char('n') | KeyCode::Char('N') => {
+            let prefill_current = matches!(key, KeyCode::Char('N'));
+            app.mode = AppMode::Create;
+            app.create_input.clear();
+            app.create_cursor = 0;
+            app.create_from_branch = if prefill_current {
+                app.worktrees
+                    .iter()
+                    .find(|wt| wt.is_current)
+                    .and_then(|wt| wt.branch.clone())
+            } else {
+                None
+            };
+            app.create_mode = CreateMode::NewBranch;
+            app.create_template = None;
+            app.create_lock = false;
+            app.create_dir_warning = app.check_worktrees_dir_writable();
+            let _ = app.refresh_branches();
+        }
+        KeyCode::Char('x') | KeyCode::Delete if app.selected_worktree().is_some() => {
+            app.mode = AppMode::Delete;
+            app.delete_confirm = false;
+        }
+        KeyCode::Enter => match app.config.enter_action {
+            config::EnterAction::ShowPath => app.show_selected_path(),
+            config::EnterAction::Cd => app.cd_into_selected(),
+            config::EnterAction::OpenEditor => {
+                app.editor_path = app.selected_worktree().map(|wt| wt.path.clone());
+            }
+            config::EnterAction::OpenTerminal => app.open_in_terminal(),
+        },
+        KeyCode::Char('o') => app.show_selected_path(),
+        KeyCode::Char('D') => app.log_requested = true,
+
+        // Change directory to selected worktree (for shell integration)
+        KeyCode::Char(' ') => app.cd_into_selected(),
+
+        // New features
+        KeyCode::Char('y') => app.copy_path_to_clipboard(),
+        KeyCode::Char('Y') => app.copy_commit_message_to_clipboard(),
+        KeyCode::Char('E') => app.copy_setup_command(),
+        KeyCode::Char('d') => app.open_merge_base_diff(),
+        KeyCode::Char('O') => app.open_in_file_manager(),
+        KeyCode::Char('T') if app.action_available(Action::CdInto) => app.open_in_tmux(),
+        KeyCode::Char('p') if app.selected_worktree().is_some() => app.queue_op(PendingOp::Pull),
+        KeyCode::Char('P') if app.selected_worktree().is_some() => app.queue_op(PendingOp::Push),
+        KeyCode::Char('s') => app.cycle_sort(),
+        KeyCode::Char('z') => app.cycle_group(),
+        KeyCode::Char('a') => app.cycle_path_display(),
+        KeyCode::Char('i') => app.open_import_dialog(),
+        KeyCode::Char('S') => {
+            let _ = app.toggle_sparse_checkout();
+        }
+        KeyCode::Char('c') => app.open_commit_dialog(),
+        KeyCode::Char('b') => app.open_new_branch_dialog(),
+        KeyCode::Char('e') => app.open_note_dialog(),
+        KeyCode::Char('H') => app.run_health_check(),
+        KeyCode::Char('h') => app.show_key_hints = true,
+        KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.history_oldest_first = !app.history_oldest_first
+        }
+        KeyCode::Char('t') => app.show_recent_commits = !app.show_recent_commits,
+        KeyCode::Char('v') => app.show_readme_preview = !app.show_readme_preview,
+        KeyCode::Char('#') => app.show_full_hash = !app.show_full_hash,
+        KeyCode::Char('L') => {
+            let _ = app.toggle_lock();
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            let _ = app.refresh_worktrees();
+        }
+        KeyCode::Char('u') => app.refresh_selected_worktree(),
+        KeyCode::Char('F') => app.queue_op(PendingOp::FetchAll),
+        KeyCode::Char('f') if app.action_available(Action::PullPush) => {
+            app.queue_op(PendingOp::FetchSelected)
+        }
+        KeyCode::Char('U') => app.queue_op(PendingOp::FastForwardMain),
+        KeyCode::Char('A') => app.request_sync_all(),
+        KeyCode::Char('M') => app.open_mergetool(),
+        KeyCode::Char('X') => app.request_prune(),
+        KeyCode::Char('W') => app.open_prune_wizard(),
+        KeyCode::Char('m') => {
+            if let Some(wt) = app.selected_worktree() {
+                if wt.is_main && wt.branch.as_deref() == Some(app.main_branch.as_str()) {
+                    // It's the main branch in the main worktree,
+                    // we can allow merging from it if the user wants to merge into something else.
+                }
+
+                if wt.branch.is_none() {
+                    app.set_status("Cannot merge detached HEAD", MessageLevel::Error);
+                } else if wt.branch_is_lossy() {
+                    app.set_status(
+                        "Branch name is not valid UTF-8, merge it with git directly",
+                        MessageLevel::Warning,
+                    );
+                } else {
+                    let idx = app.table_state.selected().unwrap();
+                    app.merge_source_idx = Some(app.filtered_indices[idx]);
+                    app.mode = AppMode::MergeSelect;
+                    app.refresh_merge_branches();
+                    app.branch_list_state.select(Some(0));
+                }
+            }
+        }
+
+        KeyCode::Char('C') => app.reload_config(),
+        KeyCode::Char('w') => {
+            app.mode = AppMode::RepoSelect;
+            let current = app
+                .repo_history
+                .repos
+                .iter()
+                .position(|r| r.path == app.repo_root);
+            app.repo_list_state.select(current.or(Some(0)));
+        }
+
+        KeyCode::Char(':') => {
+            app.mode = AppMode::Command;
+            app.command_input.clear();
+            app.command_cursor = 0;
+        }
+        KeyCode::Char('/') => {
+            app.mode = AppMode::Search;
+            app.search_query.clear();
+            app.search_cursor = 0;
+        }
+        KeyCode::Char('?') => app.mode = AppMode::Help,
+
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_help_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') | KeyCode::Enter => {
+            app.mode = AppMode::Normal;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_error_mode(
+    app: &mut App,
+    key: KeyCode,
+    tx: &mpsc::UnboundedSender<AppUpdate>,
+) -> Result<()> {
+    match key {
+        KeyCode::Char('u') if matches!(app.error_action, Some(ErrorAction::UnlockAndDelete)) => {
+            app.error_message.clear();
+            app.error_action = None;
+            app.mode = AppMode::Delete;
+            app.queue_op(PendingOp::UnlockAndDelete);
+        }
+        KeyCode::Char('i') if matches!(app.error_action, Some(ErrorAction::InitRepo(_))) => {
+            let Some(ErrorAction::InitRepo(path)) = app.error_action.take() else {
+                return Ok(());
+            };
+            app.mode = AppMode::Normal;
+            app.error_message.clear();
+            match app.init_repo(&path) {
+                Ok(true) => app.start_background_refresh(tx),
+                Ok(false) => {}
+                Err(e) => app.report_error(&e),
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+            app.mode = AppMode::Normal;
+            app.error_message.clear();
+        }
+        KeyCode::Char('y') => {
+            let error = app.error_message.clone();
+            app.copy_text_to_clipboard(&error);
+            app.mode = AppMode::Normal;
+            app.error_message.clear();
+            app.set_status("Error copied to clipboard", MessageLevel::Success);
+        }
+        KeyCode::Char('Y') => {
+            let report = app.error_diagnostics();
+            app.copy_text_to_clipboard(&report);
+            app.mode = AppMode::Normal;
+            app.error_message.clear();
+            app.set_status("Error report copied to clipboard", MessageLevel::Success);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_create_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.create_input.clear();
+            app.create_mode = CreateMode::NewBranch;
+            app.create_template = None;
+            app.create_lock = false;
+        }
+        KeyCode::Enter => app.create_worktree()?,
+        KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.cycle_create_template()
+        }
+        KeyCode::Char('l') if modifiers.contains(KeyModifiers::CONTROL) => {
+            app.create_lock = !app.create_lock
+        }
+        KeyCode::BackTab => {
+            app.create_mode = app.create_mode.next();
+        }
+        // A detached worktree has no branch to pick
+        KeyCode::Tab if app.create_mode != CreateMode::Detached => {
+            app.mode = AppMode::BranchSelect;
+            app.clear_branch_query();
+            app.branch_list_state.select(Some(0));
+        }
+        _ => {
+            edit_text_input(
+                &mut app.create_input,
+                &mut app.create_cursor,
+                key,
+                modifiers,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_delete_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.mode = AppMode::Normal;
+            app.delete_confirm = false;
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.queue_op(PendingOp::Delete),
+        KeyCode::Char('u') if app.selected_worktree().is_some_and(|wt| wt.is_locked) => {
+            app.queue_op(PendingOp::UnlockAndDelete)
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_commit_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.commit_input.clear();
+            app.commit_cursor = 0;
+        }
+        KeyCode::Enter if app.commit_input.trim().is_empty() => {
+            app.set_status("Commit message cannot be empty", MessageLevel::Warning);
+        }
+        KeyCode::Enter => app.queue_op(PendingOp::Commit),
+        _ => {
+            edit_text_input(
+                &mut app.commit_input,
+                &mut app.commit_cursor,
+                key,
+                modifiers,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_new_branch_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.branch_input.clear();
+            app.branch_cursor = 0;
+        }
+        KeyCode::Enter => app.create_branch_here()?,
+        _ => {
+            edit_text_input(
+                &mut app.branch_input,
+                &mut app.branch_cursor,
+                key,
+                modifiers,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_note_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.note_input.clear();
+            app.note_cursor = 0;
+        }
+        KeyCode::Enter => app.save_note(),
+        _ => {
+            edit_text_input(&mut app.note_input, &mut app.note_cursor, key, modifiers);
+        }
+    }
+    Ok(())
+}
+
+/// Byte index of the character before `cursor`; input cursors are byte indices
+/// that always sit on a character boundary
+fn prev_char_boundary(s: &str, cursor: usize) -> usize {
+    s[..cursor]
+        .chars()
+        .next_back()
+        .map_or(0, |c| cursor - c.len_utf8())
+}
+
+/// Byte index of the character after `cursor`
+fn next_char_boundary(s: &str, cursor: usize) -> usize {
+    s[cursor..]
+        .chars()
+        .next()
+        .map_or(cursor, |c| cursor + c.len_utf8())
+}
+
+/// Terminal column of the byte `cursor` in `s`, where wide characters take two
+fn cursor_column(s: &str, cursor: usize) -> u16 {
+    s[..cursor].width() as u16
+}
+
+/// Shared line editing for single-line text inputs; returns false for keys it ignores
+fn edit_text_input(
+    input: &mut String,
+    cursor: &mut usize,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+) -> bool {
+    match key {
+        KeyCode::Backspace if *cursor > 0 => {
+            *cursor = prev_char_boundary(input, *cursor);
+            input.remove(*cursor);
+        }
+        KeyCode::Left => *cursor = prev_char_boundary(input, *cursor),
+        KeyCode::Right => *cursor = next_char_boundary(input, *cursor),
+        KeyCode::Home => *cursor = 0,
+        KeyCode::End => *cursor = input.len(),
+        KeyCode::Char('a') if modifiers.contains(KeyModifiers::CONTROL) => *cursor = 0,
+        KeyCode::Char('e') if modifiers.contains(KeyModifiers::CONTROL) => *cursor = input.len(),
+        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+            input.clear();
+            *cursor = 0;
+        }
+        KeyCode::Char('k') if modifiers.contains(KeyModifiers::CONTROL) => input.truncate(*cursor),
+        // Unbound Ctrl combinations are not text
+        KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => {
+            input.insert(*cursor, c);
+            *cursor += c.len_utf8();
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn handle_import_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => app.close_import_dialog(),
+        KeyCode::Enter => app.import_worktree()?,
+        KeyCode::Tab | KeyCode::BackTab => {
+            app.import_branch_focused = !app.import_branch_focused;
+        }
+        _ if app.import_branch_focused => {
+            edit_text_input(
+                &mut app.import_branch_input,
+                &mut app.import_branch_cursor,
+                key,
+                modifiers,
+            );
+        }
+        _ => {
+            edit_text_input(
+                &mut app.import_path_input,
+                &mut app.import_path_cursor,
+                key,
+                modifiers,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_command_mode(
+    app: &mut App,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+    tx: &mpsc::UnboundedSender<AppUpdate>,
+) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.command_input.clear();
+            app.command_cursor = 0;
+        }
+        // Backspace on an empty prompt leaves it, like in vim
+        KeyCode::Backspace if app.command_input.is_empty() => app.mode = AppMode::Normal,
+        KeyCode::Enter => {
+            app.mode = AppMode::Normal;
+            let line = std::mem::take(&mut app.command_input);
+            app.command_cursor = 0;
+            run_command(app, &line, tx)?;
+        }
+        _ => {
+            edit_text_input(
+                &mut app.command_input,
+                &mut app.command_cursor,
+                key,
+                modifiers,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Commands accepted by the `:` prompt, with their arguments
+const COMMANDS: &[(&str, &str)] = &[
+    ("create", "<name> [base]"),
+    ("delete", ""),
+    ("merge", "<target>"),
+    ("sort", "name|status|recent|frecency"),
+    ("group", "none|status|merged|remote"),
+    ("fetch", ""),
+    ("pull", ""),
+    ("push", ""),
+    ("sync", ""),
+    ("prune", ""),
+    ("refresh", ""),
+    ("quit", ""),
+];
+
+/// Run a `:` command line by mapping it onto the matching action
+fn run_command(app: &mut App, line: &str, tx: &mpsc::UnboundedSender<AppUpdate>) -> Result<()> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return Ok(());
+    };
+    let args: Vec<&str> = words.collect();
+    if app.is_read_only()
+        && matches!(
+            command,
+            "create" | "delete" | "merge" | "fetch" | "pull" | "push" | "sync" | "prune"
+        )
+    {
+        app.set_status("Read-only mode", MessageLevel::Warning);
+        return Ok(());
+    }
+    let usage = |name: &str| {
+        COMMANDS
+            .iter()
+            .find(|(c, _)| *c == name)
+            .map(|(c, a)| format!("Usage: :{} {}", c, a))
+            .unwrap_or_default()
+    };
+
+    match (command, args.as_slice()) {
+        ("create", [name, base @ ..]) if base.len() <= 1 => {
+            app.create_input = name.to_string();
+            app.create_mode = CreateMode::NewBranch;
+            app.create_from_branch = base.first().map(|b| b.to_string());
+            app.create_template = None;
+            app.create_lock = false;
+            // The existing-branch check reads the branch list
+            let _ = app.refresh_branches();
+            app.create_worktree()?;
+            // create_worktree only resets the dialog state on success, and the
+            // existing-branch prompt still needs it
+            if app.mode != AppMode::BranchExists {
+                app.create_input.clear();
+                app.create_cursor = 0;
+                app.create_from_branch = None;
+            }
+        }
+        ("delete", []) if app.selected_worktree().is_some() => {
+            app.mode = AppMode::Delete;
+            app.delete_confirm = false;
+        }
+        ("merge", [target]) => {
+            let Some(wt) = app.selected_worktree() else {
+                return Ok(());
+            };
+            let Some(source) = wt.branch.clone() else {
+                app.set_status("Cannot merge detached HEAD", MessageLevel::Error);
+                return Ok(());
+            };
+            if wt.branch_is_lossy() {
+                app.set_status(
+                    "Branch name is not valid UTF-8, merge it with git directly",
+                    MessageLevel::Warning,
+                );
+                return Ok(());
+            }
+            if !app
+                .worktrees
+                .iter()
+                .any(|w| w.branch.as_deref() == Some(*target))
+            {
+                app.set_status(
+                    &format!("'{}' is not checked out in any worktree", target),
+                    MessageLevel::Error,
+                );
+                return Ok(());
+            }
+            let source_idx = app.filtered_indices[app.table_state.selected().unwrap_or(0)];
+            let target = target.to_string();
+            if app.config.confirm_merge {
+                app.merge_preview = app.get_merge_preview(&source, &target);
+                app.merge_source_idx = Some(source_idx);
+                app.merge_target_branch = Some(target);
+                app.mode = AppMode::MergeConfirm;
+            } else {
+                app.queue_op(PendingOp::Merge(source_idx, target));
+            }
+        }
+        ("sort", [order]) => match SortOrder::from_label(order) {
+            Some(order) => app.set_sort_order(order),
+            None => app.set_status(&usage("sort"), MessageLevel::Error),
+        },
+        ("group", [group_by]) => match GroupBy::from_label(group_by) {
+            Some(group_by) => app.set_group_by(group_by),
+            None => app.set_status(&usage("group"), MessageLevel::Error),
+        },
+        ("fetch", []) => app.queue_op(PendingOp::FetchAll),
+        ("pull", []) if app.selected_worktree().is_some() => app.queue_op(PendingOp::Pull),
+        ("push", []) if app.selected_worktree().is_some() => app.queue_op(PendingOp::Push),
+        ("sync", []) => app.request_sync_all(),
+        ("prune", []) => app.request_prune(),
+        ("refresh", []) => {
+            if app.loading_state != LoadingState::Loading {
+                app.start_background_refresh(tx);
+                app.refresh_requested = true;
+                app.set_status("Refreshing...", MessageLevel::Info);
+            }
+        }
+        ("quit" | "q", []) => app.should_quit = true,
+        (command, _) if COMMANDS.iter().any(|(c, _)| *c == command) => {
+            app.set_status(&usage(command), MessageLevel::Error);
+        }
+        (command, _) => {
+            let known: Vec<&str> = COMMANDS.iter().map(|(c, _)| *c).collect();
+            app.set_status(
+                &format!("Unknown command: {} (try {})", command, known.join(", ")),
+                MessageLevel::Error,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn handle_search_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.search_query.clear();
+            app.filtered_indices = (0..app.worktrees.len()).collect();
+        }
+        KeyCode::Enter => app.mode = AppMode::Normal,
+        KeyCode::Tab => {
+            app.search_scope = app.search_scope.next();
+            app.update_search_filter();
+        }
+        _ => {
+            let previous = app.search_query.clone();
+            if edit_text_input(
+                &mut app.search_query,
+                &mut app.search_cursor,
+                key,
+                modifiers,
+            ) && app.search_query != previous
+            {
+                app.update_search_filter();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Branch picker for the create dialog's base branch, and for setting an upstream
+fn handle_branch_select_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let picking_upstream = app.mode == AppMode::UpstreamSelect;
+    if app.branch_query_focused {
+        match key {
+            KeyCode::Esc => {
+                app.clear_branch_query();
+                app.branch_list_state.select(Some(0));
+                return Ok(());
+            }
+            // Picking and moving through the matches work while typing
+            KeyCode::Enter | KeyCode::Up | KeyCode::Down => {}
+            _ => {
+                if edit_text_input(
+                    &mut app.branch_query,
+                    &mut app.branch_query_cursor,
+                    key,
+                    modifiers,
+                ) {
+                    app.branch_list_state.select(Some(0));
+                }
+                return Ok(());
+            }
+        }
+    }
+    match key {
+        KeyCode::Esc if picking_upstream => app.mode = AppMode::Normal,
+        KeyCode::Esc => app.mode = AppMode::Create,
+        KeyCode::Enter => {
+            let selected = app.branch_list_state.selected().and_then(|idx| {
+                app.visible_branches().get(idx).map(|b| {
+                    if picking_upstream {
+                        b.upstream_name().to_string()
+                    } else {
+                        b.name.clone()
+                    }
+                })
+            });
+            if picking_upstream {
+                app.mode = AppMode::Normal;
+                let path = app.selected_worktree().map(|wt| wt.path.clone());
+                if let (Some(path), Some(name)) = (path, selected) {
+                    app.queue_op(PendingOp::SetUpstream(path, name));
+                }
+                return Ok(());
+            }
+            if let Some(name) = selected {
+                app.create_from_branch = Some(name);
+            }
+            app.mode = AppMode::Create;
+        }
+        KeyCode::Char('/') => app.branch_query_focused = true,
+        KeyCode::Char('r') => {
+            app.branch_filter = app.branch_filter.next();
+            app.branch_list_state.select(Some(0));
+            if app.branch_filter != BranchFilter::Local {
+                if let Err(e) = app.load_remote_branches() {
+                    app.set_status(
+                        &format!("Failed to list remote branches: {}", e),
+                        MessageLevel::Error,
+                    );
+                }
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down => {
+            let len = app.visible_branches().len();
+            if len > 0 {
+                let current = app.branch_list_state.selected().unwrap_or(0);
+                app.branch_list_state.select(Some((current + 1) % len));
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            let len = app.visible_branches().len();
+            if len > 0 {
+                let current = app.branch_list_state.selected().unwrap_or(0);
+                app.branch_list_state.select(Some(if current == 0 {
+                    len - 1
+                } else {
+                    current - 1
+                }));
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_merge_select_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc => {
+            app.mode = AppMode::Normal;
+            app.merge_source_idx = None;
+            app.merge_target_branch = None;
+        }
+        KeyCode::Enter => {
+            let target_branch = if let Some(idx) = app.branch_list_state.selected() {
+                app.available_branches.get(idx).map(|b| b.name.clone())
+            } else {
+                None
+            };
+
+            let source_branch = app
+                .merge_source_idx
+                .and_then(|idx| app.worktrees.get(idx))
+                .and_then(|wt| wt.branch.clone());
+
+            if let (Some(source), Some(target)) = (source_branch, target_branch) {
+                if app.config.confirm_merge {
+                    app.merge_preview = app.get_merge_preview(&source, &target);
+                    app.merge_target_branch = Some(target);
+                    app.mode = AppMode::MergeConfirm;
+                } else if let Some(source_idx) = app.merge_source_idx.take() {
+                    app.mode = AppMode::Normal;
+                    app.queue_op(PendingOp::Merge(source_idx, target));
+                }
+            }
+        }
+        KeyCode::Char('s') => app.merge_strategy = app.merge_strategy.next(),
+        KeyCode::Char('j') | KeyCode::Down => {
+            let len = app.available_branches.len();
+            if len > 0 {
+                let current = app.branch_list_state.selected().unwrap_or(0);
+                app.branch_list_state.select(Some((current + 1) % len));
+            }
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            let len = app.available_branches.len();
+            if len > 0 {
+                let current = app.branch_list_state.selected().unwrap_or(0);
+                app.branch_list_state.select(Some(if current == 0 {
+                    len - 1
+                } else {
+                    current - 1
+                }));
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_merge_confirm_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.mode = AppMode::Normal;
+            app.merge_source_idx = None;
+            app.merge_target_branch = None;
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            if let (Some(source_idx), Some(target)) =
+                (app.merge_source_idx, app.merge_target_branch.take())
+            {
+                app.queue_op(PendingOp::Merge(source_idx, target));
+            }
+            app.mode = AppMode::Normal;
+            app.merge_source_idx = None;
+            app.merge_target_branch = None;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_repo_select_mode(
+    app: &mut App,
+    key: KeyCode,
+    tx: &mpsc::UnboundedSender<AppUpdate>,
+) -> Result<()> {
+    let len = app.repo_history.repos.len();
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Normal,
+        KeyCode::Enter => {
+            let selected = app
+                .repo_list_state
+                .selected()
+                .and_then(|idx| app.repo_history.repos.get(idx))
+                .map(|r| r.path.clone());
+            if let Some(path) = selected {
+                app.mode = AppMode::Normal;
+                if path == app.repo_root {
+                    return Ok(());
+                }
+                match app.switch_repo(&path) {
+                    Ok(needs_refresh) => {
+                        if needs_refresh {
+                            app.start_background_refresh(tx);
+                        } else if app.pull_requests_stale() {
+                            app.start_pull_request_lookup(tx);
+                        }
+                    }
+                    Err(e) => {
+                        app.report_error(&e.context(format!("Cannot open {}", path.display())))
+                    }
+                }
+            }
+        }
+        KeyCode::Char('p') => {
+            let selected = app
+                .repo_list_state
+                .selected()
+                .and_then(|idx| app.repo_history.repos.get(idx))
+                .map(|r| r.path.clone());
+            if let Some(path) = selected {
+                let pinned = app.repo_history.toggle_pin(&path);
+                let _ = history::save_history(&app.repo_history);
+                // Keep the cursor on the entry that was just (un)pinned
+                let pos = app.repo_history.repos.iter().position(|r| r.path == path);
+                app.repo_list_state.select(pos);
+                app.set_status(
+                    if pinned {
+                        "Pinned repository"
+                    } else {
+                        "Unpinned repository"
+                    },
+                    MessageLevel::Info,
+                );
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+            let current = app.repo_list_state.selected().unwrap_or(0);
+            app.repo_list_state.select(Some((current + 1) % len));
+        }
+        KeyCode::Char('k') | KeyCode::Up if len > 0 => {
+            let current = app.repo_list_state.selected().unwrap_or(0);
+            app.repo_list_state
+                .select(Some(if current == 0 { len - 1 } else { current - 1 }));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_diff_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let len = app.diff_lines.len();
+    if scroll_text_popup(&mut app.diff_scroll, app.diff_page, len, key, modifiers) {
+        return Ok(());
+    }
+    if matches!(key, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('d')) {
+        app.mode = AppMode::Normal;
+        app.diff_lines.clear();
+    }
+    Ok(())
+}
+
+fn handle_verify_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let len = app.verify_output.len();
+    if scroll_text_popup(&mut app.verify_scroll, app.verify_page, len, key, modifiers) {
+        return Ok(());
+    }
+    match key {
+        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => app.cancel_verify(),
+        // A running command keeps going in the background; `K` shows it again
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('K') => app.mode = AppMode::Normal,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Scrolling keys of the text popups (diff, verify) over `len` lines shown `page` at a
+/// time; returns false for keys that don't scroll
+fn scroll_text_popup(
+    scroll: &mut usize,
+    page: usize,
+    len: usize,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+) -> bool {
+    let max_scroll = len.saturating_sub(page);
+    let half_page = (page / 2).max(1);
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    *scroll = match key {
+        KeyCode::Char('d') if ctrl => *scroll + half_page,
+        KeyCode::Char('u') if ctrl => scroll.saturating_sub(half_page),
+        KeyCode::Char('j') | KeyCode::Down => *scroll + 1,
+        KeyCode::Char('k') | KeyCode::Up => scroll.saturating_sub(1),
+        KeyCode::PageDown | KeyCode::Char(' ') => *scroll + page,
+        KeyCode::PageUp => scroll.saturating_sub(page),
+        KeyCode::Char('g') | KeyCode::Home => 0,
+        KeyCode::Char('G') | KeyCode::End => max_scroll,
+        _ => return false,
+    }
+    .min(max_scroll);
+    true
+}
+
+fn handle_exclude_confirm_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.mode = AppMode::Normal;
+            app.exclude_pattern.clear();
+            app.exclude_declined = true;
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            app.mode = AppMode::Normal;
+            app.exclude_worktrees_dir();
+        }
+        _ => return Ok(()),
+    }
+    // A failed exclude leaves its error up; the cd still happens on the next quit
+    if std::mem::take(&mut app.quit_after_exclude) && app.mode == AppMode::Normal {
+        app.should_quit = true;
+    }
+    Ok(())
+}
+
+fn handle_branch_exists_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => app.mode = AppMode::Create,
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            app.mode = AppMode::Create;
+            app.create_mode = CreateMode::CheckoutExisting;
+            app.create_from_branch = Some(app.create_input.trim().to_string());
+            app.create_worktree()?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// `git pull --ff-only` in one worktree, unless that could touch local work
+fn sync_worktree(wt: &Worktree) -> Result<SyncOutcome> {
+    if wt.status.upstream_gone {
+        return Ok(SyncOutcome::UpstreamGone);
+    }
+    // Untracked files are left alone by a fast-forward, unless it would overwrite them
+    let status = git_command()
+        .current_dir(&wt.path)
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()?;
+    if !status.stdout.is_empty() {
+        return Ok(SyncOutcome::Dirty);
+    }
+
+    // git's messages are localised, so compare commits rather than parse them
+    let head = || -> Result<String> {
+        let output = git_command()
+            .current_dir(&wt.path)
+            .args(["rev-parse", "HEAD"])
+            .output()?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let before = head()?;
+    let output = git_command()
+        .current_dir(&wt.path)
+        .args(["pull", "--ff-only"])
+        .output()?;
+    if output.status.success() {
+        return Ok(if head()? == before {
+            SyncOutcome::UpToDate
+        } else {
+            SyncOutcome::FastForwarded
+        });
+    }
+
+    // The pull fetched the upstream, so exit code 1 here means HEAD isn't behind it
+    let behind = git_command()
+        .current_dir(&wt.path)
+        .args(["merge-base", "--is-ancestor", "HEAD", "@{upstream}"])
+        .status()?;
+    if behind.code() == Some(1) {
+        return Ok(SyncOutcome::Diverged);
+    }
+    let error = String::from_utf8_lossy(&output.stderr);
+    let reason = error
+        .lines()
+        .map(|line| line.trim_start_matches("fatal: ").trim())
+        .find(|line| !line.is_empty())
+        .unwrap_or("git pull failed");
+    Ok(SyncOutcome::Failed(reason.to_string()))
+}
+
+fn handle_sync_report_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    handle_report_mode(
+        app,
+        key,
+        app.sync_results.len(),
+        |app: &mut App| &mut app.sync_list_state,
+        |app: &App, i| {
+            let result = app.sync_results.get(i)?;
+            app.worktrees.iter().position(|wt| wt.path == result.path)
+        },
+    )
+}
+
+fn handle_prune_wizard_mode(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    let Some(wizard) = app.prune_wizard.as_mut() else {
+        app.mode = AppMode::Normal;
+        return Ok(());
+    };
+    if wizard.repairing {
+        match key {
+            KeyCode::Esc => {
+                wizard.repairing = false;
+                wizard.error = None;
+            }
+            KeyCode::Enter => app.prune_wizard_repair()?,
+            _ => {
+                edit_text_input(
+                    &mut wizard.repair_input,
+                    &mut wizard.repair_cursor,
+                    key,
+                    modifiers,
+                );
+            }
+        }
+        return Ok(());
+    }
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => app.finish_prune_wizard()?,
+        KeyCode::Char('p') => app.prune_wizard_prune()?,
+        KeyCode::Char('r') => app.prune_wizard_repair()?,
+        KeyCode::Char('s') => {
+            wizard.skipped += 1;
+            app.advance_prune_wizard()?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_health_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    handle_report_mode(
+        app,
+        key,
+        app.health_issues.len(),
+        |app: &mut App| &mut app.health_list_state,
+        |app: &App, i| app.health_issues.get(i).map(|issue| issue.worktree_idx),
+    )
+}
+
+/// Keys of the per-worktree report popups (health, fast-forward all): move through the
+/// `len` rows, jump to the worktree `worktree_at` gives for one, or close
+fn handle_report_mode(
+    app: &mut App,
+    key: KeyCode,
+    len: usize,
+    list_state: fn(&mut App) -> &mut ListState,
+    worktree_at: fn(&App, usize) -> Option<usize>,
+) -> Result<()> {
+    match key {
+        KeyCode::Esc | KeyCode::Char('q') => app.mode = AppMode::Normal,
+        // Jump to the worktree of the highlighted row
+        KeyCode::Enter => {
+            let idx = list_state(app).selected().and_then(|i| worktree_at(app, i));
+            if let Some(idx) = idx {
+                app.select_worktree(idx);
+            }
+            app.mode = AppMode::Normal;
+        }
+        KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+            let state = list_state(app);
+            let current = state.selected().unwrap_or(0);
+            state.select(Some((current + 1) % len));
+        }
+        KeyCode::Char('k') | KeyCode::Up if len > 0 => {
+            let state = list_state(app);
+            let current = state.selected().unwrap_or(0);
+            state.select(Some(if current == 0 { len - 1 } else { current - 1 }));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_batch_confirm_mode(app: &mut App, key: KeyCode) -> Result<()> {
+    let len = app.batch_confirm.as_ref().map_or(0, |b| b.affected.len());
+    match key {
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.mode = AppMode::Normal;
+            app.batch_confirm = None;
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+            app.mode = AppMode::Normal;
+            if let Some(batch) = app.batch_confirm.take() {
+                app.queue_op(batch.op);
+            }
+        }
+        KeyCode::Char('j') | KeyCode::Down if len > 0 => {
+            let current = app.batch_list_state.selected().unwrap_or(0);
+            app.batch_list_state
+                .select(Some((current + 1).min(len - 1)));
+        }
+        KeyCode::Char('k') | KeyCode::Up => {
+            let current = app.batch_list_state.selected().unwrap_or(0);
+            app.batch_list_state.select(Some(current.saturating_sub(1)));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle a key in the `--pick` picker. Returns Some(selection) once the picker
+/// should close: the chosen path, or None if it was aborted.
+fn handle_picker_key(
+    app: &mut App,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+) -> Option<Option<PathBuf>> {
+    let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+    match key {
+        KeyCode::Esc => return Some(None),
+        KeyCode::Char('c') if ctrl => return Some(None),
+        KeyCode::Enter => return Some(app.selected_worktree().map(|wt| wt.path.clone())),
+        KeyCode::Down => app.move_selection(1),
+        KeyCode::Char('n') | KeyCode::Char('j') if ctrl => app.move_selection(1),
+        KeyCode::Up => app.move_selection(-1),
+        KeyCode::Char('p') | KeyCode::Char('k') if ctrl => app.move_selection(-1),
+        _ => {
+            let previous = app.search_query.clone();
+            if edit_text_input(
+                &mut app.search_query,
+                &mut app.search_cursor,
+                key,
+                modifiers,
+            ) && app.search_query != previous
+            {
+                app.update_search_filter();
+                app.select_first();
+            }
+        }
+    }
+    None
+}
+
+// ============================================================================
+// UI Rendering
+// ============================================================================
+
+fn ui(frame: &mut Frame, app: &mut App) {
+    let size = frame.area();
+
+    if app.mini_view {
+        // Just the list and a one-line status bar; dialogs still open on top
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(size);
+        render_worktree_list(frame, app, chunks[0]);
+        render_status_bar(frame, app, chunks[1]);
+    } else {
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(10),
+                Constraint::Length(3),
+            ])
+            .split(size);
+
+        render_header(frame, app, main_chunks[0]);
+        render_content(frame, app, main_chunks[1]);
+        render_status_bar(frame, app, main_chunks[2]);
+
+        if app.show_key_hints && app.mode == AppMode::Normal {
+            render_key_hints(frame, main_chunks[1]);
+        }
+    }
+
+    match app.mode {
+        AppMode::Help => render_help_dialog(frame),
+        AppMode::Create => render_create_dialog(frame, app),
+        AppMode::Delete => render_delete_dialog(frame, app),
+        AppMode::Commit => render_commit_dialog(frame, app),
+        AppMode::Note => render_note_dialog(frame, app),
+        AppMode::Import => render_import_dialog(frame, app),
+        AppMode::PruneWizard => render_prune_wizard_dialog(frame, app),
+        AppMode::NewBranch => render_new_branch_dialog(frame, app),
+        AppMode::BranchSelect => {
+            render_create_dialog(frame, app);
+            render_branch_select_dialog(frame, app, "Select Base Branch");
+        }
+        AppMode::UpstreamSelect => render_branch_select_dialog(frame, app, "Set Upstream"),
+        AppMode::MergeSelect => {
+            render_merge_dialog(frame, app);
+        }
+        AppMode::MergeConfirm => {
+            render_merge_confirm_dialog(frame, app);
+        }
+        AppMode::Search => render_search_bar(frame, app),
+        AppMode::Command => render_command_bar(frame, app),
+        AppMode::RepoSelect => render_repo_select_dialog(frame, app),
+        AppMode::Health => render_health_dialog(frame, app),
+        AppMode::SyncReport => render_sync_report_dialog(frame, app),
+        AppMode::Diff => render_diff_dialog(frame, app),
+        AppMode::Verify => render_verify_dialog(frame, app),
+        AppMode::ExcludeConfirm => render_exclude_confirm_dialog(frame, app),
+        AppMode::BranchExists => {
+            render_create_dialog(frame, app);
+            render_branch_exists_dialog(frame, app);
+        }
+        AppMode::BatchConfirm => render_batch_confirm_dialog(frame, app),
+        AppMode::Error => render_error_dialog(frame, app),
+        _ => {}
+    }
+
+    if let Some(op) = app.pending_op.as_ref().filter(|op| op.is_destructive()) {
+        render_working_overlay(frame, &op.label());
+    }
+}
+
+/// Small box over everything while a destructive op blocks input
+fn render_working_overlay(frame: &mut Frame, label: &str) {
+    let full = frame.area();
+    let width = (label.chars().count() as u16 + 6).min(full.width);
+    let height = 3.min(full.height);
+    let area = Rect::new(
+        full.x + (full.width - width) / 2,
+        full.y + (full.height - height) / 2,
+        width,
+        height,
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            label,
+            Style::default().fg(colors::CLAUDE_CREAM),
+        ))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .title(Line::from(vec![
+                    Span::raw(" "),
+                    Span::styled(
+                        "Working…",
+                        Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+                    ),
+                    Span::raw(" "),
+                ]))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+                .style(Style::default().bg(colors::CLAUDE_DARKER)),
+        ),
+        area,
+    );
+}
+
+fn render_header(frame: &mut Frame, app: &App, area: Rect) {
+    let icons = icons(app.config.icons);
+    let title_block = Block::default()
+        // .borders(Borders::BOTTOM)
+        // .border_type(BorderType::LightDoubleDashed)
+        // .border_style(Style::default().fg(colors::BORDER_INACTIVE))
+        .padding(Padding::horizontal(1));
+
+    let inner = title_block.inner(area);
+    frame.render_widget(title_block, area);
+
+    let header_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+
+    let mut logo = Line::from(vec![
+        Span::styled("  ", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled("Worktree TUI", Style::default().fg(colors::CLAUDE_CREAM)),
+        Span::raw(" "),
+        Span::styled(":: ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled(
+            &app.repo_name,
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ),
+    ]);
+    let gauge_width = (header_layout[0].width as usize).saturating_sub(logo.width());
+    logo.spans.extend(health_gauge_spans(app, gauge_width));
+    frame.render_widget(Paragraph::new(logo), header_layout[0]);
+
+    // let total = app.worktrees.len();
+    // let dirty = app
+    //     .worktrees
+    //     .iter()
+    //     .filter(|w| !w.status.is_clean())
+    //     .count();
+
+    // let mut stats_spans = vec![Span::styled(
+    //     format!("? untracked ~ modified + staged"),
+    //     Style::default().fg(colors::CLAUDE_WARM_GRAY),
+    // )];
+    let mut stats_spans = vec![
+        Span::styled(" ? ", Style::default().fg(colors::CLAUDE_CREAM)),
+        Span::styled("untracked ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled(" ~ ", Style::default().fg(colors::CLAUDE_CREAM)),
+        Span::styled("modified ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled(" + ", Style::default().fg(colors::CLAUDE_CREAM)),
+        Span::styled("staged ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    ];
+    if app.show_ignored {
+        stats_spans.extend([
+            Span::styled(" ! ", Style::default().fg(colors::CLAUDE_WARM_GRAY).dim()),
+            Span::styled("ignored ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]);
+    }
+
+    // let mut stats_spans = vec![Span::styled(
+    //     format!("{} worktrees", total),
+    //     Style::default().fg(colors::CLAUDE_WARM_GRAY),
+    // )];
+    // if dirty > 0 {
+    //     stats_spans.push(Span::styled(
+    //         format!("  {} dirty", dirty),
+    //         Style::default().fg(colors::WARNING),
+    //     ));
+    // }
+
+    stats_spans.extend([
+        Span::raw("  "),
         Span::styled(
-            &app.repo_name,
+            match app.group_by {
+                GroupBy::None => format!(" {} {}", icons.sort, app.sort_order.label()),
+                group_by => format!(
+                    " {} {} · by {}",
+                    icons.sort,
+                    app.sort_order.label(),
+                    group_by.label()
+                ),
+            },
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ),
+        Span::raw("  "),
+    ]);
+    if app.is_read_only() {
+        stats_spans.extend([
+            Span::styled("read-only", Style::default().fg(colors::WARNING).bold()),
+            Span::raw("  "),
+        ]);
+    }
+    stats_spans.extend([
+        data_source_span(app),
+        Span::raw("  "),
+        Span::styled("?", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" help", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    ]);
+
+    let stats = Line::from(stats_spans).alignment(Alignment::Right);
+    frame.render_widget(Paragraph::new(stats), header_layout[1]);
+}
+
+/// Repo-wide summary (total, dirty, behind, ahead, merged, stashes) for the header, most
+/// important first; segments that don't fit in `width` are dropped from the end
+fn health_gauge_spans(app: &App, width: usize) -> Vec<Span<'static>> {
+    let count = |f: fn(&Worktree) -> bool| app.worktrees.iter().filter(|wt| f(wt)).count();
+    let segments = [
+        (app.worktrees.len(), "worktrees", colors::CLAUDE_WARM_GRAY),
+        (count(|wt| !wt.status.is_clean()), "dirty", colors::WARNING),
+        (count(|wt| wt.status.behind > 0), "behind", colors::ERROR),
+        (count(|wt| wt.status.ahead > 0), "ahead", colors::INFO),
+        (
+            count(|wt| !wt.is_main && wt.unique_commits == Some(0)),
+            "merged",
+            colors::SUCCESS,
+        ),
+        (app.stash_total, "stashed", colors::PURPLE),
+    ];
+
+    let mut spans = Vec::new();
+    let mut used = 0;
+    // The total is always shown; the other counts only when non-zero
+    for (i, (n, label, color)) in segments.into_iter().enumerate() {
+        if i > 0 && n == 0 {
+            continue;
+        }
+        let separator = if spans.is_empty() { "  " } else { " · " };
+        let text = format!("{} {}", n, label);
+        let segment_width = separator.width() + text.width();
+        if used + segment_width > width {
+            break;
+        }
+        used += segment_width;
+        spans.push(Span::styled(
+            separator,
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ));
+        spans.push(Span::styled(text, Style::default().fg(color)));
+    }
+    spans
+}
+
+/// Header marker telling whether the list shows cached or freshly scanned data
+fn data_source_span(app: &App) -> Span<'static> {
+    match app.cached_at {
+        Some(timestamp) => {
+            let age = format_age(cache::now_secs().saturating_sub(timestamp));
+            if app.loading_state == LoadingState::Loading {
+                Span::styled(
+                    format!("cached {} ago, refreshing", age),
+                    Style::default().fg(colors::WARNING),
+                )
+            } else {
+                Span::styled(
+                    format!("cached {} ago", age),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                )
+            }
+        }
+        None => Span::styled(
+            match app.last_refresh_duration {
+                Some(elapsed) => format!("live, scanned in {:.1}s", elapsed.as_secs_f64()),
+                None => "live".to_string(),
+            },
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ),
+    }
+}
+
+/// `git worktree prune -v` reports each removal on stderr as
+/// `Removing worktrees/<name>: <reason>`
+fn parse_prune_output(output: &std::process::Output) -> Vec<(String, String)> {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stderr
+        .lines()
+        .chain(stdout.lines())
+        .filter_map(|line| line.strip_prefix("Removing "))
+        .map(|rest| match rest.split_once(": ") {
+            Some((admin, reason)) => (admin.to_string(), reason.to_string()),
+            None => (rest.to_string(), String::new()),
+        })
+        .collect()
+}
+
+/// Home row first, like vimium
+const HINT_CHARS: &[u8] = b"asdfghjklqwertyuiopzxcvbnm";
+
+/// Labels for `count` rows: single letters while they last, then letter pairs
+fn hint_labels(count: usize) -> Vec<String> {
+    let chars = HINT_CHARS.iter().map(|&c| c as char);
+    if count <= HINT_CHARS.len() {
+        return chars.take(count).map(String::from).collect();
+    }
+    chars
+        .clone()
+        .flat_map(|first| {
+            chars
+                .clone()
+                .map(move |second| format!("{}{}", first, second))
+        })
+        .take(count)
+        .collect()
+}
+
+/// Compact age like `8s`, `5m`, `3h` or `2d`
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h", s / (60 * 60)),
+        s => format!("{}d", s / (24 * 60 * 60)),
+    }
+}
+
+fn render_content(frame: &mut Frame, app: &mut App, area: Rect) {
+    let content_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    render_worktree_list(frame, app, content_chunks[0]);
+    render_details_panel(frame, app, content_chunks[1]);
+}
+
+fn render_worktree_list(frame: &mut Frame, app: &mut App, area: Rect) {
+    let icons = icons(app.config.icons);
+    app.list_area = Some(area);
+
+    let border_color = colors::BORDER_INACTIVE;
+
+    let block = if app.mini_view {
+        Block::default()
+    } else {
+        Block::default()
+            .title(Line::from(vec![
+                Span::raw(" "),
+                Span::styled("Worktrees", Style::default().fg(colors::CLAUDE_CREAM)),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(border_color))
+            .padding(Padding::horizontal(1))
+    };
+
+    let show_author = app.config.show_author_initials;
+    let columns: &[&str] = if show_author {
+        &["#", "", "By", "Branch", "Status", "Commit"]
+    } else {
+        &["#", "", "Branch", "Status", "Commit"]
+    };
+    let header_cells = columns
+        .iter()
+        .map(|h| Cell::from(*h).style(Style::default().fg(colors::CLAUDE_WARM_GRAY)));
+    let header = Row::new(header_cells).height(1).bottom_margin(1);
+    // Column the group headers put their label in
+    let branch_column = columns.iter().position(|&c| c == "Branch").unwrap_or(2);
+
+    // Size the # column to fit the largest row number (or jump hint)
+    let label_width = app.hint_labels.first().map_or(0, |(label, _)| label.len());
+    let num_width =
+        app.filtered_indices
+            .len()
+            .max(1)
+            .to_string()
+            .len()
+            .max(if app.mode == AppMode::Hint {
+                label_width
+            } else {
+                0
+            }) as u16
+            + 1;
+
+    let mut widths = vec![Constraint::Length(num_width), Constraint::Length(2)];
+    if show_author {
+        widths.push(Constraint::Length(2));
+    }
+    widths.extend([
+        Constraint::Min(12),
+        Constraint::Length(12),
+        Constraint::Length(8),
+    ]);
+    let chrome = if app.mini_view { 0 } else { 4 };
+    let branch_width = branch_column_width(area.width, &widths, chrome);
+
+    let mut rows: Vec<Row> = app
+        .filtered_indices
+        .iter()
+        .enumerate()
+        .map(|(display_idx, &idx)| {
+            let wt = &app.worktrees[idx];
+            // get the main worktree too if not main already
+            let main_wt = if wt.is_main {
+                Some(wt)
+            } else {
+                app.worktrees.iter().find(|wt| wt.is_main)
+            };
+
+            if main_wt.is_none() {
+                app.error_message = "No main worktree found!".into();
+                app.mode = AppMode::Error;
+            }
+
+            let hint = app
+                .hint_labels
+                .iter()
+                .find(|(_, pos)| *pos == display_idx)
+                .map(|(label, _)| label);
+            let num = match hint {
+                Some(label) if app.mode == AppMode::Hint => {
+                    if label.starts_with(&app.hint_input) {
+                        let (typed, rest) = label.split_at(app.hint_input.len());
+                        Line::from(vec![
+                            Span::styled(
+                                typed.to_string(),
+                                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                            ),
+                            Span::styled(
+                                rest.to_string(),
+                                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+                            ),
+                        ])
+                    } else {
+                        Line::styled(label, Style::default().fg(colors::BORDER_INACTIVE))
+                    }
+                }
+                _ => Line::styled(
+                    format!("{}", display_idx + 1),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+            };
+
+            let icon = if app.integrity_issues.contains_key(&wt.path) {
+                Span::styled(icons.prunable, Style::default().fg(colors::ERROR))
+            } else if wt.is_current {
+                // Highlight the worktree we're currently in
+                Span::styled(icons.current, Style::default().fg(colors::CLAUDE_CREAM))
+            } else if wt.is_main {
+                Span::styled(icons.main, Style::default().fg(colors::CLAUDE_ORANGE))
+            } else if wt.is_locked {
+                Span::styled(icons.locked, Style::default().fg(colors::WARNING))
+            } else if wt.is_prunable {
+                Span::styled(icons.prunable, Style::default().fg(colors::ERROR))
+            } else {
+                Span::styled(icons.worktree, Style::default().fg(colors::INFO))
+            };
+
+            let branch_name = wt.branch.as_deref().unwrap_or(if wt.is_detached {
+                "(detached)"
+            } else {
+                "(bare)"
+            });
+            let branch_style = if wt.is_main {
+                Style::default().fg(colors::CLAUDE_ORANGE)
+            } else if wt.is_prunable && !wt.is_locked {
+                // Struck through: the next prune (`X`) removes it
+                Style::default().fg(colors::ERROR).crossed_out()
+            } else if wt.is_detached {
+                Style::default().fg(colors::WARNING)
+            } else {
+                Style::default().fg(colors::CLAUDE_CREAM)
+            };
+
+            let status_style = if wt.status.is_clean() {
+                Style::default().fg(colors::SUCCESS)
+            } else if wt.status.is_staged_ready() {
+                Style::default().fg(colors::INFO)
+            } else {
+                Style::default().fg(colors::WARNING)
+            };
+
+            // make commits in table that are matching the main one highlight in purple
+            let commit_style = if wt.is_main {
+                Style::default().fg(colors::PURPLE)
+            } else {
+                match main_wt {
+                    Some(main_wt) if wt.commit == main_wt.commit => {
+                        Style::default().fg(colors::PURPLE)
+                    }
+                    _ => Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                }
+            };
+
+            let mut badges = Vec::new();
+            if let Some(pr) = &wt.pr {
+                badges.push(Span::styled(
+                    format!(" #{}", pr.number),
+                    Style::default().fg(pr_color(pr)),
+                ));
+            }
+            if app.notes.contains_key(&wt.path) {
+                badges.push(Span::styled(" ✎", Style::default().fg(colors::INFO)));
+            }
+            if wt.stash_count > 0 {
+                badges.push(Span::styled(
+                    format!(" ≡{}", wt.stash_count),
+                    Style::default().fg(colors::PURPLE),
+                ));
+            }
+            if wt.status.upstream_gone {
+                badges.push(Span::styled(" gone", Style::default().fg(colors::ERROR)));
+            }
+            let badge_width: usize = badges.iter().map(Span::width).sum();
+            let mut branch_spans = vec![Span::styled(
+                truncate_text(
+                    branch_name,
+                    branch_width.saturating_sub(badge_width),
+                    app.config.branch_truncation,
+                ),
+                branch_style,
+            )];
+            branch_spans.extend(badges);
+
+            let mut cells = vec![Cell::from(num), Cell::from(icon)];
+            if show_author {
+                cells.push(Cell::from(match wt.recent_commits.first() {
+                    Some(head) => Span::styled(
+                        author_initials(&head.author),
+                        Style::default().fg(author_color(&head.author_email)),
+                    ),
+                    None => Span::raw(""),
+                }));
+            }
+            cells.extend([
+                Cell::from(Line::from(branch_spans)),
+                Cell::from(Line::from(if !wt.status.ignored_paths.is_empty() {
+                    vec![
+                        Span::styled(wt.status.summary(), status_style),
+                        Span::styled(
+                            format!(" !{}", wt.status.ignored_paths.len()),
+                            Style::default().fg(colors::CLAUDE_WARM_GRAY).dim(),
+                        ),
+                    ]
+                } else {
+                    vec![Span::styled(wt.status.summary(), status_style)]
+                })),
+                Cell::from(if wt.is_unborn() {
+                    Span::styled(
+                        "(none)",
+                        Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                    )
+                } else {
+                    Span::styled(&wt.commit_short, commit_style)
+                }),
+            ]);
+            Row::new(cells).height(1)
+        })
+        .collect();
+
+    // Interleave group headers; the table then selects within the combined rows
+    let grouped = app.group_by != GroupBy::None;
+    if grouped {
+        let table_rows = app.table_rows();
+        let mut worktree_rows = rows.into_iter();
+        rows = table_rows
+            .iter()
+            .map(|row| match row {
+                TableRow::Header(label, count) => {
+                    let mut cells = vec![Cell::from(""); branch_column];
+                    cells.push(Cell::from(Span::styled(
+                        format!("{} ({})", label, count),
+                        Style::default().fg(colors::CLAUDE_WARM_GRAY).bold(),
+                    )));
+                    Row::new(cells)
+                }
+                TableRow::Worktree(_) => worktree_rows.next().unwrap_or_default(),
+            })
+            .collect();
+        let selected = app.table_state.selected().and_then(|pos| {
+            table_rows
+                .iter()
+                .position(|row| *row == TableRow::Worktree(pos))
+        });
+        app.group_table_state.select(selected);
+    }
+
+    let mut table = Table::new(rows, widths).block(block);
+    if !app.mini_view {
+        table = table.header(header);
+    }
+    let table = table
+        .row_highlight_style(
+            Style::default().bg(colors::SELECTION_BG), // .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(Span::styled(
+            "→ ",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ));
+
+    if grouped {
+        frame.render_stateful_widget(table, area, &mut app.group_table_state);
+    } else {
+        frame.render_stateful_widget(table, area, &mut app.table_state);
+    }
+
+    // Scrollbar
+    if app.filtered_indices.len() > area.height.saturating_sub(app.list_chrome().1) as usize {
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(Some(""))
+            .end_symbol(Some(""));
+        let mut scrollbar_state = ScrollbarState::new(app.filtered_indices.len())
+            .position(app.table_state.selected().unwrap_or(0));
+        frame.render_stateful_widget(
+            scrollbar,
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// What's left for the branch column of a table `total` columns wide: `chrome` (borders
+/// and padding), the fixed `widths`, the highlight symbol and one column of spacing between
+/// each pair of columns take the rest
+fn branch_column_width(total: u16, widths: &[Constraint], chrome: u16) -> usize {
+    let fixed_width: u16 = widths
+        .iter()
+        .map(|w| match w {
+            Constraint::Length(n) => *n,
+            _ => 0,
+        })
+        .sum();
+    total.saturating_sub(chrome + fixed_width + 2 + widths.len() as u16 - 1) as usize
+}
+
+/// Ignored paths listed in the details panel before the rest are summed up
+const MAX_IGNORED_SHOWN: usize = 10;
+
+fn render_details_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let border_color = colors::BORDER_INACTIVE;
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Details", Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border_color))
+        .padding(Padding::new(2, 2, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    if let Some(wt) = app.selected_worktree() {
+        let mut lines = Vec::new();
+
+        // --- Identity & Status ---
+        let branch_name = wt.branch.as_deref().unwrap_or(if wt.is_detached {
+            "(detached)"
+        } else {
+            "(bare)"
+        });
+        lines.push(Line::from(vec![
+            Span::styled(branch_name, Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::raw(" "),
+            if wt.is_main {
+                Span::styled("[MAIN]", Style::default().fg(colors::PURPLE))
+            } else {
+                Span::raw("")
+            },
+        ]));
+
+        let mut status_spans = vec![Span::raw("  ")];
+        if wt.status.is_clean() {
+            status_spans.push(Span::styled("Clean", Style::default().fg(colors::SUCCESS)));
+        } else if wt.status.is_staged_ready() {
+            status_spans.push(Span::styled(
+                "Staged (ready to commit)",
+                Style::default().fg(colors::INFO),
+            ));
+            status_spans.push(Span::raw(" "));
+            status_spans.push(Span::styled(
+                format!("+{}", wt.status.staged),
+                Style::default().fg(colors::SUCCESS),
+            ));
+        } else {
+            status_spans.push(Span::styled(
+                "Modified",
+                Style::default().fg(colors::WARNING),
+            ));
+            status_spans.push(Span::raw(" "));
+            let mut parts = Vec::new();
+            if wt.status.staged > 0 {
+                parts.push(Span::styled(
+                    format!("+{}", wt.status.staged),
+                    Style::default().fg(colors::SUCCESS),
+                ));
+            }
+            if wt.status.modified > 0 {
+                parts.push(Span::styled(
+                    format!("~{}", wt.status.modified),
+                    Style::default().fg(colors::WARNING),
+                ));
+            }
+            if wt.status.untracked > 0 {
+                parts.push(Span::styled(
+                    format!("?{}", wt.status.untracked),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ));
+            }
+
+            for (i, part) in parts.into_iter().enumerate() {
+                if i > 0 {
+                    status_spans.push(Span::raw(" "));
+                }
+                status_spans.push(part);
+            }
+            // status_spans.push(Span::raw(")"));
+        }
+
+        if wt.status.ahead > 0 || wt.status.behind > 0 {
+            status_spans.push(Span::styled(
+                " • ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ));
+            if wt.status.ahead > 0 {
+                status_spans.push(Span::styled(
+                    format!("↑{}", wt.status.ahead),
+                    Style::default().fg(colors::SUCCESS),
+                ));
+                if wt.status.behind > 0 {
+                    status_spans.push(Span::raw(" "));
+                }
+            }
+            if wt.status.behind > 0 {
+                status_spans.push(Span::styled(
+                    format!("↓{}", wt.status.behind),
+                    Style::default().fg(colors::ERROR),
+                ));
+            }
+        }
+        lines.push(Line::from(status_spans));
+        if wt.branch.is_some() && !wt.is_unborn() {
+            lines.push(Line::from(match &wt.status.upstream {
+                Some(upstream) if wt.status.upstream_gone => vec![
+                    Span::styled("  Tracking ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                    Span::styled(upstream, Style::default().fg(colors::ERROR).crossed_out()),
+                    Span::styled(
+                        " gone from the remote, likely merged (x to delete)",
+                        Style::default().fg(colors::ERROR),
+                    ),
+                ],
+                Some(upstream) => vec![
+                    Span::styled("  Tracking ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                    Span::styled(upstream, Style::default().fg(colors::INFO)),
+                ],
+                None => vec![Span::styled(
+                    "  No upstream (B to set one)",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                )],
+            }));
+        }
+        if wt.stash_count > 0 {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format!(
+                        "≡{} stash{} on this branch",
+                        wt.stash_count,
+                        if wt.stash_count == 1 { "" } else { "es" }
+                    ),
+                    Style::default().fg(colors::PURPLE),
+                ),
+            ]));
+        }
+        if let Some(ref pr) = wt.pr {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format!("PR #{}", pr.number),
+                    Style::default().fg(colors::INFO),
+                ),
+                Span::raw(" "),
+                Span::styled(&pr.state, Style::default().fg(pr_color(pr))),
+            ]));
+        }
+        if let Some(count) = wt.commit_count {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    format!(
+                        "{}{} commit{} in total",
+                        count,
+                        if count >= COMMIT_COUNT_CAP { "+" } else { "" },
+                        if count == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+            ]));
+        }
+        if wt.is_main {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "  Main branch: ",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+                Span::styled(&app.main_branch, Style::default().fg(colors::CLAUDE_ORANGE)),
+                Span::styled(
+                    format!(" ({})", app.main_branch_source),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ]));
+        }
+        if let Some(unique) = wt.unique_commits {
+            let base = &app.main_branch;
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                if unique == 0 {
+                    Span::styled(
+                        format!("No commits beyond {}", base),
+                        Style::default().fg(colors::SUCCESS),
+                    )
+                } else {
+                    Span::styled(
+                        format!(
+                            "{} commit{} not on {}",
+                            unique,
+                            if unique == 1 { "" } else { "s" },
+                            base
+                        ),
+                        Style::default().fg(colors::PURPLE),
+                    )
+                },
+            ]));
+        }
+        lines.push(Line::raw(""));
+
+        // --- Note ---
+        if let Some(note) = app.note_for(wt) {
+            lines.push(Line::from(Span::styled(
+                "Note",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            )));
+            lines.push(Line::from(vec![
+                Span::styled("  ✎ ", Style::default().fg(colors::INFO)),
+                Span::styled(note, Style::default().fg(colors::CLAUDE_CREAM)),
+            ]));
+            lines.push(Line::raw(""));
+        }
+
+        // --- Merge Conflicts ---
+        if let Some(files) = app.conflicts_for(wt) {
+            lines.push(Line::from(vec![
+                Span::styled("Merge Conflicts", Style::default().fg(colors::ERROR)),
+                Span::styled(
+                    " (M to open mergetool)",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ]));
+            for file in files {
+                lines.push(Line::from(vec![
+                    Span::styled("  ✗ ", Style::default().fg(colors::ERROR)),
+                    Span::styled(
+                        truncate_str(file, inner.width.saturating_sub(6) as usize),
+                        Style::default().fg(colors::CLAUDE_CREAM),
+                    ),
+                ]));
+            }
+            lines.push(Line::raw(""));
+        }
+
+        // --- Location ---
+        lines.push(Line::from(Span::styled(
+            "Location",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        )));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                truncate_path(
+                    &app.format_path(&wt.path),
+                    inner.width.saturating_sub(4) as usize,
+                    app.config.path_truncation,
+                ),
+                Style::default().fg(colors::CLAUDE_CREAM),
+            ),
+        ]));
+        if let Some(subpath) = app.current_subpath(wt) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "  You are here: ",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+                Span::styled(
+                    truncate_path(
+                        &subpath,
+                        inner.width.saturating_sub(18) as usize,
+                        app.config.path_truncation,
+                    ),
+                    Style::default().fg(colors::INFO),
+                ),
+            ]));
+        }
+        lines.push(Line::raw(""));
+
+        // --- Current Commit ---
+        lines.push(Line::from(Span::styled(
+            "Current Commit",
             Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        )));
+        let time_ago = wt
+            .recent_commits
+            .first()
+            .and_then(|c| c.time)
+            .map(|time| format_relative_time(time, cache::now_secs() as i64))
+            .unwrap_or_default();
+        if wt.is_unborn() {
+            lines.push(Line::from(Span::styled(
+                "  (no commits yet)",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+            )));
+        } else {
+            let signature = match wt.head_signed {
+                Some(true) => Span::styled(" signed", Style::default().fg(colors::SUCCESS)),
+                Some(false) => {
+                    Span::styled(" unsigned", Style::default().fg(colors::CLAUDE_WARM_GRAY))
+                }
+                None => Span::styled(
+                    " signature?",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            };
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(
+                    if app.show_full_hash {
+                        &wt.commit
+                    } else {
+                        &wt.commit_short
+                    },
+                    Style::default().fg(colors::INFO),
+                ),
+                Span::styled(
+                    format!(" • {}", time_ago),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+                Span::styled(" •", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                signature,
+            ]));
+        }
+
+        if !wt.commit_message.is_empty() {
+            // Keep the summary on one line so long messages don't push history off-screen
+            let msg = truncate_str(&wt.commit_message, inner.width.saturating_sub(2) as usize);
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(msg, Style::default().fg(colors::CLAUDE_CREAM).italic()),
+            ]));
+        }
+        lines.push(Line::raw(""));
+
+        // --- Attributes ---
+        let integrity_issue = app.integrity_issues.get(&wt.path);
+        if wt.is_locked
+            || wt.is_prunable
+            || wt.sparse_patterns.is_some()
+            || integrity_issue.is_some()
+        {
+            lines.push(Line::from(Span::styled(
+                "Attributes",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            )));
+            if wt.is_locked {
+                lines.push(Line::from(vec![
+                    Span::raw("  Locked: "),
+                    Span::styled(
+                        wt.lock_reason.as_deref().unwrap_or("no reason provided"),
+                        Style::default().fg(colors::WARNING).italic(),
+                    ),
+                ]));
+            }
+            if let Some(patterns) = wt.sparse_patterns {
+                lines.push(Line::from(vec![
+                    Span::raw("  Sparse: "),
+                    Span::styled(
+                        format!(
+                            "{} pattern{} (some files are not checked out)",
+                            patterns,
+                            if patterns == 1 { "" } else { "s" }
+                        ),
+                        Style::default().fg(colors::INFO).italic(),
+                    ),
+                ]));
+            }
+            if wt.is_prunable {
+                lines.push(Line::from(vec![
+                    Span::raw("  Prunable: "),
+                    Span::styled(
+                        wt.prune_reason
+                            .as_deref()
+                            .unwrap_or("Worktree path is missing or invalid"),
+                        Style::default().fg(colors::ERROR).italic(),
+                    ),
+                ]));
+            }
+            if let Some(issue) = integrity_issue {
+                lines.push(Line::from(vec![
+                    Span::raw("  Corrupted: "),
+                    Span::styled(issue.clone(), Style::default().fg(colors::ERROR).italic()),
+                ]));
+            }
+            lines.push(Line::raw(""));
+        }
+
+        // --- Config ---
+        if !wt.config.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "Config",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            )));
+            for (key, value) in &wt.config {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {} ", key),
+                        Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                    ),
+                    Span::styled(value, Style::default().fg(colors::CLAUDE_CREAM)),
+                ]));
+            }
+            lines.push(Line::raw(""));
+        }
+
+        // --- Ignored ---
+        if !wt.status.ignored_paths.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("Ignored ({})", wt.status.ignored_paths.len()),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+                Span::styled(
+                    " (I to toggle)",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ]));
+            for path in wt.status.ignored_paths.iter().take(MAX_IGNORED_SHOWN) {
+                lines.push(Line::from(Span::styled(
+                    format!("  !{}", path),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).dim(),
+                )));
+            }
+            if wt.status.ignored_paths.len() > MAX_IGNORED_SHOWN {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  … and {} more",
+                        wt.status.ignored_paths.len() - MAX_IGNORED_SHOWN
+                    ),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                )));
+            }
+            lines.push(Line::raw(""));
+        }
+
+        // --- README ---
+        if let Some(readme) = wt
+            .readme_preview
+            .as_ref()
+            .filter(|_| app.show_readme_preview)
+        {
+            lines.push(Line::from(vec![
+                Span::styled("README", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                Span::styled(
+                    " (v to toggle)",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ]));
+            for line in readme.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", line),
+                    Style::default().fg(colors::CLAUDE_CREAM),
+                )));
+            }
+            lines.push(Line::raw(""));
+        }
+
+        // --- History ---
+        if app.show_recent_commits && wt.recent_commits.len() > 1 {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Recent History",
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+                Span::styled(
+                    if app.history_oldest_first {
+                        " (oldest first, t to toggle)"
+                    } else {
+                        " (t to toggle)"
+                    },
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ]));
+
+            let mut history: Vec<_> = wt.recent_commits.iter().skip(1).take(8).collect();
+            if app.history_oldest_first {
+                history.reverse();
+            }
+            for commit in history {
+                let prefix = format!("  {} ", commit.hash);
+                let msg = truncate_str(
+                    &commit.message,
+                    (inner.width as usize).saturating_sub(prefix.width()),
+                );
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, Style::default().fg(colors::PURPLE)),
+                    Span::styled(msg, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                ]));
+            }
+        }
+
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    } else {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No worktree selected",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+            ))
+            .alignment(Alignment::Center),
+            inner,
+        );
+    }
+}
+
+fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        // .borders(Borders::TOP)
+        // .border_type(BorderType::LightDoubleDashed)
+        // .border_style(Style::default().fg(colors::BORDER_INACTIVE))
+        .padding(Padding::top(if app.mini_view { 0 } else { 1 }));
+    // .padding(Padding::horizontal(1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(inner);
+
+    // Hints tied to an action are dimmed when it doesn't apply to the selection
+    let mode_hints = match app.mode {
+        AppMode::Normal if app.loading_state == LoadingState::Loading => vec![
+            ("Esc", "cancel refresh", None),
+            ("j/k", "nav", None),
+            ("/", "search", None),
+        ],
+        AppMode::Normal => vec![
+            ("j/k", "nav", None),
+            ("0-9", "jump", None),
+            ("n/N", "new worktree", Some(Action::Create)),
+            ("space", "cd into", Some(Action::CdInto)),
+            ("x", "delete", Some(Action::Delete)),
+            ("m", "merge", Some(Action::Merge)),
+            ("p/P", "pull/push", Some(Action::PullPush)),
+            ("s", "sort", None),
+            ("/", "search", None),
+        ],
+        AppMode::Search => vec![("Enter", "confirm", None), ("Esc", "cancel", None)],
+        AppMode::Hint => vec![
+            ("a-z", "jump to labeled row", None),
+            ("Esc", "cancel", None),
+        ],
+        _ => vec![("Esc", "cancel", None)],
+    };
+
+    let hints: Vec<Span> = mode_hints
+        .iter()
+        .flat_map(|&(key, label, action)| {
+            let available = action.is_none_or(|a| app.action_available(a));
+            let (key_color, label_color) = if available {
+                (colors::CLAUDE_ORANGE, colors::CLAUDE_WARM_GRAY)
+            } else {
+                (colors::BORDER_INACTIVE, colors::BORDER_INACTIVE)
+            };
+            vec![
+                Span::styled(key, Style::default().fg(key_color)),
+                Span::styled(format!(" {}  ", label), Style::default().fg(label_color)),
+            ]
+        })
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(Line::from(hints)),
+        Rect::new(layout[0].x + 1, layout[0].y, layout[0].width, 1),
+    );
+
+    // Build right side content: spinner (if loading) + status message
+    let mut right_spans: Vec<Span> = Vec::new();
+
+    // Add spinner if loading or about to run a blocking op
+    if app.loading_state == LoadingState::Loading || app.pending_op.is_some() {
+        let spinner_char = SPINNER_FRAMES[app.spinner_frame];
+        right_spans.push(Span::styled(
+            format!("{} ", spinner_char),
+            Style::default().fg(colors::BORDER_INACTIVE),
+        ));
+    }
+
+    if let Some(summary) = app.hover_summary().filter(|_| app.mode == AppMode::Normal) {
+        right_spans.push(Span::styled(
+            truncate_str(&summary, layout[1].width.saturating_sub(3) as usize),
+            Style::default().fg(colors::CLAUDE_CREAM),
+        ));
+    } else if let Some(ref msg) = app.status_message {
+        let color = match msg.level {
+            MessageLevel::Info => colors::INFO,
+            MessageLevel::Success => colors::SUCCESS,
+            MessageLevel::Warning => colors::WARNING,
+            MessageLevel::Error => colors::ERROR,
+        };
+        right_spans.push(Span::styled(&msg.text, Style::default().fg(color)));
+    }
+
+    if !right_spans.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Line::from(right_spans)).alignment(Alignment::Right),
+            Rect::new(layout[1].x, layout[1].y, layout[1].width - 1, 1),
+        );
+    }
+}
+
+/// Normal-mode key bindings by category, shared by the help dialog and the key hints
+const HELP_SECTIONS: &[(&str, &[&str])] = &[
+    (
+        "Navigation",
+        &[
+            "j/k /        Move down/up",
+            "g / G            Go to first/last",
+            "0-9              Jump to item (type 12 for 12th)",
+            "J                Jump hints (type a row's label)",
+            "-                Swap to previous selection",
+            "V                Toggle mini view",
+            "Ctrl+d/u         Page down/up",
+            "Tab              Switch pane",
+        ],
+    ),
+    (
+        "Git Operations",
+        &[
+            "n / N            New worktree / from current",
+            "Shift+Tab        Cycle new/existing/detached",
+            "x / Del          Delete worktree",
+            "L                Toggle lock",
+            "S                Toggle sparse-checkout",
+            "c                Commit staged changes",
+            "b                New branch at HEAD (no worktree)",
+            "i                Import existing directory",
+            "e                Edit worktree note",
+            "p                Pull (in worktree)",
+            "P                Push (from worktree)",
+            "f                Fetch upstream of selected",
+            "B                Set upstream branch",
+            "F                Fetch all remotes",
+            "U                Fast-forward main to upstream",
+            "A                Fast-forward all worktrees",
+            "r                Refresh list",
+            "R                Force refresh (clear cache)",
+            "u                Refresh selected worktree",
+            "X                Prune stale",
+            "W                Resolve prunable one by one",
+            "m                Merge branch",
+            "M                Resolve conflicts in mergetool",
+        ],
+    ),
+    (
+        "Utilities",
+        &[
+            "Space            Change to worktree dir",
+            "Enter            cd / open (enter_action)",
+            "o                Show worktree path",
+            "y                Copy path to clipboard",
+            "Y                Copy HEAD commit message",
+            "E                Copy clone + worktree command",
+            "d                Diff branch against main",
+            "O                Open in file manager",
+            "T                Open in tmux (window or layout)",
+            "K                Run verify_command",
+            "w                Switch repository",
+            "H                Worktree health check",
+            "D                Open log file in pager",
+            "C                Reload config",
+            "s                Cycle sort order",
+            "z                Cycle grouping (status/merged/remote)",
+            "a                Cycle path display (abs/~/relative)",
+            "t                Toggle recent commits",
+            "Ctrl+t           Recent commits oldest first",
+            "v                Toggle README preview",
+            "#                Toggle full commit hash",
+            "I                Toggle ignored files",
+            "/                Search worktrees",
+            ":                Command prompt (:create, :sort, ...)",
+            "?                Toggle this help",
+            "h                Quick key hints",
+            "Esc              Cancel refresh / quit",
+            "q                Quit",
+        ],
+    ),
+];
+
+fn render_help_dialog(frame: &mut Frame) {
+    let area = centered_rect(65, 75, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                "Keyboard Shortcuts",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(2, 2, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut y = 0;
+    for &(section, items) in HELP_SECTIONS {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                section,
+                Style::default().fg(colors::CLAUDE_CREAM).bold(),
+            )),
+            Rect::new(inner.x, inner.y + y, inner.width, 1),
+        );
+        y += 1;
+
+        for item in items {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    format!("  {}", item),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                )),
+                Rect::new(inner.x, inner.y + y, inner.width, 1),
+            );
+            y += 1;
+        }
+        y += 1;
+    }
+
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            format!("Press Esc or ? to close · log: {}", LOG_PATH),
+            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+        ))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+/// Compact, non-modal version of the help dialog drawn along the bottom of `area`
+fn render_key_hints(frame: &mut Frame, area: Rect) {
+    const LABEL_WIDTH: usize = 15;
+    // Borders plus horizontal padding
+    let width = area.width.saturating_sub(4) as usize;
+
+    // Pack each section's bindings into as few lines as fit, continuing under the label
+    let mut lines: Vec<Line> = Vec::new();
+    for &(section, items) in HELP_SECTIONS {
+        let mut spans = vec![Span::styled(
+            format!("{:<LABEL_WIDTH$}", section),
+            Style::default().fg(colors::CLAUDE_CREAM).bold(),
+        )];
+        let mut used = LABEL_WIDTH;
+        for item in items {
+            let (key, desc) = item.split_once("  ").unwrap_or((item, ""));
+            let desc = desc.trim_start();
+            let entry_width = key.chars().count() + 1 + desc.chars().count();
+
+            if used > LABEL_WIDTH {
+                if used + 3 + entry_width > width {
+                    lines.push(Line::from(std::mem::take(&mut spans)));
+                    spans.push(Span::raw(" ".repeat(LABEL_WIDTH)));
+                    used = LABEL_WIDTH;
+                } else {
+                    spans.push(Span::styled(
+                        " · ",
+                        Style::default().fg(colors::BORDER_INACTIVE),
+                    ));
+                    used += 3;
+                }
+            }
+            spans.push(Span::styled(
+                key,
+                Style::default().fg(colors::CLAUDE_ORANGE),
+            ));
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                desc,
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ));
+            used += entry_width;
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let panel = Rect::new(area.x, area.y + area.height - height, area.width, height);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Keys", Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled(
+                " (any key to dismiss) ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::horizontal(1));
+
+    frame.render_widget(Clear, panel);
+    frame.render_widget(Paragraph::new(lines).block(block), panel);
+}
+
+fn render_create_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                "Create New Worktree",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(2, 2, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // Mode toggle indicator
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Mode: ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(
+                app.create_mode.label(),
+                Style::default()
+                    .fg(match app.create_mode {
+                        CreateMode::NewBranch => colors::SUCCESS,
+                        CreateMode::CheckoutExisting => colors::INFO,
+                        CreateMode::Detached => colors::PURPLE,
+                    })
+                    .bold(),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                "(Shift+Tab to cycle)",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+            ),
+        ])),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
+
+    let label = match app.create_mode {
+        CreateMode::NewBranch => "Worktree name:",
+        CreateMode::CheckoutExisting => "Worktree directory:",
+        CreateMode::Detached => "Commit (hash, tag or ref):",
+    };
+
+    let label_y = inner.y + 2;
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            label,
+            Style::default().fg(colors::CLAUDE_CREAM),
+        )),
+        Rect::new(inner.x, label_y, inner.width, 1),
+    );
+
+    let input_area = Rect::new(inner.x, label_y + 2, inner.width, 3);
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(if app.mode == AppMode::Create {
+            colors::CLAUDE_ORANGE
+        } else {
+            colors::BORDER_INACTIVE
+        }));
+
+    frame.render_widget(
+        Paragraph::new(app.create_input.as_str())
+            .block(input_block)
+            .style(Style::default().fg(colors::CLAUDE_CREAM)),
+        input_area,
+    );
+
+    if app.mode == AppMode::Create {
+        frame.set_cursor_position((
+            input_area.x + cursor_column(&app.create_input, app.create_cursor) + 1,
+            input_area.y + 1,
+        ));
+    }
+
+    let branch_line = match app.create_mode {
+        CreateMode::NewBranch | CreateMode::CheckoutExisting => {
+            let (branch_label, placeholder) = if app.create_mode == CreateMode::CheckoutExisting {
+                ("Branch to checkout:", "(select branch)")
+            } else {
+                ("Base branch:", "HEAD (base?)")
+            };
+            Line::from(vec![
+                Span::styled(branch_label, Style::default().fg(colors::CLAUDE_CREAM)),
+                Span::raw(" "),
+                Span::styled(
+                    app.create_from_branch.as_deref().unwrap_or(placeholder),
+                    Style::default().fg(colors::CLAUDE_ORANGE),
+                ),
+            ])
+        }
+        CreateMode::Detached => Line::styled(
+            "Creates detached-<hash> without a branch",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
         ),
+    };
+
+    frame.render_widget(
+        Paragraph::new(branch_line),
+        Rect::new(inner.x, label_y + 6, inner.width, 1),
+    );
+
+    let has_templates = !app.config.templates.is_empty();
+    if has_templates {
+        let template = app
+            .create_template
+            .and_then(|idx| app.config.templates.get(idx));
+        let mut spans = vec![
+            Span::styled("Template:", Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::raw(" "),
+            Span::styled(
+                template.map_or("none", |t| t.name.as_str()).to_string(),
+                Style::default().fg(colors::CLAUDE_ORANGE),
+            ),
+        ];
+        if let Some(template) = template {
+            let steps = template.copy_files.len() + template.post_create.len();
+            if steps > 0 {
+                spans.push(Span::styled(
+                    format!(" ({} post-create steps)", steps),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ));
+            }
+        }
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)),
+            Rect::new(inner.x, label_y + 7, inner.width, 1),
+        );
+    }
+    let lock_y = label_y + if has_templates { 8 } else { 7 };
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Lock:", Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::raw(" "),
+            if app.create_lock {
+                Span::styled(
+                    format!("{} locked on creation", icons(app.config.icons).locked),
+                    Style::default().fg(colors::WARNING),
+                )
+            } else {
+                Span::styled("no", Style::default().fg(colors::CLAUDE_WARM_GRAY))
+            },
+        ])),
+        Rect::new(inner.x, lock_y, inner.width, 1),
+    );
+
+    // Problems that would only surface after Enter
+    let mut warnings = Vec::new();
+    if let Some(warning) = &app.create_dir_warning {
+        warnings.push(warning.clone());
+    }
+    if let Some(path) = app.create_target_conflict() {
+        warnings.push(format!("{} already exists", path.display()));
+    }
+    let warnings_y = lock_y + 2;
+    let hints_y = inner.y + inner.height - 1;
+    if !warnings.is_empty() && warnings_y < hints_y {
+        let lines: Vec<Line> = warnings
+            .into_iter()
+            .map(|warning| {
+                Line::styled(
+                    format!("⚠ {}", warning),
+                    Style::default().fg(colors::WARNING).italic(),
+                )
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: true }),
+            Rect::new(inner.x, warnings_y, inner.width, hints_y - warnings_y),
+        );
+    }
+
+    let mut hints = vec![
+        Span::styled("Shift+Tab", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" mode  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Tab", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" branch  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    ];
+    if has_templates {
+        hints.extend([
+            Span::styled("Ctrl+T", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" template  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]);
+    }
+    hints.extend([
+        Span::styled("Ctrl+L", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" lock  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" create  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
     ]);
-    frame.render_widget(Paragraph::new(logo), header_layout[0]);
+    frame.render_widget(
+        Paragraph::new(Line::from(hints)).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+fn render_branch_select_dialog(frame: &mut Frame, app: &mut App, title: &str) {
+    let icons = icons(app.config.icons);
+    let area = centered_rect(40, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(title, Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled(
+                format!(" ({})", app.branch_filter.label()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(1, 1, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    // The footer takes the last line, the query (once there is one) the line above it
+    let show_query = app.branch_query_focused || !app.branch_query.is_empty();
+    let reserved = if show_query { 2 } else { 1 };
+    let list_area = Rect {
+        height: inner.height.saturating_sub(reserved),
+        ..inner
+    };
+
+    let items: Vec<ListItem> = app
+        .visible_branches()
+        .into_iter()
+        .map(|b| {
+            let style = if b.is_current {
+                Style::default().fg(colors::CLAUDE_ORANGE).bold()
+            } else if b.is_remote {
+                Style::default().fg(colors::INFO)
+            } else {
+                Style::default().fg(colors::CLAUDE_CREAM)
+            };
+            let prefix = if b.is_current {
+                format!("{} ", icons.branch_current)
+            } else if b.is_remote {
+                format!("{} ", icons.branch_remote)
+            } else {
+                "  ".to_string()
+            };
+            let mut spans = vec![
+                Span::styled(prefix, style),
+                Span::styled(b.name.clone(), style),
+            ];
+            if let Some(upstream) = b.upstream.as_ref().filter(|_| b.tracked) {
+                spans.push(Span::styled(
+                    format!(" = {}", upstream),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(
+            Style::default().bg(colors::SELECTION_BG), // .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(" ");
+
+    frame.render_stateful_widget(list, list_area, &mut app.branch_list_state);
+
+    if show_query {
+        let query_y = inner.y + inner.height.saturating_sub(2);
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("/", Style::default().fg(colors::CLAUDE_ORANGE)),
+                Span::styled(&app.branch_query, Style::default().fg(colors::CLAUDE_CREAM)),
+            ])),
+            Rect::new(inner.x, query_y, inner.width, 1),
+        );
+        if app.branch_query_focused {
+            frame.set_cursor_position((
+                inner.x + 1 + cursor_column(&app.branch_query, app.branch_query_cursor),
+                query_y,
+            ));
+        }
+    }
+
+    let footer = if app.branch_query_focused {
+        vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" select  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                " clear filter",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ]
+    } else {
+        vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" select  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("/", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" filter  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("r", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" remotes  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]
+    };
+    frame.render_widget(
+        Paragraph::new(Line::from(footer)).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+fn render_repo_select_dialog(frame: &mut Frame, app: &mut App) {
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                "Switch Repository",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(1, 1, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let list_area = Rect::new(
+        inner.x,
+        inner.y,
+        inner.width,
+        inner.height.saturating_sub(2),
+    );
+    let path_width = (inner.width as usize).saturating_sub(28);
+
+    let items: Vec<ListItem> = app
+        .repo_history
+        .repos
+        .iter()
+        .map(|r| {
+            let is_active = r.path == app.repo_root;
+            let exists = r.path.exists();
+            let name_style = if !exists {
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).crossed_out()
+            } else if is_active {
+                Style::default().fg(colors::CLAUDE_ORANGE).bold()
+            } else {
+                Style::default().fg(colors::CLAUDE_CREAM)
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if r.pinned { "★ " } else { "  " },
+                    Style::default().fg(colors::WARNING),
+                ),
+                Span::styled(format!("{:<20} ", truncate_str(&r.name(), 20)), name_style),
+                Span::styled(
+                    truncate_path(&r.path, path_width, app.config.path_truncation),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(colors::SELECTION_BG))
+        .highlight_symbol(" ");
+
+    frame.render_stateful_widget(list, list_area, &mut app.repo_list_state);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" switch  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("p", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" pin  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+fn render_health_dialog(frame: &mut Frame, app: &mut App) {
+    let errors = app
+        .health_issues
+        .iter()
+        .filter(|i| i.level == MessageLevel::Error)
+        .count();
+    let warnings = app.health_issues.len() - errors;
+
+    let items: Vec<ListItem> = app
+        .health_issues
+        .iter()
+        .map(|issue| {
+            let color = if issue.level == MessageLevel::Error {
+                colors::ERROR
+            } else {
+                colors::WARNING
+            };
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::styled("● ", Style::default().fg(color)),
+                    Span::styled(
+                        issue.problem.clone(),
+                        Style::default().fg(colors::CLAUDE_CREAM),
+                    ),
+                ]),
+                Line::styled(
+                    format!("    → {}", issue.suggestion),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                ),
+            ])
+        })
+        .collect();
+
+    render_report_dialog(
+        frame,
+        "Worktree Health",
+        format!(" ({} errors, {} warnings) ", errors, warnings),
+        items,
+        &mut app.health_list_state,
+    );
+}
+
+/// Popup listing one row per worktree, with Enter jumping to it (health, fast-forward all)
+fn render_report_dialog(
+    frame: &mut Frame,
+    title: &str,
+    summary: String,
+    items: Vec<ListItem>,
+    list_state: &mut ListState,
+) {
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(title, Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled(summary, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(1, 1, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let list_area = Rect::new(
+        inner.x,
+        inner.y,
+        inner.width,
+        inner.height.saturating_sub(2),
+    );
+
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(colors::SELECTION_BG))
+        .highlight_symbol(" ");
 
-    // let total = app.worktrees.len();
-    // let dirty = app
-    //     .worktrees
-    //     .iter()
-    //     .filter(|w| !w.status.is_clean())
-    //     .count();
+    frame.render_stateful_widget(list, list_area, list_state);
 
-    // let mut stats_spans = vec![Span::styled(
-    //     format!("? untracked ~ modified + staged"),
-    //     Style::default().fg(colors::CLAUDE_WARM_GRAY),
-    // )];
-    let mut stats_spans = vec![
-        Span::styled(format!(" ? "), Style::default().fg(colors::CLAUDE_CREAM)),
-        Span::styled(
-            format!("untracked "),
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                " jump to worktree  ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" close", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+fn render_sync_report_dialog(frame: &mut Frame, app: &mut App) {
+    let updated = app
+        .sync_results
+        .iter()
+        .filter(|r| matches!(r.outcome, SyncOutcome::FastForwarded))
+        .count();
+
+    let items: Vec<ListItem> = app
+        .sync_results
+        .iter()
+        .map(|result| {
+            let (text, color) = match &result.outcome {
+                SyncOutcome::FastForwarded => ("fast-forwarded".into(), colors::SUCCESS),
+                SyncOutcome::UpToDate => ("up to date".into(), colors::CLAUDE_WARM_GRAY),
+                SyncOutcome::Diverged => {
+                    ("skipped, diverged from upstream".into(), colors::WARNING)
+                }
+                SyncOutcome::Dirty => ("skipped, uncommitted changes".into(), colors::WARNING),
+                SyncOutcome::UpstreamGone => {
+                    ("skipped, upstream was deleted".into(), colors::WARNING)
+                }
+                SyncOutcome::Failed(reason) => (format!("failed: {}", reason), colors::ERROR),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled("● ", Style::default().fg(color)),
+                Span::styled(
+                    result.name.clone(),
+                    Style::default().fg(colors::CLAUDE_CREAM),
+                ),
+                Span::styled(format!("  {}", text), Style::default().fg(color)),
+            ]))
+        })
+        .collect();
+
+    render_report_dialog(
+        frame,
+        "Fast-forward All",
+        format!(" ({} of {} updated) ", updated, app.sync_results.len()),
+        items,
+        &mut app.sync_list_state,
+    );
+}
+
+fn render_diff_dialog(frame: &mut Frame, app: &mut App) {
+    let inner =
+        render_text_popup_frame(frame, "Branch Diff", &app.diff_title, colors::CLAUDE_ORANGE);
+    render_text_popup_body(
+        frame,
+        inner,
+        &app.diff_lines,
+        diff_line_style,
+        &mut app.diff_scroll,
+        &mut app.diff_page,
+        Vec::new(),
+    );
+}
+
+fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("diff --git") {
+        Style::default().fg(colors::CLAUDE_ORANGE).bold()
+    } else if line.starts_with("+++") || line.starts_with("---") {
+        Style::default().fg(colors::CLAUDE_CREAM).bold()
+    } else if line.starts_with("@@") {
+        Style::default().fg(colors::INFO)
+    } else if line.starts_with('+') {
+        Style::default().fg(colors::SUCCESS)
+    } else if line.starts_with('-') {
+        Style::default().fg(colors::ERROR)
+    } else {
+        Style::default().fg(colors::CLAUDE_WARM_GRAY)
+    }
+}
+
+fn render_verify_dialog(frame: &mut Frame, app: &mut App) {
+    let (status, color) = match app.verify_result {
+        None => ("Running…".to_string(), colors::CLAUDE_ORANGE),
+        Some((Some(0), elapsed)) => (
+            format!("✓ Passed in {:.1}s", elapsed.as_secs_f64()),
+            colors::SUCCESS,
         ),
-        Span::styled(format!(" ~ "), Style::default().fg(colors::CLAUDE_CREAM)),
-        Span::styled(
-            format!("modified "),
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        Some((Some(code), elapsed)) => (
+            format!("✗ Failed (exit {}) in {:.1}s", code, elapsed.as_secs_f64()),
+            colors::ERROR,
         ),
-        Span::styled(format!(" + "), Style::default().fg(colors::CLAUDE_CREAM)),
-        Span::styled(
-            format!("staged "),
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        Some((None, _)) => ("✗ Stopped".to_string(), colors::ERROR),
+    };
+
+    let inner = render_text_popup_frame(frame, "Verify", &app.verify_title, color);
+    frame.render_widget(
+        Paragraph::new(Span::styled(status, Style::default().fg(color).bold())),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
+
+    // The status line and a blank line sit above the output
+    let mut hints = Vec::new();
+    if app.verify_result.is_none() {
+        hints.extend([
+            Span::styled("Ctrl+C", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" stop  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]);
+    }
+    render_text_popup_body(
+        frame,
+        Rect::new(
+            inner.x,
+            inner.y + 2,
+            inner.width,
+            inner.height.saturating_sub(2),
         ),
-    ];
+        &app.verify_output,
+        |_| Style::default().fg(colors::CLAUDE_CREAM),
+        &mut app.verify_scroll,
+        &mut app.verify_page,
+        hints,
+    );
+}
 
-    // let mut stats_spans = vec![Span::styled(
-    //     format!("{} worktrees", total),
-    //     Style::default().fg(colors::CLAUDE_WARM_GRAY),
-    // )];
-    // if dirty > 0 {
-    //     stats_spans.push(Span::styled(
-    //         format!("  {} dirty", dirty),
-    //         Style::default().fg(colors::WARNING),
-    //     ));
-    // }
+/// Clears a large centered area and draws the bordered block of a text popup, returning
+/// the area inside it
+fn render_text_popup_frame(frame: &mut Frame, title: &str, subtitle: &str, color: Color) -> Rect {
+    let area = centered_rect(90, 85, frame.area());
+    frame.render_widget(Clear, area);
 
-    stats_spans.extend([
-        Span::raw("  "),
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(title.to_string(), Style::default().fg(color).bold()),
+            Span::styled(
+                format!(" {} ", subtitle),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(color))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(1, 1, 0, 0));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    inner
+}
+
+/// Visible slice of `text` with the key hints and position on the last line of `area`.
+/// Records the page size for `scroll_text_popup` and clamps `scroll` to it
+fn render_text_popup_body(
+    frame: &mut Frame,
+    area: Rect,
+    text: &[String],
+    style_line: fn(&str) -> Style,
+    scroll: &mut usize,
+    page: &mut usize,
+    extra_hints: Vec<Span>,
+) {
+    // A blank line and the hints take two lines
+    let text_height = area.height.saturating_sub(2) as usize;
+    *page = text_height.max(1);
+    *scroll = (*scroll).min(text.len().saturating_sub(*page));
+
+    // Only the visible slice is styled, since branch diffs can be huge
+    let lines: Vec<Line> = text
+        .iter()
+        .skip(*scroll)
+        .take(text_height)
+        .map(|line| Line::styled(line.as_str(), style_line(line)))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines),
+        Rect::new(area.x, area.y, area.width, text_height as u16),
+    );
+
+    let position = format!(
+        "  {}-{}/{}",
+        (*scroll + 1).min(text.len()),
+        (*scroll + text_height).min(text.len()),
+        text.len()
+    );
+    let mut hints = vec![
+        Span::styled("j/k", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" scroll  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Ctrl+d/u", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" page  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("g/G", Style::default().fg(colors::CLAUDE_ORANGE)),
         Span::styled(
-            format!("  {}", app.sort_order.label()),
+            " top/bottom  ",
             Style::default().fg(colors::CLAUDE_WARM_GRAY),
         ),
-        Span::raw("  "),
-        Span::styled("?", Style::default().fg(colors::CLAUDE_ORANGE)),
-        Span::styled(" help", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    ];
+    hints.extend(extra_hints);
+    hints.extend([
+        Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" close", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled(position, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
     ]);
-
-    let stats = Line::from(stats_spans).alignment(Alignment::Right);
-    frame.render_widget(Paragraph::new(stats), header_layout[1]);
+    frame.render_widget(
+        Paragraph::new(Line::from(hints)).alignment(Alignment::Center),
+        Rect::new(
+            area.x,
+            area.y + area.height.saturating_sub(1),
+            area.width,
+            1,
+        ),
+    );
 }
 
-fn render_content(frame: &mut Frame, app: &mut App, area: Rect) {
-    let content_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(area);
+fn render_batch_confirm_dialog(frame: &mut Frame, app: &mut App) {
+    let Some(batch) = &app.batch_confirm else {
+        return;
+    };
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
 
-    render_worktree_list(frame, app, content_chunks[0]);
-    render_details_panel(frame, app, content_chunks[1]);
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                batch.title.as_str(),
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::styled(
+                format!(" ({} worktrees) ", batch.affected.len()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::WARNING))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(1, 1, 1, 1));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    frame.render_widget(
+        Paragraph::new(Line::styled(
+            "Review every affected worktree before continuing:",
+            Style::default().fg(colors::CLAUDE_CREAM),
+        )),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
+
+    let items: Vec<ListItem> = batch
+        .affected
+        .iter()
+        .map(|line| {
+            ListItem::new(Line::from(vec![
+                Span::styled("● ", Style::default().fg(colors::WARNING)),
+                Span::styled(
+                    truncate_str(line, inner.width.saturating_sub(4) as usize),
+                    Style::default().fg(colors::CLAUDE_CREAM),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(colors::SELECTION_BG))
+        .highlight_symbol(" ");
+    frame.render_stateful_widget(
+        list,
+        Rect::new(
+            inner.x,
+            inner.y + 2,
+            inner.width,
+            inner.height.saturating_sub(4),
+        ),
+        &mut app.batch_list_state,
+    );
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("y/Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" confirm  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("j/k", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" scroll  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("n/Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
 }
 
-fn render_worktree_list(frame: &mut Frame, app: &mut App, area: Rect) {
-    app.list_area = Some(area);
+fn render_merge_dialog(frame: &mut Frame, app: &mut App) {
+    let area = centered_rect(50, 60, frame.area());
+    frame.render_widget(Clear, area);
 
-    let border_color = colors::BORDER_INACTIVE;
+    // Get source branch name
+    let source_branch = app
+        .merge_source_idx
+        .and_then(|idx| app.worktrees.get(idx))
+        .and_then(|wt| wt.branch.clone())
+        .unwrap_or_else(|| "unknown".to_string());
 
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
-            Span::styled("Worktrees", Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::styled(
+                "Merge Branch",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
             Span::raw(" "),
         ]))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(border_color))
-        .padding(Padding::horizontal(1));
-
-    let header_cells = ["#", "", "Branch", "Status", "Commit"]
-        .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(colors::CLAUDE_WARM_GRAY)));
-    let header = Row::new(header_cells).height(1).bottom_margin(1);
-
-    let rows: Vec<Row> = app
-        .filtered_indices
-        .iter()
-        .enumerate()
-        .map(|(display_idx, &idx)| {
-            let wt = &app.worktrees[idx];
-            // get the main worktree too if not main already
-            let main_wt = if wt.is_main {
-                Some(wt)
-            } else {
-                app.worktrees.iter().find(|wt| wt.is_main)
-            };
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(2, 2, 1, 1));
 
-            if main_wt.is_none() {
-                app.error_message = "No main worktree found!".into();
-                app.mode = AppMode::Error;
-            }
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
 
-            let num = if display_idx < 9 {
-                Span::styled(
-                    format!("{}", display_idx + 1),
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
-                )
-            } else {
-                Span::raw(" ")
-            };
+    // Source branch display with arrow
+    let merge_direction = Line::from(vec![
+        Span::styled(&source_branch, Style::default().fg(colors::INFO).bold()),
+        Span::styled("  ---→  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled(
+            "(select target below)",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+        ),
+    ]);
+    frame.render_widget(
+        Paragraph::new(merge_direction).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
 
-            let icon = if wt.is_current {
-                // Highlight the worktree we're currently in
-                Span::styled("*", Style::default().fg(colors::CLAUDE_CREAM)) // other ones: ○
-            } else if wt.is_main {
-                Span::styled("", Style::default().fg(colors::CLAUDE_ORANGE))
-            } else if wt.is_locked {
-                Span::styled("", Style::default().fg(colors::WARNING))
-            } else if wt.is_prunable {
-                Span::styled("", Style::default().fg(colors::ERROR))
-            } else {
-                Span::styled("", Style::default().fg(colors::INFO))
-            };
+    // Branch list area
+    let list_area = Rect::new(
+        inner.x,
+        inner.y + 2,
+        inner.width,
+        inner.height.saturating_sub(4),
+    );
 
-            let branch_name = wt.branch.as_deref().unwrap_or(if wt.is_detached {
-                "(detached)"
-            } else {
-                "(bare)"
-            });
-            let branch_style = if wt.is_main {
-                Style::default().fg(colors::CLAUDE_ORANGE)
-            } else if wt.is_detached {
-                Style::default().fg(colors::WARNING)
+    let items: Vec<ListItem> = app
+        .available_branches
+        .iter()
+        .map(|b| {
+            let is_source = b.name == source_branch;
+            let style = if is_source {
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic()
+            } else if b.is_current {
+                Style::default().fg(colors::CLAUDE_ORANGE).bold()
             } else {
                 Style::default().fg(colors::CLAUDE_CREAM)
             };
-
-            let status_style = if wt.status.is_clean() {
-                Style::default().fg(colors::SUCCESS)
+            let prefix = if is_source {
+                " ✗ "
+            } else if b.is_current {
+                "  "
             } else {
-                Style::default().fg(colors::WARNING)
+                "   "
             };
-
-            // make commits in table that are matching the main one highlight in purple
-            let commit_style = if wt.is_main {
-                Style::default().fg(colors::PURPLE)
-            } else {
-                if main_wt.is_none() {
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY)
+            ListItem::new(Line::from(vec![
+                Span::styled(prefix, style),
+                Span::styled(&b.name, style),
+                if is_source {
+                    Span::styled(
+                        " (source)",
+                        Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+                    )
                 } else {
-                    if wt.commit == main_wt.unwrap().commit {
-                        Style::default().fg(colors::PURPLE)
-                    } else {
-                        Style::default().fg(colors::CLAUDE_WARM_GRAY)
-                    }
-                }
-            };
-
-            Row::new(vec![
-                Cell::from(num),
-                Cell::from(icon),
-                Cell::from(Span::styled(branch_name, branch_style)),
-                Cell::from(Span::styled(wt.status.summary(), status_style)),
-                Cell::from(Span::styled(&wt.commit_short, commit_style)),
-            ])
-            .height(1)
+                    Span::raw("")
+                },
+            ]))
         })
         .collect();
 
-    let widths = [
-        Constraint::Length(2),
-        Constraint::Length(2),
-        Constraint::Min(12),
-        Constraint::Length(12),
-        Constraint::Length(8),
-    ];
-
-    let table = Table::new(rows, widths)
-        .header(header)
-        .block(block)
-        .row_highlight_style(
-            Style::default().bg(colors::SELECTION_BG), // .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(Span::styled(
-            "→ ",
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
-        ));
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(colors::SELECTION_BG))
+        .highlight_symbol(" ");
 
-    frame.render_stateful_widget(table, area, &mut app.table_state);
+    frame.render_stateful_widget(list, list_area, &mut app.branch_list_state);
 
-    // Scrollbar
-    if app.filtered_indices.len() > (area.height - 4) as usize {
-        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
-            .begin_symbol(Some(""))
-            .end_symbol(Some(""));
-        let mut scrollbar_state = ScrollbarState::new(app.filtered_indices.len())
-            .position(app.table_state.selected().unwrap_or(0));
-        frame.render_stateful_widget(
-            scrollbar,
-            area.inner(Margin {
-                vertical: 1,
-                horizontal: 0,
-            }),
-            &mut scrollbar_state,
-        );
-    }
+    // Footer instructions
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" confirm  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("j/k", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" navigate  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("s", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                format!(" strategy: {}  ", app.merge_strategy.label()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
 }
 
-fn render_details_panel(frame: &mut Frame, app: &App, area: Rect) {
-    let border_color = colors::BORDER_INACTIVE;
+fn render_merge_confirm_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(45, 35, frame.area());
+    frame.render_widget(Clear, area);
+
+    // Get source and target branch names
+    let source_branch = app
+        .merge_source_idx
+        .and_then(|idx| app.worktrees.get(idx))
+        .and_then(|wt| wt.branch.clone())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let target_branch = app
+        .merge_target_branch
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
 
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
-            Span::styled("Details", Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::styled("Confirm Merge", Style::default().fg(colors::WARNING).bold()),
             Span::raw(" "),
         ]))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(border_color))
+        .border_style(Style::default().fg(colors::WARNING))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
         .padding(Padding::new(2, 2, 1, 1));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    if let Some(wt) = app.selected_worktree() {
-        let mut lines = Vec::new();
-
-        // --- Identity & Status ---
-        let branch_name = wt.branch.as_deref().unwrap_or(if wt.is_detached {
-            "(detached)"
-        } else {
-            "(bare)"
-        });
-        lines.push(Line::from(vec![
-            Span::styled(branch_name, Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::raw(" "),
-            if wt.is_main {
-                Span::styled("[MAIN]", Style::default().fg(colors::PURPLE))
-            } else {
-                Span::raw("")
-            },
-        ]));
-
-        let mut status_spans = vec![Span::raw("  ")];
-        if wt.status.is_clean() {
-            status_spans.push(Span::styled("Clean", Style::default().fg(colors::SUCCESS)));
-        } else {
-            status_spans.push(Span::styled(
-                "Modified",
-                Style::default().fg(colors::WARNING),
-            ));
-            status_spans.push(Span::raw(" "));
-            let mut parts = Vec::new();
-            if wt.status.staged > 0 {
-                parts.push(Span::styled(
-                    format!("+{}", wt.status.staged),
-                    Style::default().fg(colors::SUCCESS),
-                ));
-            }
-            if wt.status.modified > 0 {
-                parts.push(Span::styled(
-                    format!("~{}", wt.status.modified),
-                    Style::default().fg(colors::WARNING),
-                ));
-            }
-            if wt.status.untracked > 0 {
-                parts.push(Span::styled(
-                    format!("?{}", wt.status.untracked),
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
-                ));
-            }
-
-            for (i, part) in parts.into_iter().enumerate() {
-                if i > 0 {
-                    status_spans.push(Span::raw(" "));
-                }
-                status_spans.push(part);
-            }
-            // status_spans.push(Span::raw(")"));
-        }
-
-        if wt.status.ahead > 0 || wt.status.behind > 0 {
-            status_spans.push(Span::styled(
-                " • ",
-                Style::default().fg(colors::CLAUDE_WARM_GRAY),
-            ));
-            if wt.status.ahead > 0 {
-                status_spans.push(Span::styled(
-                    format!("↑{}", wt.status.ahead),
-                    Style::default().fg(colors::SUCCESS),
-                ));
-                if wt.status.behind > 0 {
-                    status_spans.push(Span::raw(" "));
-                }
-            }
-            if wt.status.behind > 0 {
-                status_spans.push(Span::styled(
-                    format!("↓{}", wt.status.behind),
-                    Style::default().fg(colors::ERROR),
-                ));
-            }
-        }
-        lines.push(Line::from(status_spans));
-        lines.push(Line::raw(""));
-
-        // --- Location ---
-        lines.push(Line::from(Span::styled(
-            "Location",
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
-        )));
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled(
-                truncate_path(&wt.path, inner.width.saturating_sub(4) as usize),
-                Style::default().fg(colors::CLAUDE_CREAM),
-            ),
-        ]));
-        lines.push(Line::raw(""));
-
-        // --- Current Commit ---
-        lines.push(Line::from(Span::styled(
-            "Current Commit",
-            Style::default().fg(colors::CLAUDE_WARM_GRAY),
-        )));
-        let time_ago = wt
-            .recent_commits
-            .first()
-            .map(|c| c.time_ago.clone())
-            .unwrap_or_default();
-        lines.push(Line::from(vec![
-            Span::raw("  "),
-            Span::styled(&wt.commit_short, Style::default().fg(colors::INFO)),
-            Span::styled(
-                format!(" • {}", time_ago),
-                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-            ),
-        ]));
-
-        if !wt.commit_message.is_empty() {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
+    // Confirmation message
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Merge ", Style::default().fg(colors::CLAUDE_CREAM)),
+                Span::styled(&source_branch, Style::default().fg(colors::INFO).bold()),
+                Span::styled(" into ", Style::default().fg(colors::CLAUDE_CREAM)),
                 Span::styled(
-                    &wt.commit_message,
-                    Style::default().fg(colors::CLAUDE_CREAM).italic(),
+                    &target_branch,
+                    Style::default().fg(colors::CLAUDE_ORANGE).bold(),
                 ),
-            ]));
-        }
-        lines.push(Line::raw(""));
-
-        // --- Attributes ---
-        if wt.is_locked || wt.is_prunable {
-            lines.push(Line::from(Span::styled(
-                "Attributes",
-                Style::default().fg(colors::CLAUDE_WARM_GRAY),
-            )));
-            if wt.is_locked {
-                lines.push(Line::from(vec![
-                    Span::raw("  Locked: "),
-                    Span::styled(
-                        wt.lock_reason.as_deref().unwrap_or("no reason provided"),
-                        Style::default().fg(colors::WARNING).italic(),
+                Span::styled("?", Style::default().fg(colors::CLAUDE_CREAM)),
+            ]),
+            Line::raw(""),
+            Line::from(vec![
+                Span::styled(&source_branch, Style::default().fg(colors::INFO)),
+                Span::styled("  ------→  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+                Span::styled(&target_branch, Style::default().fg(colors::CLAUDE_ORANGE)),
+            ]),
+            Line::raw(""),
+            match app.merge_preview {
+                Some((commits, fast_forward)) => Line::from(vec![
+                    Span::styled(
+                        format!("{} commit{}", commits, if commits == 1 { "" } else { "s" }),
+                        Style::default().fg(if commits == 0 {
+                            colors::CLAUDE_WARM_GRAY
+                        } else {
+                            colors::INFO
+                        }),
                     ),
-                ]));
-            }
-            if wt.is_prunable {
-                lines.push(Line::from(vec![
-                    Span::raw("  Prunable: "),
                     Span::styled(
-                        "Worktree path is missing or invalid",
-                        Style::default().fg(colors::ERROR).italic(),
+                        ", fast-forward: ",
+                        Style::default().fg(colors::CLAUDE_WARM_GRAY),
                     ),
-                ]));
-            }
-            lines.push(Line::raw(""));
-        }
-
-        // --- History ---
-        if app.show_recent_commits && wt.recent_commits.len() > 1 {
-            lines.push(Line::from(vec![
-                Span::styled(
-                    "Recent History",
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
-                ),
-                Span::styled(
-                    " (t to toggle)",
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-                ),
-            ]));
-
-            for commit in wt.recent_commits.iter().skip(1).take(8) {
-                let msg = truncate_str(&commit.message, inner.width.saturating_sub(16) as usize);
-                lines.push(Line::from(vec![
                     Span::styled(
-                        format!("  {} ", commit.hash),
-                        Style::default().fg(colors::PURPLE),
+                        if fast_forward { "yes" } else { "no" },
+                        Style::default().fg(if fast_forward {
+                            colors::SUCCESS
+                        } else {
+                            colors::WARNING
+                        }),
                     ),
-                    Span::styled(msg, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-                ]));
-            }
-        }
-
-        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
-    } else {
-        frame.render_widget(
-            Paragraph::new(Span::styled(
-                "No worktree selected",
+                ]),
+                None => Line::raw(""),
+            },
+            Line::styled(
+                match app.merge_strategy.flag() {
+                    Some(flag) => {
+                        format!("In {}: git merge {} {}", target_branch, flag, source_branch)
+                    }
+                    None => format!("In {}: git merge {}", target_branch, source_branch),
+                },
                 Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-            ))
-            .alignment(Alignment::Center),
-            inner,
-        );
-    }
+            ),
+        ])
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + 1, inner.width, 6),
+    );
+
+    // Yes/No buttons
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                " y ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::SUCCESS),
+            ),
+            Span::styled(
+                " Yes, merge  ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(
+                " n ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(" Cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 2, inner.width, 1),
+    );
 }
 
-fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+fn render_exclude_confirm_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 30, frame.area());
+    frame.render_widget(Clear, area);
+
     let block = Block::default()
-        // .borders(Borders::TOP)
-        // .border_type(BorderType::LightDoubleDashed)
-        // .border_style(Style::default().fg(colors::BORDER_INACTIVE))
-        .padding(Padding::top(1));
-    // .padding(Padding::horizontal(1));
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled(
+                "Ignore Worktrees Directory",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER))
+        .padding(Padding::new(2, 2, 1, 1));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let layout = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(inner);
-
-    let mode_hints = match app.mode {
-        AppMode::Normal => vec![
-            ("j/k", "nav"),
-            ("1-9", "jump"),
-            ("n/N", "new worktree"),
-            ("space", "cd into"),
-            ("x", "delete"),
-            ("m", "merge"),
-            ("p/P", "pull/push"),
-            ("s", "sort"),
-            ("/", "search"),
-        ],
-        AppMode::Search => vec![("Enter", "confirm"), ("Esc", "cancel")],
-        _ => vec![("Esc", "cancel")],
-    };
-
-    let hints: Vec<Span> = mode_hints
-        .iter()
-        .flat_map(|(key, action)| {
-            vec![
-                Span::styled(*key, Style::default().fg(colors::CLAUDE_ORANGE)),
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(vec![
                 Span::styled(
-                    format!(" {}  ", action),
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                    &app.exclude_pattern,
+                    Style::default().fg(colors::INFO).bold(),
                 ),
-            ]
-        })
-        .collect();
+                Span::styled(
+                    " is inside the main worktree and not ignored,",
+                    Style::default().fg(colors::CLAUDE_CREAM),
+                ),
+            ]),
+            Line::styled(
+                "so it would show up there as untracked.",
+                Style::default().fg(colors::CLAUDE_CREAM),
+            ),
+            Line::raw(""),
+            Line::styled(
+                "Add it to .git/info/exclude?",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+            ),
+        ])
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true }),
+        Rect::new(
+            inner.x,
+            inner.y,
+            inner.width,
+            inner.height.saturating_sub(2),
+        ),
+    );
 
     frame.render_widget(
-        Paragraph::new(Line::from(hints)),
-        Rect::new(layout[0].x + 1, layout[0].y, layout[0].width, 1),
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                " y ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::SUCCESS),
+            ),
+            Span::styled(
+                " Yes, exclude  ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(
+                " n ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(" Not now", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
+        .alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
     );
-
-    // Build right side content: spinner (if loading) + status message
-    let mut right_spans: Vec<Span> = Vec::new();
-
-    // Add spinner if loading
-    if app.loading_state == LoadingState::Loading {
-        let spinner_char = SPINNER_FRAMES[app.spinner_frame];
-        right_spans.push(Span::styled(
-            format!("{} ", spinner_char),
-            Style::default().fg(colors::BORDER_INACTIVE),
-        ));
-    }
-
-    if let Some(ref msg) = app.status_message {
-        let color = match msg.level {
-            MessageLevel::Info => colors::INFO,
-            MessageLevel::Success => colors::SUCCESS,
-            MessageLevel::Warning => colors::WARNING,
-            MessageLevel::Error => colors::ERROR,
-        };
-        right_spans.push(Span::styled(&msg.text, Style::default().fg(color)));
-    }
-
-    if !right_spans.is_empty() {
-        frame.render_widget(
-            Paragraph::new(Line::from(right_spans)).alignment(Alignment::Right),
-            Rect::new(layout[1].x, layout[1].y, layout[1].width - 1, 1),
-        );
-    }
 }
 
-fn render_help_dialog(frame: &mut Frame) {
-    let area = centered_rect(65, 75, frame.area());
+fn render_branch_exists_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 25, frame.area());
     frame.render_widget(Clear, area);
 
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
             Span::styled(
-                "Keyboard Shortcuts",
+                "Branch Exists",
                 Style::default().fg(colors::CLAUDE_ORANGE).bold(),
             ),
             Span::raw(" "),
@@ -2278,289 +8537,224 @@ fn render_help_dialog(frame: &mut Frame) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    let help_text = vec![
-        (
-            "Navigation",
-            vec![
-                "j/k /        Move down/up",
-                "g / G            Go to first/last",
-                "1-9              Jump to item",
-                "Ctrl+d/u         Page down/up",
-                "Tab              Switch pane",
-            ],
-        ),
-        (
-            "Git Operations",
-            vec![
-                "n / N            New worktree / from current",
-                "Shift+Tab        Toggle new/existing branch",
-                "x / Del          Delete worktree",
-                "L                Toggle lock",
-                "p                Pull (in worktree)",
-                "P                Push (from worktree)",
-                "F                Fetch all remotes",
-                "r / R            Refresh list",
-                "X                Prune stale",
-                "m                Merge branch",
-            ],
-        ),
-        (
-            "Utilities",
-            vec![
-                "Space            Change to worktree dir",
-                "y                Copy path to clipboard",
-                "O                Open in file manager",
-                "s                Cycle sort order",
-                "t                Toggle recent commits",
-                "/                Search worktrees",
-                "?                Toggle this help",
-                "q / Esc          Quit",
-            ],
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Branch ", Style::default().fg(colors::CLAUDE_CREAM)),
+                Span::styled(
+                    app.create_input.trim(),
+                    Style::default().fg(colors::INFO).bold(),
+                ),
+                Span::styled(
+                    " already exists.",
+                    Style::default().fg(colors::CLAUDE_CREAM),
+                ),
+            ]),
+            Line::raw(""),
+            Line::styled(
+                "Check it out in a new worktree instead?",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+            ),
+        ])
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true }),
+        Rect::new(
+            inner.x,
+            inner.y,
+            inner.width,
+            inner.height.saturating_sub(2),
         ),
-    ];
-
-    let mut y = 0;
-    for (section, items) in help_text {
-        frame.render_widget(
-            Paragraph::new(Span::styled(
-                section,
-                Style::default().fg(colors::CLAUDE_CREAM).bold(),
-            )),
-            Rect::new(inner.x, inner.y + y, inner.width, 1),
-        );
-        y += 1;
-
-        for item in items {
-            frame.render_widget(
-                Paragraph::new(Span::styled(
-                    format!("  {}", item),
-                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
-                )),
-                Rect::new(inner.x, inner.y + y, inner.width, 1),
-            );
-            y += 1;
-        }
-        y += 1;
-    }
+    );
 
     frame.render_widget(
-        Paragraph::new(Span::styled(
-            "Press Esc or ? to close",
-            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-        ))
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                " y ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::SUCCESS),
+            ),
+            Span::styled(
+                " Check out  ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(
+                " n ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(" Back", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ]))
         .alignment(Alignment::Center),
         Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
     );
 }
 
-fn render_create_dialog(frame: &mut Frame, app: &App) {
-    let area = centered_rect(50, 40, frame.area());
+fn render_delete_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(50, 25, frame.area());
     frame.render_widget(Clear, area);
 
+    let wt_name = app
+        .selected_worktree()
+        .and_then(|w| w.branch.clone())
+        .unwrap_or_else(|| "this worktree".into());
+    let lock_reason = app
+        .selected_worktree()
+        .filter(|w| w.is_locked)
+        .map(|w| w.lock_reason.clone().unwrap_or_default());
+    let is_current = app.selected_worktree().is_some_and(|w| w.is_current);
+
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
-            Span::styled(
-                "Create New Worktree",
-                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
-            ),
+            Span::styled("Delete Worktree", Style::default().fg(colors::ERROR).bold()),
             Span::raw(" "),
         ]))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .border_style(Style::default().fg(colors::ERROR))
         .style(Style::default().bg(colors::CLAUDE_DARKER))
         .padding(Padding::new(2, 2, 1, 1));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Mode toggle indicator
-    frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled("Mode: ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    let mut lines = vec![
+        Line::from(vec![
             Span::styled(
-                if app.create_checkout_existing {
-                    "Checkout Existing"
-                } else {
-                    "Create New Branch"
-                },
-                Style::default()
-                    .fg(if app.create_checkout_existing {
-                        colors::INFO
-                    } else {
-                        colors::SUCCESS
-                    })
-                    .bold(),
+                "Are you sure you want to delete ",
+                Style::default().fg(colors::CLAUDE_CREAM),
             ),
-            Span::raw(" "),
-            Span::styled(
-                "(Shift+Tab to toggle)",
+            Span::styled(&wt_name, Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled("?", Style::default().fg(colors::CLAUDE_CREAM)),
+        ]),
+        Line::raw(""),
+        match &lock_reason {
+            Some(reason) if !reason.is_empty() => Line::styled(
+                format!("Worktree is locked: {}", reason),
+                Style::default().fg(colors::WARNING),
+            ),
+            Some(_) => Line::styled("Worktree is locked.", Style::default().fg(colors::WARNING)),
+            None => Line::styled(
+                "This action cannot be undone.",
                 Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
             ),
-        ])),
-        Rect::new(inner.x, inner.y, inner.width, 1),
-    );
-
-    let label = if app.create_checkout_existing {
-        "Worktree directory:"
-    } else {
-        "Worktree name:"
-    };
-
-    let label_y = inner.y + 2;
-    frame.render_widget(
-        Paragraph::new(Span::styled(
-            label,
-            Style::default().fg(colors::CLAUDE_CREAM),
-        )),
-        Rect::new(inner.x, label_y, inner.width, 1),
-    );
-
-    let input_area = Rect::new(inner.x, label_y + 2, inner.width, 3);
-    let input_block = Block::default()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(if app.mode == AppMode::Create {
-            colors::CLAUDE_ORANGE
-        } else {
-            colors::BORDER_INACTIVE
-        }));
+        },
+    ];
+    if is_current {
+        lines.push(Line::styled(
+            "This is the worktree you're in; wtt will cd to main on exit.",
+            Style::default().fg(colors::WARNING),
+        ));
+    }
 
     frame.render_widget(
-        Paragraph::new(app.create_input.as_str())
-            .block(input_block)
-            .style(Style::default().fg(colors::CLAUDE_CREAM)),
-        input_area,
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        Rect::new(
+            inner.x,
+            inner.y + 1,
+            inner.width,
+            inner.height.saturating_sub(3),
+        ),
     );
 
-    if app.mode == AppMode::Create {
-        frame.set_cursor_position((
-            input_area.x + app.create_cursor as u16 + 1,
-            input_area.y + 1,
-        ));
-    }
-
-    let branch_label = if app.create_checkout_existing {
-        "Branch to checkout:"
+    let (confirm_key, confirm_label) = if lock_reason.is_some() {
+        (" u ", " Unlock & delete  ")
     } else {
-        "Base branch:"
+        (" y ", " Yes  ")
     };
 
     frame.render_widget(
         Paragraph::new(Line::from(vec![
-            Span::styled(branch_label, Style::default().fg(colors::CLAUDE_CREAM)),
-            Span::raw(" "),
             Span::styled(
-                app.create_from_branch
-                    .as_deref()
-                    .unwrap_or(if app.create_checkout_existing {
-                        "(select branch)"
-                    } else {
-                        "HEAD (base?)"
-                    }),
-                Style::default().fg(colors::CLAUDE_ORANGE),
+                confirm_key,
+                Style::default().fg(colors::CLAUDE_DARKER).bg(colors::ERROR),
             ),
-        ])),
-        Rect::new(inner.x, label_y + 6, inner.width, 1),
-    );
-
-    frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled("Shift+Tab", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" mode  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Tab", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" branch  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" create  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(confirm_label, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(
+                " n ",
+                Style::default()
+                    .fg(colors::CLAUDE_DARKER)
+                    .bg(colors::CLAUDE_WARM_GRAY),
+            ),
+            Span::styled(" No", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
         ]))
         .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+        Rect::new(inner.x, inner.y + inner.height - 2, inner.width, 1),
     );
 }
 
-fn render_branch_select_dialog(frame: &mut Frame, app: &mut App, title: &str) {
-    let area = centered_rect(40, 50, frame.area());
-    frame.render_widget(Clear, area);
-
-    let block = Block::default()
-        .title(Line::from(vec![
-            Span::raw(" "),
-            Span::styled(title, Style::default().fg(colors::CLAUDE_ORANGE).bold()),
-            Span::raw(" "),
-        ]))
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
-        .style(Style::default().bg(colors::CLAUDE_DARKER))
-        .padding(Padding::new(1, 1, 1, 1));
-
-    let inner = block.inner(area);
-    frame.render_widget(block, area);
-
-    let items: Vec<ListItem> = app
-        .available_branches
-        .iter()
-        .map(|b| {
-            let style = if b.is_current {
-                Style::default().fg(colors::CLAUDE_ORANGE).bold()
-            } else if b.is_remote {
-                Style::default().fg(colors::INFO)
-            } else {
-                Style::default().fg(colors::CLAUDE_CREAM)
-            };
-            let prefix = if b.is_current {
-                " "
-            } else if b.is_remote {
-                " "
-            } else {
-                "  "
-            };
-            ListItem::new(Line::from(vec![
-                Span::styled(prefix, style),
-                Span::styled(&b.name, style),
-            ]))
+fn render_commit_dialog(frame: &mut Frame, app: &App) {
+    let (wt_name, staged) = app
+        .selected_worktree()
+        .map(|w| {
+            (
+                w.branch.clone().unwrap_or_else(|| w.commit_short.clone()),
+                w.status.staged,
+            )
         })
-        .collect();
-
-    let list = List::new(items)
-        .highlight_style(
-            Style::default().bg(colors::SELECTION_BG), // .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(" ");
+        .unwrap_or_default();
 
-    frame.render_stateful_widget(list, inner, &mut app.branch_list_state);
+    render_text_input_dialog(
+        frame,
+        "Commit Staged Changes",
+        Line::from(vec![
+            Span::styled(
+                format!("+{} staged", staged),
+                Style::default().fg(colors::SUCCESS),
+            ),
+            Span::styled(" in ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(wt_name, Style::default().fg(colors::CLAUDE_ORANGE)),
+        ]),
+        "Message",
+        &app.commit_input,
+        app.commit_cursor,
+        "commit",
+    );
+}
 
-    frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" select  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-        ]))
-        .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+fn render_note_dialog(frame: &mut Frame, app: &App) {
+    let wt_name = app
+        .selected_worktree()
+        .map(|w| w.branch.clone().unwrap_or_else(|| w.commit_short.clone()))
+        .unwrap_or_default();
+
+    render_text_input_dialog(
+        frame,
+        "Worktree Note",
+        Line::from(vec![
+            Span::styled("Note for ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(wt_name, Style::default().fg(colors::CLAUDE_ORANGE)),
+        ]),
+        "Note",
+        &app.note_input,
+        app.note_cursor,
+        "save (empty removes)",
     );
 }
 
-fn render_merge_dialog(frame: &mut Frame, app: &mut App) {
-    let area = centered_rect(50, 60, frame.area());
+/// Popup with a line of context above a single text input, confirmed with Enter.
+/// `action` describes what Enter does in the hints
+fn render_text_input_dialog(
+    frame: &mut Frame,
+    title: &str,
+    context: Line,
+    label: &str,
+    input: &str,
+    cursor: usize,
+    action: &str,
+) {
+    let area = centered_rect(60, 30, frame.area());
     frame.render_widget(Clear, area);
 
-    // Get source branch name
-    let source_branch = app
-        .merge_source_idx
-        .and_then(|idx| app.worktrees.get(idx))
-        .and_then(|wt| wt.branch.clone())
-        .unwrap_or_else(|| "unknown".to_string());
-
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
             Span::styled(
-                "Merge Branch",
+                title.to_string(),
                 Style::default().fg(colors::CLAUDE_ORANGE).bold(),
             ),
             Span::raw(" "),
@@ -2574,75 +8768,40 @@ fn render_merge_dialog(frame: &mut Frame, app: &mut App) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Source branch display with arrow
-    let merge_direction = Line::from(vec![
-        Span::styled(&source_branch, Style::default().fg(colors::INFO).bold()),
-        Span::styled("  ---→  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-        Span::styled(
-            "(select target below)",
-            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-        ),
-    ]);
     frame.render_widget(
-        Paragraph::new(merge_direction).alignment(Alignment::Center),
+        Paragraph::new(context),
         Rect::new(inner.x, inner.y, inner.width, 1),
     );
 
-    // Branch list area
-    let list_area = Rect::new(
-        inner.x,
-        inner.y + 2,
-        inner.width,
-        inner.height.saturating_sub(4),
-    );
-
-    let items: Vec<ListItem> = app
-        .available_branches
-        .iter()
-        .map(|b| {
-            let is_source = b.name == source_branch;
-            let style = if is_source {
-                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic()
-            } else if b.is_current {
-                Style::default().fg(colors::CLAUDE_ORANGE).bold()
-            } else {
-                Style::default().fg(colors::CLAUDE_CREAM)
-            };
-            let prefix = if is_source {
-                " ✗ "
-            } else if b.is_current {
-                "  "
-            } else {
-                "   "
-            };
-            ListItem::new(Line::from(vec![
-                Span::styled(prefix, style),
-                Span::styled(&b.name, style),
-                if is_source {
-                    Span::styled(
-                        " (source)",
-                        Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-                    )
-                } else {
-                    Span::raw("")
-                },
-            ]))
-        })
-        .collect();
+    let input_area = Rect::new(inner.x, inner.y + 2, inner.width, 3);
+    let input_block = Block::default()
+        .title(Span::styled(
+            format!(" {} ", label),
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE));
 
-    let list = List::new(items)
-        .highlight_style(Style::default().bg(colors::SELECTION_BG))
-        .highlight_symbol(" ");
+    frame.render_widget(
+        Paragraph::new(input)
+            .block(input_block)
+            .style(Style::default().fg(colors::CLAUDE_CREAM)),
+        input_area,
+    );
 
-    frame.render_stateful_widget(list, list_area, &mut app.branch_list_state);
+    frame.set_cursor_position((
+        input_area.x + cursor_column(input, cursor) + 1,
+        input_area.y + 1,
+    ));
 
-    // Footer instructions
     frame.render_widget(
         Paragraph::new(Line::from(vec![
             Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" confirm  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("j/k", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" navigate  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(
+                format!(" {}  ", action),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
             Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
             Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
         ]))
@@ -2651,153 +8810,242 @@ fn render_merge_dialog(frame: &mut Frame, app: &mut App) {
     );
 }
 
-fn render_merge_confirm_dialog(frame: &mut Frame, app: &App) {
-    let area = centered_rect(45, 35, frame.area());
-    frame.render_widget(Clear, area);
-
-    // Get source and target branch names
-    let source_branch = app
-        .merge_source_idx
-        .and_then(|idx| app.worktrees.get(idx))
-        .and_then(|wt| wt.branch.clone())
-        .unwrap_or_else(|| "unknown".to_string());
+fn render_new_branch_dialog(frame: &mut Frame, app: &App) {
+    let (wt_name, commit_short) = app
+        .selected_worktree()
+        .map(|w| {
+            (
+                w.branch.clone().unwrap_or_else(|| "detached".into()),
+                w.commit_short.clone(),
+            )
+        })
+        .unwrap_or_default();
+
+    render_text_input_dialog(
+        frame,
+        "New Branch From Here",
+        Line::from(vec![
+            Span::styled("At ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(commit_short, Style::default().fg(colors::INFO)),
+            Span::styled(" (", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled(wt_name, Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                "), no worktree is created",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ]),
+        "Branch name",
+        &app.branch_input,
+        app.branch_cursor,
+        "create",
+    );
+}
 
-    let target_branch = app
-        .merge_target_branch
-        .clone()
-        .unwrap_or_else(|| "unknown".to_string());
+fn render_import_dialog(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 40, frame.area());
+    frame.render_widget(Clear, area);
 
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
-            Span::styled("Confirm Merge", Style::default().fg(colors::WARNING).bold()),
+            Span::styled(
+                "Import Existing Directory",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
             Span::raw(" "),
         ]))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colors::WARNING))
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
         .style(Style::default().bg(colors::CLAUDE_DARKER))
         .padding(Padding::new(2, 2, 1, 1));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
-    // Confirmation message
     frame.render_widget(
-        Paragraph::new(vec![
-            Line::from(vec![
-                Span::styled("Merge ", Style::default().fg(colors::CLAUDE_CREAM)),
-                Span::styled(&source_branch, Style::default().fg(colors::INFO).bold()),
-                Span::styled(" into ", Style::default().fg(colors::CLAUDE_CREAM)),
-                Span::styled(
-                    &target_branch,
-                    Style::default().fg(colors::CLAUDE_ORANGE).bold(),
-                ),
-                Span::styled("?", Style::default().fg(colors::CLAUDE_CREAM)),
-            ]),
-            Line::raw(""),
-            Line::from(vec![
-                Span::styled(&source_branch, Style::default().fg(colors::INFO)),
-                Span::styled("  ------→  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-                Span::styled(&target_branch, Style::default().fg(colors::CLAUDE_ORANGE)),
-            ]),
-            Line::raw(""),
-            Line::styled(
-                format!("In {}: git merge {}", target_branch, source_branch),
-                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-            ),
-        ])
-        .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + 1, inner.width, 5),
+        Paragraph::new(Line::from(Span::styled(
+            "Empty directories get the branch checked out; orphaned worktrees are repaired",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ))),
+        Rect::new(inner.x, inner.y, inner.width, 1),
     );
 
-    // Yes/No buttons
+    let inputs = [
+        (
+            " Path (relative to the repo's parent) ",
+            &app.import_path_input,
+            app.import_path_cursor,
+            !app.import_branch_focused,
+        ),
+        (
+            " Branch ",
+            &app.import_branch_input,
+            app.import_branch_cursor,
+            app.import_branch_focused,
+        ),
+    ];
+    for (i, (title, input, cursor, focused)) in inputs.into_iter().enumerate() {
+        let input_area = Rect::new(inner.x, inner.y + 2 + i as u16 * 3, inner.width, 3);
+        let input_block = Block::default()
+            .title(Span::styled(
+                title,
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(if focused {
+                colors::CLAUDE_ORANGE
+            } else {
+                colors::BORDER_INACTIVE
+            }));
+
+        frame.render_widget(
+            Paragraph::new(input.as_str())
+                .block(input_block)
+                .style(Style::default().fg(colors::CLAUDE_CREAM)),
+            input_area,
+        );
+
+        if focused {
+            frame.set_cursor_position((
+                input_area.x + cursor_column(input, cursor) + 1,
+                input_area.y + 1,
+            ));
+        }
+    }
+
     frame.render_widget(
         Paragraph::new(Line::from(vec![
+            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" import  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("Tab", Style::default().fg(colors::CLAUDE_ORANGE)),
             Span::styled(
-                " y ",
-                Style::default()
-                    .fg(colors::CLAUDE_DARKER)
-                    .bg(colors::SUCCESS),
-            ),
-            Span::styled(
-                " Yes, merge  ",
+                " switch field  ",
                 Style::default().fg(colors::CLAUDE_WARM_GRAY),
             ),
-            Span::styled(
-                " n ",
-                Style::default()
-                    .fg(colors::CLAUDE_DARKER)
-                    .bg(colors::CLAUDE_WARM_GRAY),
-            ),
-            Span::styled(" Cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" cancel", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
         ]))
         .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + inner.height - 2, inner.width, 1),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
     );
 }
 
-fn render_delete_dialog(frame: &mut Frame, app: &App) {
-    let area = centered_rect(50, 25, frame.area());
+fn render_prune_wizard_dialog(frame: &mut Frame, app: &App) {
+    let (Some(wizard), Some(step)) = (app.prune_wizard.as_ref(), app.prune_wizard_step()) else {
+        return;
+    };
+    let area = centered_rect(60, 45, frame.area());
     frame.render_widget(Clear, area);
 
-    let wt_name = app
-        .selected_worktree()
-        .and_then(|w| w.branch.clone())
-        .unwrap_or_else(|| "this worktree".into());
-
     let block = Block::default()
         .title(Line::from(vec![
             Span::raw(" "),
-            Span::styled("Delete Worktree", Style::default().fg(colors::ERROR).bold()),
-            Span::raw(" "),
+            Span::styled(
+                "Resolve Prunable Worktrees",
+                Style::default().fg(colors::CLAUDE_ORANGE).bold(),
+            ),
+            Span::styled(
+                format!(" ({}/{}) ", wizard.current + 1, wizard.steps.len()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
         ]))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(colors::ERROR))
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
         .style(Style::default().bg(colors::CLAUDE_DARKER))
         .padding(Padding::new(2, 2, 1, 1));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
+    // git gives the same reason for both; what's on disk tells them apart
+    let diagnosis = if step.path.exists() {
+        "The directory is still there, but its link to the repository is broken."
+    } else {
+        "The directory is gone: moved or deleted."
+    };
+    let mut lines = vec![
+        Line::styled(&step.name, Style::default().fg(colors::CLAUDE_CREAM).bold()),
+        Line::styled(
+            step.path.display().to_string(),
+            Style::default().fg(colors::INFO),
+        ),
+        Line::raw(""),
+        Line::styled(diagnosis, Style::default().fg(colors::CLAUDE_CREAM)),
+    ];
+    if let Some(reason) = &step.reason {
+        lines.push(Line::styled(
+            format!("git: {}", reason),
+            Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
+        ));
+    }
+    if let Some(error) = &wizard.error {
+        lines.push(Line::raw(""));
+        lines.push(Line::styled(
+            error.as_str(),
+            Style::default().fg(colors::ERROR),
+        ));
+    }
     frame.render_widget(
-        Paragraph::new(vec![
-            Line::from(vec![
-                Span::styled(
-                    "Are you sure you want to delete ",
-                    Style::default().fg(colors::CLAUDE_CREAM),
-                ),
-                Span::styled(&wt_name, Style::default().fg(colors::CLAUDE_ORANGE).bold()),
-                Span::styled("?", Style::default().fg(colors::CLAUDE_CREAM)),
-            ]),
-            Line::raw(""),
-            Line::styled(
-                "This action cannot be undone.",
-                Style::default().fg(colors::CLAUDE_WARM_GRAY).italic(),
-            ),
-        ])
-        .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + 1, inner.width, 3),
+        Paragraph::new(lines).wrap(Wrap { trim: true }),
+        Rect::new(
+            inner.x,
+            inner.y,
+            inner.width,
+            inner
+                .height
+                .saturating_sub(if wizard.repairing { 5 } else { 2 }),
+        ),
     );
 
+    let hints: &[(&str, &str)] = if wizard.repairing {
+        let input_area = Rect::new(
+            inner.x,
+            inner.y + inner.height.saturating_sub(5),
+            inner.width,
+            3,
+        );
+        let input_block = Block::default()
+            .title(Span::styled(
+                " Where is it now? ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(colors::CLAUDE_ORANGE));
+        frame.render_widget(
+            Paragraph::new(wizard.repair_input.as_str())
+                .block(input_block)
+                .style(Style::default().fg(colors::CLAUDE_CREAM)),
+            input_area,
+        );
+        frame.set_cursor_position((
+            input_area.x + cursor_column(&wizard.repair_input, wizard.repair_cursor) + 1,
+            input_area.y + 1,
+        ));
+        &[("Enter", " repair  "), ("Esc", " back")]
+    } else {
+        &[
+            ("p", " prune  "),
+            ("r", " repair  "),
+            ("s", " skip  "),
+            ("Esc", " stop"),
+        ]
+    };
+    let spans: Vec<Span> = hints
+        .iter()
+        .flat_map(|&(key, label)| {
+            [
+                Span::styled(key, Style::default().fg(colors::CLAUDE_ORANGE)),
+                Span::styled(label, Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+            ]
+        })
+        .collect();
     frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled(
-                " y ",
-                Style::default().fg(colors::CLAUDE_DARKER).bg(colors::ERROR),
-            ),
-            Span::styled(" Yes  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled(
-                " n ",
-                Style::default()
-                    .fg(colors::CLAUDE_DARKER)
-                    .bg(colors::CLAUDE_WARM_GRAY),
-            ),
-            Span::styled(" No", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-        ]))
-        .alignment(Alignment::Center),
-        Rect::new(inner.x, inner.y + inner.height - 2, inner.width, 1),
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
     );
 }
 
@@ -2814,6 +9062,10 @@ fn render_search_bar(frame: &mut Frame, app: &App) {
         .title(Line::from(vec![
             Span::raw(" "),
             Span::styled(" Search", Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled(
+                format!(" ({}, Tab to switch)", app.search_scope.label()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
             Span::raw(" "),
         ]))
         .borders(Borders::ALL)
@@ -2834,7 +9086,50 @@ fn render_search_bar(frame: &mut Frame, app: &App) {
         inner,
     );
 
-    frame.set_cursor_position((inner.x + app.search_cursor as u16, inner.y));
+    frame.set_cursor_position((
+        inner.x + cursor_column(&app.search_query, app.search_cursor),
+        inner.y,
+    ));
+}
+
+fn render_command_bar(frame: &mut Frame, app: &App) {
+    let area = Rect::new(
+        frame.area().x + 1,
+        frame.area().height.saturating_sub(7),
+        frame.area().width.saturating_sub(2),
+        3,
+    );
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(Line::from(vec![
+            Span::raw(" "),
+            Span::styled("Command", Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::raw(" "),
+        ]))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(colors::CLAUDE_ORANGE))
+        .style(Style::default().bg(colors::CLAUDE_DARKER));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(":", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                app.command_input.as_str(),
+                Style::default().fg(colors::CLAUDE_CREAM),
+            ),
+        ])),
+        inner,
+    );
+
+    frame.set_cursor_position((
+        inner.x + 1 + cursor_column(&app.command_input, app.command_cursor),
+        inner.y,
+    ));
 }
 
 fn render_error_dialog(frame: &mut Frame, app: &App) {
@@ -2872,24 +9167,166 @@ fn render_error_dialog(frame: &mut Frame, app: &App) {
         Rect::new(inner.x, inner.y, inner.width, inner.height - 3),
     );
 
+    let mut hints = match app.error_action {
+        Some(ErrorAction::UnlockAndDelete) => vec![
+            Span::styled("u", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(
+                " unlock & delete  ",
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ],
+        Some(ErrorAction::InitRepo(_)) => vec![
+            Span::styled("i", Style::default().fg(colors::CLAUDE_ORANGE)),
+            Span::styled(" git init  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        ],
+        None => Vec::new(),
+    };
+    hints.extend([
+        Span::styled("y", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" copy  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Y", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(
+            " copy report  ",
+            Style::default().fg(colors::CLAUDE_WARM_GRAY),
+        ),
+        Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" close  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+        Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
+        Span::styled(" close", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
+    ]);
+
     frame.render_widget(
-        Paragraph::new(Line::from(vec![
-            Span::styled("y", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" copy  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Enter", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" close  ", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-            Span::styled("Esc", Style::default().fg(colors::CLAUDE_ORANGE)),
-            Span::styled(" close", Style::default().fg(colors::CLAUDE_WARM_GRAY)),
-        ]))
-        .alignment(Alignment::Center),
+        Paragraph::new(Line::from(hints)).alignment(Alignment::Center),
         Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
     );
 }
 
+/// Compact single-list view used by `--pick`
+fn render_picker(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(colors::CLAUDE_ORANGE).bold()),
+            Span::styled(&app.search_query, Style::default().fg(colors::CLAUDE_CREAM)),
+            Span::styled(
+                format!("  {}/{}", app.filtered_indices.len(), app.worktrees.len()),
+                Style::default().fg(colors::CLAUDE_WARM_GRAY),
+            ),
+        ])),
+        chunks[0],
+    );
+    frame.set_cursor_position((
+        chunks[0].x + 2 + cursor_column(&app.search_query, app.search_cursor),
+        chunks[0].y,
+    ));
+
+    let branch_width = app
+        .worktrees
+        .iter()
+        .map(|wt| wt.branch.as_deref().unwrap_or(&wt.commit_short).width())
+        .max()
+        .unwrap_or(0)
+        .clamp(8, 40) as u16;
+    let path_width = area.width.saturating_sub(branch_width + 3) as usize;
+
+    let rows: Vec<Row> = app
+        .filtered_indices
+        .iter()
+        .map(|&idx| {
+            let wt = &app.worktrees[idx];
+            let branch = wt.branch.as_deref().unwrap_or(&wt.commit_short);
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    truncate_text(branch, branch_width as usize, app.config.branch_truncation),
+                    Style::default().fg(if wt.is_main {
+                        colors::PURPLE
+                    } else {
+                        colors::CLAUDE_ORANGE
+                    }),
+                )),
+                Cell::from(Span::styled(
+                    truncate_path(
+                        &app.format_path(&wt.path),
+                        path_width,
+                        app.config.path_truncation,
+                    ),
+                    Style::default().fg(colors::CLAUDE_WARM_GRAY),
+                )),
+            ])
+        })
+        .collect();
+
+    let table = Table::new(rows, [Constraint::Length(branch_width), Constraint::Min(0)])
+        .column_spacing(1)
+        .row_highlight_style(Style::default().bg(colors::SELECTION_BG).bold())
+        .highlight_symbol("▌ ");
+
+    frame.render_stateful_widget(table, chunks[1], &mut app.table_state);
+}
+
 // ============================================================================
 // Utilities
 // ============================================================================
 
+/// Pick the git executable (`WTT_GIT` env var, then config `git_path`, then `git`)
+/// and check that it runs
+fn apply_git_path(config: &config::Config) -> Result<()> {
+    let path = std::env::var("WTT_GIT")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| config.git_path.clone())
+        .unwrap_or_default();
+    set_git_path(&path);
+
+    let runs = git_command()
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !runs {
+        let shown = if path.is_empty() { "git" } else { &path };
+        anyhow::bail!("git executable '{}' could not be run", shown);
+    }
+    Ok(())
+}
+
+fn pr_color(pr: &PrInfo) -> ratatui::style::Color {
+    match pr.state.as_str() {
+        "OPEN" => colors::SUCCESS,
+        "MERGED" => colors::PURPLE,
+        "CLOSED" => colors::ERROR,
+        _ => colors::CLAUDE_WARM_GRAY,
+    }
+}
+
+/// Two-letter initials of an author name ("Ada Lovelace" -> "AL", "ada" -> "AD")
+fn author_initials(name: &str) -> String {
+    let mut words = name.split_whitespace();
+    let initials: String = match (words.next(), words.last()) {
+        (Some(first), Some(last)) => first.chars().take(1).chain(last.chars().take(1)).collect(),
+        (Some(only), None) => only.chars().take(2).collect(),
+        _ => "?".into(),
+    };
+    initials.to_uppercase()
+}
+
+/// Stable color for an author, so the same person always gets the same one
+fn author_color(email: &str) -> ratatui::style::Color {
+    // FNV-1a: unlike the std hasher, guaranteed not to change between releases
+    let hash = email
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    colors::AUTHOR_PALETTE[(hash % colors::AUTHOR_PALETTE.len() as u64) as usize]
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -2929,8 +9366,25 @@ fn truncate_str(s: &str, max_len: usize) -> String {
     }
 }
 
-fn truncate_path(path: &PathBuf, max_len: usize) -> String {
-    let s = path.to_string_lossy();
+/// Shorten `s` to `max_len` columns, leaving out the part `style` gives up
+fn truncate_text(s: &str, max_len: usize, style: config::TruncateStyle) -> String {
+    // Too narrow to keep any of `s` next to the ellipsis
+    if max_len < 3 && s.width() > max_len {
+        return ".".repeat(max_len);
+    }
+    match style {
+        config::TruncateStyle::Start => truncate_start(s, max_len),
+        config::TruncateStyle::Middle => truncate_middle(s, max_len),
+        config::TruncateStyle::End => truncate_str(s, max_len),
+    }
+}
+
+fn truncate_path(path: &Path, max_len: usize, style: config::TruncateStyle) -> String {
+    truncate_text(&path.to_string_lossy(), max_len, style)
+}
+
+/// Keep the end of `s`, e.g. `...worktrees/feature`
+fn truncate_start(s: &str, max_len: usize) -> String {
     let width = s.width();
     if width <= max_len {
         s.to_string()
@@ -2945,14 +9399,58 @@ fn truncate_path(path: &PathBuf, max_len: usize) -> String {
             result.push(c);
             current_width += char_width;
         }
-        format!("...{}", result.chars().rev().collect::<String>())
-    }
+        format!("...{}", result.chars().rev().collect::<String>())
+    }
+}
+
+/// Keep both ends of `s`, e.g. `feature/JIRA-1...add-login`
+fn truncate_middle(s: &str, max_len: usize) -> String {
+    if s.width() <= max_len {
+        return s.to_string();
+    }
+    let budget = max_len.saturating_sub(3);
+    let take = |chars: &mut dyn Iterator<Item = char>, limit: usize| {
+        let mut taken = Vec::new();
+        let mut width = 0;
+        for c in chars {
+            let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            if width + char_width > limit {
+                break;
+            }
+            taken.push(c);
+            width += char_width;
+        }
+        taken
+    };
+    // The head gets the odd column: prefixes like `feature/` tend to be read first
+    let head = take(&mut s.chars(), budget.div_ceil(2));
+    let tail = take(&mut s.chars().rev(), budget / 2);
+    format!(
+        "{}...{}",
+        head.into_iter().collect::<String>(),
+        tail.into_iter().rev().collect::<String>()
+    )
 }
 
 // ============================================================================
 // Main
 // ============================================================================
 
+/// Max delay between digits for them to combine into one quick-jump number
+const JUMP_TIMEOUT: Duration = Duration::from_millis(800);
+
+/// How long the mouse rests on a row before its summary shows in the status bar
+const HOVER_DELAY: Duration = Duration::from_millis(300);
+
+/// How long pull request lookups are reused before asking gh again
+const PR_CACHE_TTL_SECS: u64 = 300;
+
+/// Search term that keeps only worktrees whose upstream branch was deleted
+const GONE_FILTER: &str = "is:gone";
+
+/// Where tracing output goes
+const LOG_PATH: &str = "/tmp/wtt.log";
+
 /// Spinner characters for loading indicator
 const SPINNER_FRAMES: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
 
@@ -2971,7 +9469,7 @@ async fn main() -> Result<()> {
     let log_file = std::fs::OpenOptions::new()
         .create(true)
         .append(true)
-        .open("/tmp/wtt.log")
+        .open(LOG_PATH)
         .expect("Failed to open log file");
 
     let (non_blocking, _guard) = tracing_appender::non_blocking(log_file);
@@ -2991,11 +9489,57 @@ async fn main() -> Result<()> {
         .init();
 
     info!("Starting worktree-tui");
+
+    // --print-shell-integration <shell>: print the `wt` wrapper function and exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(pos) = args
+        .iter()
+        .position(|arg| arg.starts_with("--print-shell-integration"))
+    {
+        let shell = match args[pos].strip_prefix("--print-shell-integration=") {
+            Some(shell) => Some(shell.to_string()),
+            None => args.get(pos + 1).cloned(),
+        }
+        .context("Usage: wtt --print-shell-integration <bash|zsh|fish|nu>")?;
+        let script = shell::integration_script(&shell).with_context(|| {
+            format!(
+                "Unsupported shell '{}' (expected bash, zsh, fish or nu)",
+                shell
+            )
+        })?;
+        print!("{}", script);
+        return Ok(());
+    }
+
     // Parse --cwd-file argument (for shell integration)
     let cwd_file: Option<PathBuf> = std::env::args()
         .skip(1)
         .find(|arg| arg.starts_with("--cwd-file="))
         .map(|arg| PathBuf::from(arg.strip_prefix("--cwd-file=").unwrap()));
+    let cwd_format = match std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.strip_prefix("--cwd-format=").map(str::to_string))
+    {
+        Some(value) => CwdFormat::parse(&value).with_context(|| {
+            format!(
+                "Unknown --cwd-format '{}' (expected path, cd or export)",
+                value
+            )
+        })?,
+        None => CwdFormat::Path,
+    };
+
+    // --pick: compact fuzzy picker instead of the full TUI
+    if std::env::args().skip(1).any(|arg| arg == "--pick") {
+        let picked = run_picker()?;
+        if let Some(path) = picked {
+            println!("{}", path.display());
+            if let Some(ref file_path) = cwd_file {
+                write_cwd_file(file_path, cwd_format, &path);
+            }
+        }
+        return Ok(());
+    }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -3003,7 +9547,11 @@ async fn main() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let app_result = App::new();
+    let read_only = std::env::args().skip(1).any(|arg| arg == "--read-only");
+    let app_result = App::new().map(|mut app| {
+        app.read_only = read_only;
+        app
+    });
 
     let result = match app_result {
         Ok(mut app) => run_app(&mut terminal, &mut app).await,
@@ -3016,7 +9564,11 @@ async fn main() -> Result<()> {
             )?;
             terminal.show_cursor()?;
             eprintln!("Error: {}", e);
-            eprintln!("\nMake sure you're running this from within a Git repository.");
+            if let Some(WttError::NotARepo(_)) = e.downcast_ref::<WttError>() {
+                eprintln!("\nRun wtt from within a Git repository, or `git init` to create one.");
+            } else {
+                eprintln!("\nMake sure you're running this from within a Git repository.");
+            }
             return Err(e);
         }
     };
@@ -3033,9 +9585,7 @@ async fn main() -> Result<()> {
         Ok(Some(cd_path)) => {
             // Write path to cwd-file for shell integration
             if let Some(ref file_path) = cwd_file {
-                if let Ok(mut file) = File::create(file_path) {
-                    let _ = writeln!(file, "{}", cd_path.display());
-                }
+                write_cwd_file(file_path, cwd_format, &cd_path);
             }
         }
         Ok(None) => {}
@@ -3046,6 +9596,106 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// What `--cwd-file` receives on exit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CwdFormat {
+    /// Just the path
+    Path,
+    /// An eval-able `cd '<path>'`
+    Cd,
+    /// `export`s of the worktree path and branch, then the `cd`
+    Export,
+}
+
+impl CwdFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "path" => Some(CwdFormat::Path),
+            "cd" => Some(CwdFormat::Cd),
+            "export" => Some(CwdFormat::Export),
+            _ => None,
+        }
+    }
+}
+
+/// Quote for POSIX shells: wrap in single quotes, escaping any inside
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Write the chosen worktree to the shell integration's cwd-file
+fn write_cwd_file(file_path: &Path, format: CwdFormat, path: &Path) {
+    let path_str = path.to_string_lossy();
+    let content = match format {
+        CwdFormat::Path => format!("{}\n", path_str),
+        CwdFormat::Cd => format!("cd {}\n", shell_quote(&path_str)),
+        CwdFormat::Export => {
+            let branch = git_command()
+                .current_dir(path)
+                .args(["symbolic-ref", "--short", "-q", "HEAD"])
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                .unwrap_or_default();
+            format!(
+                "export WTT_WORKTREE={}\nexport WTT_BRANCH={}\ncd {}\n",
+                shell_quote(&path_str),
+                shell_quote(&branch),
+                shell_quote(&path_str)
+            )
+        }
+    };
+    if let Ok(mut file) = File::create(file_path) {
+        let _ = file.write_all(content.as_bytes());
+    }
+}
+
+/// Run the `--pick` picker. It draws on stderr so stdout stays free for the
+/// chosen path (e.g. `cd "$(wtt --pick)"`)
+fn run_picker() -> Result<Option<PathBuf>> {
+    let mut app = App::new()?;
+
+    // The picker only needs paths and branches; list them directly on a cache miss
+    if app.worktrees.is_empty() {
+        app.worktrees = discover_worktrees(&app.repo_root, &app.current_worktree_path)?;
+    }
+    app.worktrees.retain(|wt| !wt.is_bare && wt.path.exists());
+    app.search_query.clear();
+    app.search_cursor = 0;
+    app.update_search_filter();
+    app.select_first();
+
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen)?;
+    let mut terminal: Terminal<CrosstermBackend<Stderr>> =
+        Terminal::new(CrosstermBackend::new(stderr))?;
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|f| render_picker(f, &mut app)) {
+            break Err(e.into());
+        }
+        match crossterm::event::read() {
+            Ok(Event::Key(key)) => {
+                if let Some(selection) = handle_picker_key(&mut app, key.code, key.modifiers) {
+                    if let Some(path) = &selection {
+                        app.record_access(path);
+                    }
+                    break Ok(selection);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     app: &mut App,
@@ -3055,11 +9705,11 @@ async fn run_app(
 
     // If we need to load/refresh, spawn background task
     if app.loading_state == LoadingState::Loading {
-        spawn_refresh_task(
-            tx.clone(),
-            app.repo_root.clone(),
-            app.current_worktree_path.clone(),
-        );
+        app.start_background_refresh(&tx);
+    }
+
+    if app.loading_state == LoadingState::Idle && app.pull_requests_stale() {
+        app.start_pull_request_lookup(&tx);
     }
 
     // Create async event stream
@@ -3072,12 +9722,41 @@ async fn run_app(
         // Render
         terminal.draw(|f| ui(f, app))?;
 
+        if let Some(path) = app.mergetool_path.take() {
+            run_mergetool(terminal, app, &path)?;
+            continue;
+        }
+
+        if let Some(path) = app.editor_path.take() {
+            run_editor(terminal, app, &path)?;
+            continue;
+        }
+
+        if std::mem::take(&mut app.log_requested) {
+            run_log_pager(terminal, app)?;
+            continue;
+        }
+
+        // Blocking ops run after the frame above has shown the busy state
+        if let Some(op) = app.pending_op.take() {
+            let destructive = op.is_destructive();
+            app.run_pending_op(op);
+            if destructive {
+                // Keys typed while the op ran were aimed at the old list
+                discard_pending_input();
+            }
+            continue;
+        }
+
         // Async event handling with tokio::select!
         tokio::select! {
             // Handle keyboard/mouse events
             maybe_event = event_stream.next() => {
                 if let Some(Ok(event)) = maybe_event {
                     if handle_event(app, event, &tx)? {
+                        // Don't leave a running verify command behind
+                        app.cancel_verify();
+                        app.save_ui_state();
                         return Ok(app.cd_path.take());
                     }
                 }
@@ -3086,12 +9765,25 @@ async fn run_app(
             // Handle background refresh updates
             Some(update) = rx.recv() => {
                 match update {
-                    AppUpdate::WorktreesLoaded(worktrees) => {
+                    AppUpdate::WorktreesLoaded(repo_root, ..) if repo_root != app.repo_root => {
+                        // Stale result from a repo we switched away from
+                    }
+                    AppUpdate::WorktreesLoaded(_, worktrees, elapsed) => {
                         let selected = app.table_state.selected();
                         app.worktrees = worktrees;
+                        app.update_main_branch();
+                        app.apply_pull_requests();
+                        app.recheck_merge_conflicts();
+                        app.check_integrity();
+                        app.load_stash_total();
                         app.apply_sort();
-                        app.filtered_indices = (0..app.worktrees.len()).collect();
+                        if app.search_query.is_empty() {
+                            app.filtered_indices = (0..app.worktrees.len()).collect();
+                        } else {
+                            app.update_search_filter();
+                        }
                         app.loading_state = LoadingState::Idle;
+                        app.cached_at = None;
                         app.save_to_cache();
 
                         // Restore selection
@@ -3105,7 +9797,54 @@ async fn run_app(
                             app.table_state.select(Some(0));
                         }
 
-                        app.set_status("Refreshed from background", MessageLevel::Success);
+                        app.record_refresh_duration(elapsed);
+                        app.set_status(&app.refresh_summary(), MessageLevel::Success);
+                        if std::mem::take(&mut app.refresh_requested) {
+                            app.ring_bell(false);
+                        }
+
+                        if app.pull_requests_stale() {
+                            app.start_pull_request_lookup(&tx);
+                        }
+                    }
+                    AppUpdate::PullRequestsLoaded(repo_root, _) if repo_root != app.repo_root => {}
+                    AppUpdate::PullRequestsLoaded(_, pull_requests) => {
+                        info!(count = pull_requests.len(), "Pull requests loaded");
+                        app.pull_requests = pull_requests;
+                        app.apply_pull_requests();
+                        app.save_to_cache();
+                    }
+                    AppUpdate::VerifyOutput(path, _) | AppUpdate::VerifyDone(path, ..)
+                        if app.verify_path.as_ref() != Some(&path) => {}
+                    AppUpdate::VerifyOutput(_, line) => {
+                        // Follow the output unless scrolled up to read something
+                        let following =
+                            app.verify_scroll + app.verify_page >= app.verify_output.len();
+                        app.verify_output.push(line);
+                        if following {
+                            app.verify_scroll =
+                                app.verify_output.len().saturating_sub(app.verify_page);
+                        }
+                    }
+                    AppUpdate::VerifyDone(_, code, elapsed) => {
+                        app.verify_result = Some((code, elapsed));
+                        let passed = code == Some(0);
+                        if app.mode != AppMode::Verify {
+                            app.set_status(
+                                &format!(
+                                    "Verify of {} {} in {:.1}s (K for output)",
+                                    app.verify_title,
+                                    if passed { "passed" } else { "failed" },
+                                    elapsed.as_secs_f64()
+                                ),
+                                if passed {
+                                    MessageLevel::Success
+                                } else {
+                                    MessageLevel::Warning
+                                },
+                            );
+                        }
+                        app.ring_bell(!passed);
                     }
                 }
             }
@@ -3121,141 +9860,332 @@ async fn run_app(
     }
 }
 
+/// Hand the terminal over to whatever `run` starts, then take it back, also when `run`
+/// failed or leaving the TUI went only part of the way
+fn with_suspended_terminal<T>(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    run: impl FnOnce() -> T,
+) -> Result<T> {
+    let suspended = disable_raw_mode()
+        .and_then(|()| {
+            execute!(
+                terminal.backend_mut(),
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )
+        })
+        .and_then(|()| terminal.show_cursor());
+    let result = suspended.map(|()| run());
+
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+    Ok(result?)
+}
+
+/// Leave the TUI, run `git mergetool` in `path` with the terminal, then come back
+/// and report what is still conflicted
+fn run_mergetool(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    path: &Path,
+) -> Result<()> {
+    let status = with_suspended_terminal(terminal, || {
+        git_command().current_dir(path).arg("mergetool").status()
+    })?;
+
+    if let Err(e) = status {
+        app.report_error(&anyhow::Error::from(e).context("Failed to run git mergetool"));
+        return Ok(());
+    }
+    app.recheck_merge_conflicts();
+    let remaining = app
+        .merge_conflicts
+        .as_ref()
+        .filter(|(conflict_path, _)| conflict_path == path)
+        .map_or(0, |(_, files)| files.len());
+    if let Err(e) = app.refresh_worktrees() {
+        app.report_error(&e);
+    } else if remaining == 0 {
+        app.set_status(
+            "All conflicts resolved, commit to finish the merge",
+            MessageLevel::Success,
+        );
+    } else {
+        app.set_status(
+            &format!(
+                "{} conflicted file{} left",
+                remaining,
+                if remaining == 1 { "" } else { "s" }
+            ),
+            MessageLevel::Warning,
+        );
+    }
+    Ok(())
+}
+
+/// Leave the TUI, run `$VISUAL` (or `$EDITOR`, or `vi`) in `path`, then come back
+fn run_editor(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    path: &Path,
+) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // Through the shell, since $EDITOR often carries flags (`code -w`)
+    let status = with_suspended_terminal(terminal, || {
+        Command::new("sh")
+            .args(["-c", &format!("{} .", editor)])
+            .current_dir(path)
+            .status()
+    })?;
+
+    match status {
+        Ok(status) if status.success() => {
+            app.record_access(path);
+            if let Err(e) = app.refresh_worktrees() {
+                app.report_error(&e);
+            }
+        }
+        Ok(status) => app.set_status(
+            &format!("{} exited with {}", editor, status),
+            MessageLevel::Warning,
+        ),
+        Err(e) => {
+            app.report_error(&anyhow::Error::from(e).context(format!("Failed to run {}", editor)))
+        }
+    }
+    Ok(())
+}
+
+/// Leave the TUI, page through the log file with `$PAGER` (or `less`), then come back
+fn run_log_pager(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> Result<()> {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| "less +G".to_string());
+
+    let status = with_suspended_terminal(terminal, || {
+        Command::new("sh")
+            .args(["-c", &format!("{} {}", pager, LOG_PATH)])
+            .status()
+    })?;
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => app.set_status(
+            &format!("{} exited with {}", pager, status),
+            MessageLevel::Warning,
+        ),
+        Err(e) => {
+            app.report_error(&anyhow::Error::from(e).context(format!("Failed to run {}", pager)))
+        }
+    }
+    Ok(())
+}
+
+/// Drop input events queued while the UI was blocked
+fn discard_pending_input() {
+    while crossterm::event::poll(Duration::ZERO).unwrap_or(false) {
+        if crossterm::event::read().is_err() {
+            break;
+        }
+    }
+}
+
 /// Spawn a background task to refresh worktree data
 fn spawn_refresh_task(
     tx: mpsc::UnboundedSender<AppUpdate>,
     repo_root: PathBuf,
     current_path: PathBuf,
+    main_branch: String,
+    max_parallel: usize,
+    show_ignored: bool,
+    cancel: Arc<AtomicBool>,
 ) {
     tokio::spawn(async move {
         // Run blocking git commands in a blocking task
-        let result =
-            tokio::task::spawn_blocking(move || fetch_all_worktrees(&repo_root, &current_path))
-                .await;
+        let root = repo_root.clone();
+        let start = Instant::now();
+        let result = tokio::task::spawn_blocking(move || {
+            fetch_all_worktrees(
+                &root,
+                &current_path,
+                Some(&main_branch),
+                max_parallel,
+                show_ignored,
+                &cancel,
+            )
+        })
+        .await;
 
         if let Ok(Ok(worktrees)) = result {
-            let _ = tx.send(AppUpdate::WorktreesLoaded(worktrees));
+            let _ = tx.send(AppUpdate::WorktreesLoaded(
+                repo_root,
+                worktrees,
+                start.elapsed(),
+            ));
         }
     });
 }
 
-/// Fetch all worktree data (runs in blocking thread with parallel git commands)
-fn fetch_all_worktrees(repo_root: &PathBuf, current_path: &PathBuf) -> Result<Vec<Worktree>> {
-    let start_all = Instant::now();
-    let output = Command::new("git")
-        .current_dir(repo_root)
-        .args(["worktree", "list", "--porcelain"])
-        .output()
-        .context("Failed to list worktrees")?;
-
-    if !output.status.success() {
-        anyhow::bail!("git worktree list failed");
-    }
+/// Run `command` in `path` on a blocking thread, sending its combined stdout and
+/// stderr line by line. Setting `cancel` kills it at the next line of output.
+fn spawn_verify_task(
+    tx: mpsc::UnboundedSender<AppUpdate>,
+    path: PathBuf,
+    command: String,
+    cancel: Arc<AtomicBool>,
+    pid: Arc<AtomicU32>,
+) {
+    tokio::task::spawn_blocking(move || {
+        use std::io::BufRead;
+
+        let start = Instant::now();
+        // `exec 2>&1` merges stderr for the whole script, not just its last command
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", &format!("exec 2>&1\n{}", command)])
+            .current_dir(&path)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped());
+        // Its own process group, so stopping it also stops whatever it started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
+        let child = cmd.spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = tx.send(AppUpdate::VerifyOutput(
+                    path.clone(),
+                    format!("Failed to run sh: {}", e),
+                ));
+                let _ = tx.send(AppUpdate::VerifyDone(path, None, start.elapsed()));
+                return;
+            }
+        };
+        pid.store(child.id(), Ordering::Relaxed);
+        // Stopped before the pid was known
+        if cancel.load(Ordering::Relaxed) && pid.swap(0, Ordering::Relaxed) != 0 {
+            kill_process_group(child.id());
+        }
 
-    let content = String::from_utf8(output.stdout)?;
-    let mut worktrees = App::parse_worktree_list(&content, repo_root, current_path)?;
+        if let Some(stdout) = child.stdout.take() {
+            for line in io::BufReader::new(stdout).split(b'\n') {
+                if cancel.load(Ordering::Relaxed) {
+                    let _ = child.kill();
+                    break;
+                }
+                let Ok(line) = line else {
+                    break;
+                };
+                let line = String::from_utf8_lossy(&line);
+                // Progress bars redraw with \r; only the last state is worth showing
+                let line = line.rsplit('\r').next().unwrap_or_default();
+                let _ = tx.send(AppUpdate::VerifyOutput(path.clone(), line.to_string()));
+            }
+        }
 
-    // Enum to hold different types of git command results safely with durations
-    enum GitResult {
-        Status(usize, (usize, usize, usize, usize, usize), Duration),
-        Log(usize, (String, Option<i64>, Vec<CommitInfo>), Duration),
-    }
+        let code = child.wait().ok().and_then(|status| status.code());
+        pid.store(0, Ordering::Relaxed);
+        if !cancel.load(Ordering::Relaxed) {
+            let _ = tx.send(AppUpdate::VerifyDone(path, code, start.elapsed()));
+        }
+    });
+}
 
-    struct PerfEntry {
-        branch: String,
-        status_dur: Option<Duration>,
-        log_dur: Option<Duration>,
+/// Kill a command started in its own process group, along with everything it started
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // SAFETY: killpg takes plain integers and only sends a signal
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
     }
+}
 
-    // Fetch additional status for each worktree IN PARALLEL (All commands for all worktrees)
+/// Without process groups the verify task kills the command once it next prints
+#[cfg(not(unix))]
+fn kill_process_group(_pid: u32) {}
 
-    // Use thread::scope to spawn tasks for every single git command
-    std::thread::scope(|s| {
-        let mut task_handles = Vec::new();
+/// Spawn a background task to look up pull requests for all branches with gh
+fn spawn_pull_request_task(
+    tx: mpsc::UnboundedSender<AppUpdate>,
+    repo_root: PathBuf,
+    cancel: Arc<AtomicBool>,
+) {
+    tokio::spawn(async move {
+        let root = repo_root.clone();
+        let result = tokio::task::spawn_blocking(move || fetch_pull_requests(&root)).await;
 
-        for (i, wt) in worktrees.iter().enumerate() {
-            if wt.is_bare {
-                continue;
+        match result {
+            Ok(Ok(_)) if cancel.load(Ordering::Relaxed) => {}
+            Ok(Ok(pull_requests)) => {
+                let _ = tx.send(AppUpdate::PullRequestsLoaded(repo_root, pull_requests));
             }
-            let path = wt.path.clone();
-
-            // 1. Full Status Task (Porcelain + Ahead/Behind)
-            let p1 = path.clone();
-            task_handles.push(s.spawn(move || {
-                let start = Instant::now();
-                let res = App::get_worktree_status(&p1);
-                GitResult::Status(i, res, start.elapsed())
-            }));
-
-            // 2. Log Task (Current Commit + Recent History)
-            let p2 = path.clone();
-            task_handles.push(s.spawn(move || {
-                let start = Instant::now();
-                let res = App::get_worktree_log(&p2, 10);
-                GitResult::Log(i, res, start.elapsed())
-            }));
+            Ok(Err(e)) => info!(error = %e, "Pull request lookup failed"),
+            Err(_) => {}
         }
+    });
+}
 
-        let mut perf_stats: Vec<PerfEntry> = worktrees
-            .iter()
-            .map(|wt| PerfEntry {
-                branch: wt.branch.clone().unwrap_or_else(|| "bare".to_string()),
-                status_dur: None,
-                log_dur: None,
-            })
-            .collect();
-
-        // Collect results as they finish and update worktrees
-        for handle in task_handles {
-            if let Ok(res) = handle.join() {
-                match res {
-                    GitResult::Status(idx, (staged, modded, untracked, ahead, behind), dur) => {
-                        worktrees[idx].status.staged = staged;
-                        worktrees[idx].status.modified = modded;
-                        worktrees[idx].status.untracked = untracked;
-                        worktrees[idx].status.ahead = ahead;
-                        worktrees[idx].status.behind = behind;
-                        perf_stats[idx].status_dur = Some(dur);
-                    }
-                    GitResult::Log(idx, (msg, time, recent), dur) => {
-                        worktrees[idx].commit_message = msg;
-                        worktrees[idx].commit_time = time;
-                        worktrees[idx].recent_commits = recent;
-                        perf_stats[idx].log_dur = Some(dur);
-                    }
-                }
-            }
-        }
+/// Query gh for pull requests in one batched call, keyed by head branch.
+/// When a branch has several PRs, an open one wins, otherwise the newest.
+fn fetch_pull_requests(repo_root: &Path) -> Result<HashMap<String, PrInfo>> {
+    #[derive(serde::Deserialize)]
+    struct GhPr {
+        number: u64,
+        state: String,
+        #[serde(rename = "headRefName")]
+        head_ref_name: String,
+    }
 
-        // Log Performance Summary Table
-        let mut table = String::from("\nRefresh Performance Summary (ms):\n");
-        table.push_str("Branch                          | Status | Log    | Total\n");
-        table.push_str("--------------------------------|--------|--------|-------\n");
+    let output = Command::new("gh")
+        .current_dir(repo_root)
+        .args([
+            "pr",
+            "list",
+            "--state",
+            "all",
+            "--limit",
+            "200",
+            "--json",
+            "number,state,headRefName",
+        ])
+        .output()
+        .context("Failed to run gh (is the GitHub CLI installed?)")?;
 
-        for p in perf_stats {
-            let total =
-                p.status_dur.unwrap_or(Duration::ZERO) + p.log_dur.unwrap_or(Duration::ZERO);
+    if !output.status.success() {
+        anyhow::bail!(
+            "gh pr list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
 
-            table.push_str(&format!(
-                "{:<31} | {:>6} | {:>6} | {:>6}\n",
-                if p.branch.len() > 30 {
-                    format!("{}...", &p.branch[..27])
-                } else {
-                    p.branch
+    let prs: Vec<GhPr> = serde_json::from_slice(&output.stdout)?;
+    let mut by_branch: HashMap<String, PrInfo> = HashMap::new();
+    for pr in prs {
+        let replace = match by_branch.get(&pr.head_ref_name) {
+            None => true,
+            Some(existing) => {
+                let existing_open = existing.state == "OPEN";
+                let new_open = pr.state == "OPEN";
+                (new_open && !existing_open)
+                    || (new_open == existing_open && pr.number > existing.number)
+            }
+        };
+        if replace {
+            by_branch.insert(
+                pr.head_ref_name,
+                PrInfo {
+                    number: pr.number,
+                    state: pr.state,
                 },
-                p.status_dur.map(|d| d.as_millis()).unwrap_or(0),
-                p.log_dur.map(|d| d.as_millis()).unwrap_or(0),
-                total.as_millis()
-            ));
+            );
         }
-        info!(
-            "Refresh Performance Summary (ms) - Wall Time: {}ms{}",
-            start_all.elapsed().as_millis(),
-            table
-        );
-    });
-
-    Ok(worktrees)
+    }
+    Ok(by_branch)
 }
 
 /// Handle a single event, return true if should quit
@@ -3264,23 +10194,42 @@ fn handle_event(
     event: Event,
     tx: &mpsc::UnboundedSender<AppUpdate>,
 ) -> Result<bool> {
+    let selected_before = app.selected_worktree().map(|wt| wt.path.clone());
     match event {
         Event::Key(key) => match app.mode {
             AppMode::Normal => handle_normal_mode_async(app, key.code, key.modifiers, tx)?,
             AppMode::Help => handle_help_mode(app, key.code)?,
             AppMode::Create => handle_create_mode(app, key.code, key.modifiers)?,
             AppMode::Delete => handle_delete_mode(app, key.code)?,
+            AppMode::Commit => handle_commit_mode(app, key.code, key.modifiers)?,
+            AppMode::NewBranch => handle_new_branch_mode(app, key.code, key.modifiers)?,
+            AppMode::Note => handle_note_mode(app, key.code, key.modifiers)?,
+            AppMode::Import => handle_import_mode(app, key.code, key.modifiers)?,
+            AppMode::PruneWizard => handle_prune_wizard_mode(app, key.code, key.modifiers)?,
+            AppMode::Command => handle_command_mode(app, key.code, key.modifiers, tx)?,
             AppMode::Search => handle_search_mode(app, key.code, key.modifiers)?,
-            AppMode::BranchSelect => handle_branch_select_mode(app, key.code)?,
+            AppMode::Hint => handle_hint_mode(app, key.code)?,
+            AppMode::BranchSelect | AppMode::UpstreamSelect => {
+                handle_branch_select_mode(app, key.code, key.modifiers)?
+            }
             AppMode::MergeSelect => handle_merge_select_mode(app, key.code)?,
             AppMode::MergeConfirm => handle_merge_confirm_mode(app, key.code)?,
-            AppMode::Error => handle_error_mode(app, key.code)?,
+            AppMode::RepoSelect => handle_repo_select_mode(app, key.code, tx)?,
+            AppMode::Health => handle_health_mode(app, key.code)?,
+            AppMode::SyncReport => handle_sync_report_mode(app, key.code)?,
+            AppMode::Diff => handle_diff_mode(app, key.code, key.modifiers)?,
+            AppMode::Verify => handle_verify_mode(app, key.code, key.modifiers)?,
+            AppMode::ExcludeConfirm => handle_exclude_confirm_mode(app, key.code)?,
+            AppMode::BranchExists => handle_branch_exists_mode(app, key.code)?,
+            AppMode::BatchConfirm => handle_batch_confirm_mode(app, key.code)?,
+            AppMode::Error => handle_error_mode(app, key.code, tx)?,
         },
         Event::Mouse(mouse) => {
             handle_mouse_event(app, mouse)?;
         }
         _ => {}
     }
+    app.note_selection_change(selected_before);
     Ok(app.should_quit)
 }
 
@@ -3291,21 +10240,239 @@ fn handle_normal_mode_async(
     modifiers: KeyModifiers,
     tx: &mpsc::UnboundedSender<AppUpdate>,
 ) -> Result<()> {
+    // Any key dismisses the hint overlay; Esc and h do nothing else
+    if std::mem::take(&mut app.show_key_hints) && matches!(key, KeyCode::Esc | KeyCode::Char('h')) {
+        return Ok(());
+    }
+
     match key {
         // Refresh triggers background task instead of blocking
-        KeyCode::Char('r') | KeyCode::Char('R') => {
+        KeyCode::Char('r') => {
             if app.loading_state != LoadingState::Loading {
-                app.loading_state = LoadingState::Loading;
-                spawn_refresh_task(
-                    tx.clone(),
-                    app.repo_root.clone(),
-                    app.current_worktree_path.clone(),
-                );
+                app.start_background_refresh(tx);
+                app.refresh_requested = true;
                 app.set_status("Refreshing...", MessageLevel::Info);
             }
         }
+        // Forced refresh: drop the cached worktrees and PRs so nothing stale can influence the
+        // result; notes and access data live in the same file and are kept
+        KeyCode::Char('R') => {
+            let _ = cache::clear_cache(&app.repo_root);
+            app.pull_requests.clear();
+            app.pull_requests_fetched_at = 0;
+            if app.loading_state != LoadingState::Loading {
+                app.start_background_refresh(tx);
+                app.refresh_requested = true;
+            }
+            app.set_status("Forced full refresh (cache cleared)", MessageLevel::Info);
+        }
+        KeyCode::Char('K') => app.start_verify(tx),
+        KeyCode::Char('I') => {
+            app.show_ignored = !app.show_ignored;
+            app.cancel_background_tasks();
+            app.start_background_refresh(tx);
+            app.set_status(
+                if app.show_ignored {
+                    "Showing ignored files"
+                } else {
+                    "Hiding ignored files"
+                },
+                MessageLevel::Info,
+            );
+        }
+        // Esc aborts an in-flight refresh first, and only quits when nothing is running
+        KeyCode::Esc if app.cancel_background_tasks() => {}
         // All other keys handled by existing function
         _ => handle_normal_mode(app, key, modifiers)?,
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "機能/テスト";
+
+    fn press(input: &mut String, cursor: &mut usize, key: KeyCode) -> bool {
+        edit_text_input(input, cursor, key, KeyModifiers::NONE)
+    }
+
+    fn press_ctrl(input: &mut String, cursor: &mut usize, c: char) -> bool {
+        edit_text_input(input, cursor, KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn char_boundaries_step_over_whole_characters() {
+        assert_eq!(prev_char_boundary(INPUT, 0), 0);
+        assert_eq!(prev_char_boundary(INPUT, 6), 3);
+        assert_eq!(prev_char_boundary(INPUT, 7), 6);
+        assert_eq!(prev_char_boundary(INPUT, INPUT.len()), 13);
+        assert_eq!(next_char_boundary(INPUT, 0), 3);
+        assert_eq!(next_char_boundary(INPUT, 6), 7);
+        assert_eq!(next_char_boundary(INPUT, INPUT.len()), INPUT.len());
+    }
+
+    #[test]
+    fn cursor_column_counts_wide_characters_twice() {
+        assert_eq!(cursor_column(INPUT, 0), 0);
+        assert_eq!(cursor_column(INPUT, 6), 4);
+        assert_eq!(cursor_column(INPUT, 7), 5);
+        assert_eq!(cursor_column(INPUT, INPUT.len()), 11);
+    }
+
+    #[test]
+    fn edit_text_input_keeps_the_cursor_on_boundaries() {
+        let mut input = INPUT.to_string();
+        let mut cursor = input.len();
+
+        assert!(press(&mut input, &mut cursor, KeyCode::Backspace));
+        assert_eq!((input.as_str(), cursor), ("機能/テス", 13));
+
+        for _ in 0..3 {
+            assert!(press(&mut input, &mut cursor, KeyCode::Left));
+        }
+        assert_eq!(cursor, 6);
+        assert!(press(&mut input, &mut cursor, KeyCode::Char('の')));
+        assert_eq!((input.as_str(), cursor), ("機能の/テス", 9));
+        assert!(press(&mut input, &mut cursor, KeyCode::Right));
+        assert_eq!(cursor, 10);
+
+        // Nothing to delete before the start
+        assert!(press(&mut input, &mut cursor, KeyCode::Home));
+        assert!(!press(&mut input, &mut cursor, KeyCode::Backspace));
+        assert_eq!((input.as_str(), cursor), ("機能の/テス", 0));
+
+        assert!(press(&mut input, &mut cursor, KeyCode::Right));
+        assert!(press_ctrl(&mut input, &mut cursor, 'k'));
+        assert_eq!((input.as_str(), cursor), ("機", 3));
+
+        assert!(!press(&mut input, &mut cursor, KeyCode::Enter));
+        assert!(press_ctrl(&mut input, &mut cursor, 'u'));
+        assert_eq!((input.as_str(), cursor), ("", 0));
+    }
+
+    #[test]
+    fn truncate_middle_keeps_both_ends() {
+        assert_eq!(
+            truncate_middle("feature/JIRA-1234-login", 30),
+            "feature/JIRA-1234-login"
+        );
+        assert_eq!(
+            truncate_middle("feature/JIRA-1234-login", 12),
+            "featu...ogin"
+        );
+        assert_eq!(
+            truncate_middle("feature/JIRA-1234-login", 11),
+            "feat...ogin"
+        );
+    }
+
+    #[test]
+    fn truncate_text_never_exceeds_max_len() {
+        for style in [
+            config::TruncateStyle::Start,
+            config::TruncateStyle::Middle,
+            config::TruncateStyle::End,
+        ] {
+            for max_len in 0..8 {
+                let text = truncate_text("feature/login", max_len, style);
+                assert!(text.width() <= max_len, "{style:?} {max_len}: {text:?}");
+            }
+            assert_eq!(truncate_text("feature/login", 2, style), "..");
+            assert_eq!(truncate_text("ab", 2, style), "ab");
+        }
+    }
+
+    #[test]
+    fn truncate_text_measures_wide_characters() {
+        // A wide character that doesn't fit whole leaves its column unused
+        let branch = "機能/ログイン画面の実装";
+        assert_eq!(
+            truncate_text(branch, 12, config::TruncateStyle::Middle),
+            "機能/...実装"
+        );
+        assert_eq!(
+            truncate_text(branch, 11, config::TruncateStyle::End),
+            "機能/ロ..."
+        );
+        assert_eq!(
+            truncate_text(branch, 10, config::TruncateStyle::Start),
+            "...の実装"
+        );
+    }
+
+    /// Row `y` of `buffer` as text, skipping the cells hidden behind wide characters
+    fn row_text(buffer: &ratatui::buffer::Buffer, y: u16) -> String {
+        let mut text = String::new();
+        let mut hidden = 0;
+        for x in 0..buffer.area.width {
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            let symbol = buffer[(x, y)].symbol();
+            hidden = symbol.width().saturating_sub(1);
+            text.push_str(symbol);
+        }
+        text
+    }
+
+    #[test]
+    fn wide_branch_names_keep_the_table_aligned() {
+        let widths = [
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Min(12),
+            Constraint::Length(12),
+            Constraint::Length(8),
+        ];
+        let branch_width = branch_column_width(51, &widths, 4);
+        let branches = [
+            "機能/ログイン画面の実装-とても長い名前",
+            "feature/JIRA-1234-login-page",
+            "feature/login",
+        ];
+        let truncated: Vec<String> = branches
+            .iter()
+            .map(|b| truncate_text(b, branch_width, config::TruncateStyle::Middle))
+            .collect();
+        let rows = truncated.iter().enumerate().map(|(i, branch)| {
+            Row::new(vec![
+                Cell::from(i.to_string()),
+                Cell::from("●"),
+                Cell::from(branch.as_str()),
+                Cell::from("clean"),
+                Cell::from("abc1234"),
+            ])
+        });
+        let table = Table::new(rows, widths)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .padding(Padding::horizontal(1)),
+            )
+            .highlight_symbol("→ ");
+
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(51, 5)).unwrap();
+        let mut state = TableState::default().with_selected(Some(0));
+        terminal
+            .draw(|frame| frame.render_stateful_widget(table, frame.area(), &mut state))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+
+        let mut status_columns = Vec::new();
+        for (i, branch) in truncated.iter().enumerate() {
+            let row = row_text(buffer, i as u16 + 1);
+            // The whole truncated name is shown, and the next columns start where they should
+            assert!(row.contains(branch.as_str()), "{row:?} lacks {branch:?}");
+            let status = row.find("clean").unwrap();
+            status_columns.push(row[..status].width());
+            assert_eq!(
+                row[..row.find("abc1234").unwrap()].width(),
+                status_columns[i] + 13
+            );
+        }
+        assert!(status_columns.iter().all(|&c| c == status_columns[0]));
+    }
+}
@@ -0,0 +1,45 @@
+//! Shell integration snippets printed by `--print-shell-integration`
+//! Each defines a `wt` function that runs wtt with a temp `--cwd-file` and cds on exit
+
+const POSIX: &str = r#"wt() {
+    local tmp cwd
+    tmp="$(mktemp -t "wtt-cwd.XXXXXX")"
+    command wtt --cwd-file="$tmp" "$@"
+    if cwd="$(command cat -- "$tmp")" && [ -n "$cwd" ] && [ "$cwd" != "$PWD" ]; then
+        builtin cd -- "$cwd"
+    fi
+    rm -f -- "$tmp"
+}
+"#;
+
+const FISH: &str = r#"function wt
+    set -l tmp (mktemp -t "wtt-cwd.XXXXXX")
+    command wtt --cwd-file="$tmp" $argv
+    set -l cwd (command cat -- "$tmp")
+    if test -n "$cwd"; and test "$cwd" != "$PWD"
+        builtin cd -- "$cwd"
+    end
+    rm -f -- "$tmp"
+end
+"#;
+
+const NUSHELL: &str = r#"def --env wt [...args] {
+    let tmp = (mktemp -t "wtt-cwd.XXXXXX")
+    ^wtt $"--cwd-file=($tmp)" ...$args
+    let cwd = (open --raw $tmp | str trim)
+    if $cwd != "" and $cwd != $env.PWD {
+        cd $cwd
+    }
+    rm -f $tmp
+}
+"#;
+
+/// Integration function for `shell`, None if the shell isn't supported
+pub fn integration_script(shell: &str) -> Option<&'static str> {
+    match shell {
+        "bash" | "zsh" | "sh" => Some(POSIX),
+        "fish" => Some(FISH),
+        "nu" | "nushell" => Some(NUSHELL),
+        _ => None,
+    }
+}
@@ -0,0 +1,916 @@
+//! Worktree discovery: lists a repository's worktrees and fills in their status,
+//! recent commits and notable config with plain `git` commands
+
+use crate::{error::WttError, git::git_command};
+use anyhow::{Context, Result};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Git config keys worth surfacing per worktree (as printed by `git config --list`)
+const NOTABLE_CONFIG_KEYS: &[&str] = &[
+    "user.name",
+    "user.email",
+    "user.signingkey",
+    "commit.gpgsign",
+    "core.sparsecheckout",
+    "core.sparsecheckoutcone",
+    "core.hookspath",
+    "pull.rebase",
+];
+
+/// A git worktree and everything wtt shows about it
+#[derive(Debug, Clone)]
+pub struct Worktree {
+    /// Canonical path of the worktree's working directory
+    pub path: PathBuf,
+    /// Checked-out branch without `refs/heads/`, None when detached or bare
+    pub branch: Option<String>,
+    pub commit: String,
+    pub commit_short: String,
+    /// Subject of the HEAD commit
+    pub commit_message: String,
+    /// Committer time of HEAD (unix seconds)
+    pub commit_time: Option<i64>,
+    /// The repository's main worktree (not a linked one)
+    pub is_main: bool,
+    /// Contains the directory wtt was started from
+    pub is_current: bool,
+    pub is_bare: bool,
+    pub is_detached: bool,
+    pub is_locked: bool,
+    pub lock_reason: Option<String>,
+    /// Reported as prunable by git (its directory is gone)
+    pub is_prunable: bool,
+    /// Why git considers it prunable, e.g. "gitdir file points to non-existent location"
+    pub prune_reason: Option<String>,
+    pub status: WorktreeStatus,
+    /// Latest commits on HEAD, newest first
+    pub recent_commits: Vec<CommitInfo>,
+    /// Pull request for the branch; never filled in by discovery itself
+    pub pr: Option<PrInfo>,
+    /// Notable repo/worktree-scoped git config (key, value)
+    pub config: Vec<(String, String)>,
+    /// Number of sparse-checkout patterns, None if sparse-checkout is off
+    pub sparse_patterns: Option<usize>,
+    /// Commits on this branch that are not on the main worktree's branch
+    pub unique_commits: Option<usize>,
+    /// Commits reachable from HEAD, counted up to `COMMIT_COUNT_CAP`
+    pub commit_count: Option<usize>,
+    /// Stashes made on this worktree's branch (stashes are shared by all worktrees)
+    pub stash_count: usize,
+    /// Whether the HEAD commit carries a GPG/SSH signature, None if unknown
+    pub head_signed: Option<bool>,
+    /// First lines of the README at the worktree root, None if there is none
+    pub readme_preview: Option<String>,
+}
+
+impl Worktree {
+    /// The branch name isn't valid UTF-8, so `branch` only approximates it
+    /// (with U+FFFD placeholders) and must not be passed back to git as a ref
+    pub fn branch_is_lossy(&self) -> bool {
+        self.branch
+            .as_deref()
+            .is_some_and(|b| b.contains(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// HEAD points at a branch with no commits yet, as in a freshly `git init`ed
+    /// repository. git lists such a worktree with an all-zero HEAD.
+    pub fn is_unborn(&self) -> bool {
+        !self.commit.is_empty() && self.commit.bytes().all(|b| b == b'0')
+    }
+}
+
+/// Pull request associated with a worktree's branch (from gh)
+#[derive(Debug, Clone)]
+pub struct PrInfo {
+    pub number: u64,
+    pub state: String,
+}
+
+/// One entry of a worktree's recent history
+#[derive(Debug, Clone)]
+pub struct CommitInfo {
+    pub hash: String,
+    pub message: String,
+    /// Committer time as a unix timestamp; see [`format_relative_time`]
+    pub time: Option<i64>,
+    pub author: String,
+    pub author_email: String,
+}
+
+/// Working tree changes and upstream divergence
+#[derive(Debug, Clone, Default)]
+pub struct WorktreeStatus {
+    pub modified: usize,
+    pub staged: usize,
+    pub untracked: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    /// Tracking branch, e.g. `origin/main`; None if the branch has no upstream
+    pub upstream: Option<String>,
+    /// The upstream branch no longer exists on the remote (as of the last fetch with
+    /// `--prune`), usually because it was merged and deleted
+    pub upstream_gone: bool,
+    /// Ignored files and directories as git reports them, whole ignored directories
+    /// as `dir/`. Only looked up when asked for; never part of `untracked` or the clean check
+    pub ignored_paths: Vec<String>,
+}
+
+impl WorktreeStatus {
+    /// No staged, modified or untracked files
+    pub fn is_clean(&self) -> bool {
+        self.modified == 0 && self.staged == 0 && self.untracked == 0
+    }
+
+    /// Everything is staged and the working tree matches the index (ready to commit)
+    pub fn is_staged_ready(&self) -> bool {
+        self.staged > 0 && self.modified == 0 && self.untracked == 0
+    }
+
+    /// Compact form for the table, e.g. `+1 ~2 ↑3`
+    pub fn summary(&self) -> String {
+        if self.is_clean() && self.ahead == 0 && self.behind == 0 {
+            return String::from("clean");
+        }
+
+        let mut parts = Vec::new();
+        if self.staged > 0 {
+            parts.push(format!("+{}", self.staged));
+        }
+        if self.modified > 0 {
+            parts.push(format!("~{}", self.modified));
+        }
+        if self.untracked > 0 {
+            parts.push(format!("?{}", self.untracked));
+        }
+        if self.ahead > 0 {
+            parts.push(format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            parts.push(format!("↓{}", self.behind));
+        }
+        parts.join(" ")
+    }
+}
+
+/// List all worktrees of the repository at `repo_root` with their details loaded.
+/// `is_current` is relative to the process's working directory, or to `repo_root`
+/// if that directory no longer exists.
+pub fn list_worktrees(repo_root: &Path) -> Result<Vec<Worktree>> {
+    let repo_root = canonical_path(repo_root);
+    let current_path = std::env::current_dir()
+        .map(|d| canonical_path(&d))
+        .unwrap_or_else(|_| repo_root.clone());
+    let max_parallel = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4);
+    fetch_all_worktrees(
+        &repo_root,
+        &current_path,
+        None,
+        max_parallel,
+        false,
+        &AtomicBool::new(false),
+    )
+}
+
+/// List worktrees without any per-worktree details (status, log, config)
+pub fn discover_worktrees(repo_root: &Path, current_path: &Path) -> Result<Vec<Worktree>> {
+    let output = git_command()
+        .current_dir(repo_root)
+        .args(["worktree", "list", "--porcelain"])
+        .output()
+        .context("Failed to list worktrees")?;
+
+    if !output.status.success() {
+        return Err(WttError::git_failed("worktree list", &output).into());
+    }
+
+    parse_worktree_list(&output.stdout, repo_root, current_path)
+}
+
+/// Fill in status and commit history for a single worktree.
+/// Ignored files are only listed with `show_ignored`. `stashes` is the repo-wide
+/// count per branch from `get_stash_counts`, so refreshing many worktrees lists them once.
+pub fn load_worktree_details(
+    worktree: &mut Worktree,
+    main_branch: Option<&str>,
+    show_ignored: bool,
+    stashes: &HashMap<String, usize>,
+) {
+    if worktree.is_bare {
+        return;
+    }
+
+    worktree.status = get_worktree_status(&worktree.path, show_ignored);
+
+    worktree.config = get_worktree_config(&worktree.path);
+    worktree.sparse_patterns = get_sparse_patterns(&worktree.path, &worktree.config);
+    worktree.readme_preview = read_readme_preview(&worktree.path);
+
+    // Everything below reads commits, and git errors out on all of it until the first one
+    if worktree.is_unborn() {
+        return;
+    }
+
+    let log_info = get_worktree_log(&worktree.path, 10);
+    worktree.commit_message = log_info.0;
+    worktree.commit_time = log_info.1;
+    worktree.recent_commits = log_info.2;
+
+    worktree.unique_commits = unique_commits_base(worktree, main_branch)
+        .and_then(|base| get_unique_commits(&worktree.path, &base));
+    worktree.commit_count = get_commit_count(&worktree.path);
+    worktree.stash_count = worktree
+        .branch
+        .as_ref()
+        .and_then(|branch| stashes.get(branch).copied())
+        .unwrap_or(0);
+    worktree.head_signed = get_head_signed(&worktree.path);
+}
+
+/// Path from raw git output. On Unix the bytes are kept as they are, so a
+/// non-UTF-8 path still points at the right directory.
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// Parse `git worktree list --porcelain` output. It is read as bytes because
+/// paths and branch names don't have to be UTF-8.
+fn parse_worktree_list(
+    content: &[u8],
+    repo_root: &Path,
+    current_path: &Path,
+) -> Result<Vec<Worktree>> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<Worktree> = None;
+
+    for raw_line in content.split(|&b| b == b'\n') {
+        if let Some(path) = raw_line.strip_prefix(b"worktree ") {
+            if let Some(wt) = current.take() {
+                worktrees.push(wt);
+            }
+            let path = canonical_path(&path_from_bytes(path));
+            let is_main = path == *repo_root;
+            // Check if this worktree contains the current working directory
+            let is_current = current_path.starts_with(&path);
+            current = Some(Worktree {
+                path,
+                branch: None,
+                commit: String::new(),
+                commit_short: String::new(),
+                commit_message: String::new(),
+                commit_time: None,
+                is_main,
+                is_current,
+                is_bare: false,
+                is_detached: false,
+                is_locked: false,
+                lock_reason: None,
+                is_prunable: false,
+                prune_reason: None,
+                status: WorktreeStatus::default(),
+                recent_commits: Vec::new(),
+                pr: None,
+                config: Vec::new(),
+                sparse_patterns: None,
+                unique_commits: None,
+                commit_count: None,
+                stash_count: 0,
+                head_signed: None,
+                readme_preview: None,
+            });
+        } else if let Some(ref mut wt) = current {
+            if let Some(name) = raw_line.strip_prefix(b"branch ") {
+                let name = name.strip_prefix(b"refs/heads/").unwrap_or(name);
+                // Shown with placeholders; `Worktree::branch_is_lossy` keeps it away from git
+                let branch = match std::str::from_utf8(name) {
+                    Ok(branch) => branch.to_string(),
+                    Err(_) => {
+                        warn!(bytes = ?name, "Branch name is not valid UTF-8");
+                        String::from_utf8_lossy(name).into_owned()
+                    }
+                };
+                wt.branch = Some(branch);
+                continue;
+            }
+            if let Some(reason) = raw_line.strip_prefix(b"locked ") {
+                wt.is_locked = true;
+                let reason = unquote_c_style(reason);
+                wt.lock_reason = Some(String::from_utf8_lossy(&reason).into_owned());
+                continue;
+            }
+            let line = String::from_utf8_lossy(raw_line);
+            let line = line.as_ref();
+            if line.starts_with("HEAD ") {
+                wt.commit = line.strip_prefix("HEAD ").unwrap().to_string();
+                wt.commit_short = wt.commit.chars().take(7).collect();
+            } else if line == "bare" {
+                wt.is_bare = true;
+            } else if line == "detached" {
+                wt.is_detached = true;
+            } else if line == "locked" {
+                wt.is_locked = true;
+            } else if line == "prunable" {
+                wt.is_prunable = true;
+            } else if let Some(reason) = line.strip_prefix("prunable ") {
+                // Newer git says why: `prunable gitdir file points to non-existent location`
+                wt.is_prunable = true;
+                wt.prune_reason = Some(reason.to_string());
+            }
+        }
+    }
+
+    if let Some(wt) = current {
+        worktrees.push(wt);
+    }
+
+    Ok(worktrees)
+}
+
+/// Undo the C-style quoting git applies to free text in porcelain output, e.g. a lock
+/// reason with quotes or non-ASCII characters comes out as `"caf\303\251 \"wip\""`.
+/// Unquoted text is returned as it is.
+fn unquote_c_style(bytes: &[u8]) -> Vec<u8> {
+    let Some(inner) = bytes
+        .strip_prefix(b"\"")
+        .and_then(|rest| rest.strip_suffix(b"\""))
+    else {
+        return bytes.to_vec();
+    };
+    let mut out = Vec::with_capacity(inner.len());
+    let mut iter = inner.iter().copied().peekable();
+    while let Some(b) = iter.next() {
+        if b != b'\\' {
+            out.push(b);
+            continue;
+        }
+        match iter.next() {
+            Some(b'a') => out.push(0x07),
+            Some(b'b') => out.push(0x08),
+            Some(b't') => out.push(b'\t'),
+            Some(b'n') => out.push(b'\n'),
+            Some(b'v') => out.push(0x0b),
+            Some(b'f') => out.push(0x0c),
+            Some(b'r') => out.push(b'\r'),
+            Some(d @ b'0'..=b'7') => {
+                let mut value = u32::from(d - b'0');
+                for _ in 0..2 {
+                    match iter.peek() {
+                        Some(&d @ b'0'..=b'7') => {
+                            value = value * 8 + u32::from(d - b'0');
+                            iter.next();
+                        }
+                        _ => break,
+                    }
+                }
+                out.push(value as u8);
+            }
+            Some(other) => out.push(other),
+            None => out.push(b'\\'),
+        }
+    }
+    out
+}
+
+/// File counts and upstream divergence from `git status`, plus ignored files with `show_ignored`
+fn get_worktree_status(path: &Path, show_ignored: bool) -> WorktreeStatus {
+    let mut staged = 0;
+    let mut modified = 0;
+    let mut untracked = 0;
+    let mut ahead = 0;
+    let mut behind = 0;
+    let mut upstream = None;
+    let mut upstream_gone = false;
+    let mut ignored_paths = Vec::new();
+
+    let mut args = vec!["status", "--porcelain=v1", "--branch"];
+    if show_ignored {
+        args.push("--ignored");
+    }
+    if let Ok(output) = git_command().current_dir(path).args(&args).output() {
+        if output.status.success() {
+            let content = String::from_utf8_lossy(&output.stdout);
+            for line in content.lines() {
+                if line.starts_with("##") {
+                    if let Some((_, rest)) = line.split_once("...") {
+                        upstream = rest.split(' ').next().map(str::to_string);
+                    }
+                    // Parse ahead/behind from branch line: ## main...origin/main [ahead 1, behind 2]
+                    // (or `[gone]` once the upstream branch was deleted on the remote)
+                    if let Some(pos) = line.find('[') {
+                        let info = &line[pos + 1..line.len() - 1];
+                        upstream_gone = info == "gone";
+                        for part in info.split(',') {
+                            let part = part.trim();
+                            if let Some(n) = part.strip_prefix("ahead ") {
+                                ahead = n.parse().unwrap_or(0);
+                            } else if let Some(n) = part.strip_prefix("behind ") {
+                                behind = n.parse().unwrap_or(0);
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if line.len() < 2 {
+                    continue;
+                }
+                if let Some(ignored) = line.strip_prefix("!! ") {
+                    ignored_paths.push(ignored.trim_matches('"').to_string());
+                    continue;
+                }
+                let index = line.chars().next().unwrap();
+                let worktree = line.chars().nth(1).unwrap();
+                if index != ' ' && index != '?' {
+                    staged += 1;
+                }
+                if worktree == 'M' || worktree == 'D' {
+                    modified += 1;
+                }
+                // Worktrees nested inside this one (e.g. `<repo>/.worktrees/<name>`)
+                // show up as untracked directories but aren't untracked work
+                if index == '?' && !is_nested_worktree_dir(&path.join(line[3..].trim_matches('"')))
+                {
+                    untracked += 1;
+                }
+            }
+        }
+    }
+    WorktreeStatus {
+        modified,
+        staged,
+        untracked,
+        ahead,
+        behind,
+        upstream,
+        upstream_gone,
+        ignored_paths,
+    }
+}
+
+/// Whether an untracked directory is another worktree, or only holds worktrees
+fn is_nested_worktree_dir(dir: &Path) -> bool {
+    if dir.join(".git").is_file() {
+        return true;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    let mut found = false;
+    for entry in entries.flatten() {
+        if !entry.path().join(".git").is_file() {
+            return false;
+        }
+        found = true;
+    }
+    found
+}
+
+/// HEAD subject and time plus the last `count` commits
+fn get_worktree_log(path: &Path, count: usize) -> (String, Option<i64>, Vec<CommitInfo>) {
+    let output = git_command()
+        .current_dir(path)
+        // Subject last, so a `|` in it doesn't shift the other fields
+        .args(["log", &format!("-{}", count), "--format=%h|%ct|%an|%ae|%s"])
+        .output();
+
+    let mut current_msg = String::new();
+    let mut current_time = None;
+    let mut recent = Vec::new();
+
+    if let Ok(output) = output {
+        if output.status.success() {
+            let content = String::from_utf8_lossy(&output.stdout);
+            for (i, line) in content.lines().enumerate() {
+                let parts: Vec<&str> = line.splitn(5, '|').collect();
+                if parts.len() == 5 {
+                    let time = parts[1].parse().ok();
+                    if i == 0 {
+                        current_msg = parts[4].chars().take(60).collect();
+                        current_time = time;
+                    }
+                    recent.push(CommitInfo {
+                        hash: parts[0].to_string(),
+                        message: parts[4].chars().take(50).collect(),
+                        time,
+                        author: parts[2].to_string(),
+                        author_email: parts[3].to_string(),
+                    });
+                }
+            }
+        }
+    }
+    (current_msg, current_time, recent)
+}
+
+/// "5 minutes ago"-style label for a unix timestamp, in the same units as git's `%cr`.
+/// Formatted at draw time so the labels don't go stale while the app stays open.
+pub fn format_relative_time(timestamp: i64, now: i64) -> String {
+    let secs = now.saturating_sub(timestamp);
+    if secs < 0 {
+        return "in the future".to_string();
+    }
+    let (count, unit) = match secs {
+        s if s < 90 => (s, "second"),
+        s if s < 90 * 60 => ((s + 30) / 60, "minute"),
+        s if s < 36 * 60 * 60 => ((s + 30 * 60) / (60 * 60), "hour"),
+        s if s < 14 * 24 * 60 * 60 => ((s + 12 * 60 * 60) / (24 * 60 * 60), "day"),
+        s if s < 10 * 7 * 24 * 60 * 60 => ((s + 3 * 24 * 60 * 60) / (7 * 24 * 60 * 60), "week"),
+        s if s < 365 * 24 * 60 * 60 => ((s + 15 * 24 * 60 * 60) / (30 * 24 * 60 * 60), "month"),
+        s => ((s + 182 * 24 * 60 * 60) / (365 * 24 * 60 * 60), "year"),
+    };
+    format!(
+        "{} {}{} ago",
+        count,
+        unit,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+/// Read the curated `NOTABLE_CONFIG_KEYS` that are set at repo or worktree scope
+/// (global/system values are the same everywhere, so they'd just be noise)
+fn get_worktree_config(path: &Path) -> Vec<(String, String)> {
+    let mut config = Vec::new();
+
+    if let Ok(output) = git_command()
+        .current_dir(path)
+        .args(["config", "--show-scope", "--list"])
+        .output()
+    {
+        if output.status.success() {
+            let content = String::from_utf8_lossy(&output.stdout);
+            for line in content.lines() {
+                let Some((scope, entry)) = line.split_once('\t') else {
+                    continue;
+                };
+                if scope != "local" && scope != "worktree" {
+                    continue;
+                }
+                let Some((key, value)) = entry.split_once('=') else {
+                    continue;
+                };
+                if !NOTABLE_CONFIG_KEYS.contains(&key) {
+                    continue;
+                }
+                // Later entries override earlier ones (worktree scope comes last)
+                match config
+                    .iter_mut()
+                    .find(|(k, _): &&mut (String, String)| k == key)
+                {
+                    Some(existing) => existing.1 = value.to_string(),
+                    None => config.push((key.to_string(), value.to_string())),
+                }
+            }
+        }
+    }
+    config
+}
+
+/// Count sparse-checkout patterns if `core.sparseCheckout` is enabled for the worktree
+fn get_sparse_patterns(path: &Path, config: &[(String, String)]) -> Option<usize> {
+    let enabled = config
+        .iter()
+        .any(|(k, v)| k == "core.sparsecheckout" && v == "true");
+    if !enabled {
+        return None;
+    }
+
+    let output = git_command()
+        .current_dir(path)
+        .args(["sparse-checkout", "list"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .count(),
+    )
+}
+
+/// Branch to count unique commits against, None for the main worktree itself
+/// and for detached worktrees
+fn unique_commits_base(worktree: &Worktree, main_branch: Option<&str>) -> Option<String> {
+    if worktree.is_main || worktree.branch.is_none() {
+        return None;
+    }
+    main_branch.map(str::to_string)
+}
+
+/// Check whether HEAD has a signature header. This only detects presence;
+/// verifying it would need gpg/ssh and is far slower.
+fn get_head_signed(path: &Path) -> Option<bool> {
+    let output = git_command()
+        .current_dir(path)
+        .args(["cat-file", "commit", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let content = String::from_utf8_lossy(&output.stdout);
+    // Headers end at the first blank line, before the commit message
+    Some(
+        content
+            .lines()
+            .take_while(|l| !l.is_empty())
+            .any(|l| l.starts_with("gpgsig ") || l.starts_with("gpgsig-sha256 ")),
+    )
+}
+
+/// Count commits reachable from the worktree's HEAD but not from `main_branch`
+fn get_unique_commits(path: &Path, main_branch: &str) -> Option<usize> {
+    let output = git_command()
+        .current_dir(path)
+        .args(["rev-list", "--count", &format!("{}..HEAD", main_branch)])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Stop counting a branch's commits here, so long histories stay cheap
+pub const COMMIT_COUNT_CAP: usize = 10_000;
+
+/// Count commits reachable from the worktree's HEAD, up to `COMMIT_COUNT_CAP`
+fn get_commit_count(path: &Path) -> Option<usize> {
+    let output = git_command()
+        .current_dir(path)
+        .args([
+            "rev-list",
+            "--count",
+            &format!("--max-count={}", COMMIT_COUNT_CAP),
+            "HEAD",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Number of stashes per branch they were made on, from any worktree of the repo.
+/// Stashes made on a detached HEAD are counted under `(no branch)`.
+pub fn get_stash_counts(path: &Path) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    let Ok(output) = git_command()
+        .current_dir(path)
+        .args(["stash", "list", "--format=%gs"])
+        .output()
+    else {
+        return counts;
+    };
+    // Subjects look like "WIP on <branch>: <commit>" or "On <branch>: <message>"
+    for subject in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((branch, _)) = subject
+            .strip_prefix("WIP on ")
+            .or_else(|| subject.strip_prefix("On "))
+            .and_then(|rest| rest.split_once(": "))
+        else {
+            continue;
+        };
+        *counts.entry(branch.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Non-empty README lines kept for the preview
+const README_PREVIEW_LINES: usize = 6;
+
+/// Read the first lines of the worktree's README, dropping heading markers,
+/// badges and HTML so the preview shows prose. Only the file's start is read.
+fn read_readme_preview(path: &Path) -> Option<String> {
+    let file = [
+        "README.md",
+        "README",
+        "README.markdown",
+        "README.txt",
+        "readme.md",
+    ]
+    .iter()
+    .find_map(|name| File::open(path.join(name)).ok())?;
+    let lines: Vec<String> = BufReader::new(file.take(16 * 1024))
+        .lines()
+        .map_while(|line| line.ok())
+        .map(|line| line.trim_start_matches('#').trim().to_string())
+        .filter(|line| !line.is_empty() && !line.starts_with("[![") && !line.starts_with('<'))
+        .take(README_PREVIEW_LINES)
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Fetch all worktree data (runs in blocking thread with parallel git commands).
+/// Unique commits are counted against `main_branch`, or the main worktree's branch if None
+pub fn fetch_all_worktrees(
+    repo_root: &Path,
+    current_path: &Path,
+    main_branch: Option<&str>,
+    max_parallel: usize,
+    show_ignored: bool,
+    cancel: &AtomicBool,
+) -> Result<Vec<Worktree>> {
+    let start_all = Instant::now();
+    let mut worktrees = discover_worktrees(repo_root, current_path)?;
+    let stashes = get_stash_counts(repo_root);
+    for wt in &mut worktrees {
+        if let Some(count) = wt.branch.as_ref().and_then(|b| stashes.get(b)) {
+            wt.stash_count = *count;
+        }
+    }
+    let main_branch = main_branch.map(str::to_string).or_else(|| {
+        worktrees
+            .iter()
+            .find(|wt| wt.is_main)
+            .and_then(|wt| wt.branch.clone())
+    });
+
+    // Enum to hold different types of git command results safely with durations
+    enum GitResult {
+        Status(usize, WorktreeStatus, Duration),
+        Log(
+            usize,
+            (String, Option<i64>, Vec<CommitInfo>),
+            (Option<usize>, Option<bool>, Option<usize>),
+            Duration,
+        ),
+        Config(
+            usize,
+            (Vec<(String, String)>, Option<usize>, Option<String>),
+            Duration,
+        ),
+    }
+
+    struct PerfEntry {
+        branch: String,
+        status_dur: Option<Duration>,
+        log_dur: Option<Duration>,
+        config_dur: Option<Duration>,
+    }
+
+    // One job per git command per worktree
+    enum GitJob {
+        Status(usize, PathBuf),
+        Log(usize, PathBuf, Option<String>),
+        Config(usize, PathBuf),
+    }
+
+    let mut jobs = Vec::new();
+    for (i, wt) in worktrees.iter().enumerate() {
+        if wt.is_bare {
+            continue;
+        }
+        jobs.push(GitJob::Status(i, wt.path.clone()));
+        // Every command of the log job fails until the first commit exists
+        if !wt.is_unborn() {
+            let unique_base = unique_commits_base(wt, main_branch.as_deref());
+            jobs.push(GitJob::Log(i, wt.path.clone(), unique_base));
+        }
+        jobs.push(GitJob::Config(i, wt.path.clone()));
+    }
+
+    let run_job = |job: &GitJob| -> GitResult {
+        let start = Instant::now();
+        match job {
+            // 1. Full Status Task (Porcelain + Ahead/Behind)
+            GitJob::Status(i, path) => {
+                let res = get_worktree_status(path, show_ignored);
+                GitResult::Status(*i, res, start.elapsed())
+            }
+            // 2. Log Task (Current Commit + Recent History + Unique Commits)
+            GitJob::Log(i, path, unique_base) => {
+                let res = get_worktree_log(path, 10);
+                let unique = unique_base
+                    .as_deref()
+                    .and_then(|base| get_unique_commits(path, base));
+                let signed = get_head_signed(path);
+                let count = get_commit_count(path);
+                GitResult::Log(*i, res, (unique, signed, count), start.elapsed())
+            }
+            // 3. Config Task (Notable repo/worktree-scoped settings + sparse-checkout + README)
+            GitJob::Config(i, path) => {
+                let config = get_worktree_config(path);
+                let sparse = get_sparse_patterns(path, &config);
+                let readme = read_readme_preview(path);
+                GitResult::Config(*i, (config, sparse, readme), start.elapsed())
+            }
+        }
+    };
+
+    // Fetch additional status for each worktree IN PARALLEL, with at most
+    // `max_parallel` git commands running at once
+    let next_job = AtomicUsize::new(0);
+    std::thread::scope(|s| {
+        let workers: Vec<_> = (0..max_parallel.clamp(1, jobs.len().max(1)))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut results = Vec::new();
+                    // Each worker stops picking up jobs once the refresh has been cancelled
+                    while !cancel.load(Ordering::Relaxed) {
+                        let Some(job) = jobs.get(next_job.fetch_add(1, Ordering::Relaxed)) else {
+                            break;
+                        };
+                        results.push(run_job(job));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        let mut perf_stats: Vec<PerfEntry> = worktrees
+            .iter()
+            .map(|wt| PerfEntry {
+                branch: wt.branch.clone().unwrap_or_else(|| "bare".to_string()),
+                status_dur: None,
+                log_dur: None,
+                config_dur: None,
+            })
+            .collect();
+
+        // Collect results once the workers finish and update worktrees
+        for res in workers.into_iter().filter_map(|w| w.join().ok()).flatten() {
+            match res {
+                GitResult::Status(idx, status, dur) => {
+                    worktrees[idx].status = status;
+                    perf_stats[idx].status_dur = Some(dur);
+                }
+                GitResult::Log(idx, (msg, time, recent), (unique, signed, count), dur) => {
+                    worktrees[idx].commit_message = msg;
+                    worktrees[idx].commit_time = time;
+                    worktrees[idx].recent_commits = recent;
+                    worktrees[idx].unique_commits = unique;
+                    worktrees[idx].head_signed = signed;
+                    worktrees[idx].commit_count = count;
+                    perf_stats[idx].log_dur = Some(dur);
+                }
+                GitResult::Config(idx, (config, sparse, readme), dur) => {
+                    worktrees[idx].config = config;
+                    worktrees[idx].sparse_patterns = sparse;
+                    worktrees[idx].readme_preview = readme;
+                    perf_stats[idx].config_dur = Some(dur);
+                }
+            }
+        }
+
+        // Log Performance Summary Table
+        let mut table = String::from("\nRefresh Performance Summary (ms):\n");
+        table.push_str("Branch                          | Status | Log    | Config | Total\n");
+        table.push_str("--------------------------------|--------|--------|--------|-------\n");
+
+        for p in perf_stats {
+            let total = p.status_dur.unwrap_or(Duration::ZERO)
+                + p.log_dur.unwrap_or(Duration::ZERO)
+                + p.config_dur.unwrap_or(Duration::ZERO);
+
+            table.push_str(&format!(
+                "{:<31} | {:>6} | {:>6} | {:>6} | {:>6}\n",
+                if p.branch.chars().count() > 30 {
+                    format!("{}...", p.branch.chars().take(27).collect::<String>())
+                } else {
+                    p.branch
+                },
+                p.status_dur.map(|d| d.as_millis()).unwrap_or(0),
+                p.log_dur.map(|d| d.as_millis()).unwrap_or(0),
+                p.config_dur.map(|d| d.as_millis()).unwrap_or(0),
+                total.as_millis()
+            ));
+        }
+        info!(
+            "Refresh Performance Summary (ms) - Wall Time: {}ms{}",
+            start_all.elapsed().as_millis(),
+            table
+        );
+    });
+
+    if cancel.load(Ordering::Relaxed) {
+        anyhow::bail!("Refresh cancelled");
+    }
+
+    Ok(worktrees)
+}
+
+/// Resolve symlinks so paths compare reliably against the canonical repo root.
+/// Paths that no longer exist (e.g. prunable worktrees) are kept as given.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,33 @@
+//! Lives in its own test binary: it moves the process into a directory that then disappears
+
+mod common;
+
+use common::{git, init_repo, TempDir};
+use worktree_tui::list_worktrees;
+
+#[test]
+fn falls_back_to_repo_root_when_cwd_is_deleted() {
+    let tmp = TempDir::new();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo, false);
+    let feature = tmp.path().join("feature");
+    git(
+        &repo,
+        &["worktree", "add", "-q", "-b", "feature", "../feature"],
+    );
+
+    std::env::set_current_dir(&feature).unwrap();
+    std::fs::remove_dir_all(&feature).unwrap();
+
+    let worktrees = list_worktrees(&repo).unwrap();
+    assert_eq!(worktrees.len(), 2);
+
+    let main = worktrees.iter().find(|wt| wt.is_main).unwrap();
+    assert_eq!(main.path, repo);
+    assert!(main.is_current);
+
+    let gone = worktrees.iter().find(|wt| !wt.is_main).unwrap();
+    assert_eq!(gone.path, feature);
+    assert!(gone.is_prunable);
+    assert!(!gone.is_current);
+}
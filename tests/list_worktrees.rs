@@ -0,0 +1,85 @@
+mod common;
+
+use common::{git, init_repo, TempDir};
+use worktree_tui::{list_worktrees, Worktree};
+
+fn find<'a>(worktrees: &'a [Worktree], name: &str) -> &'a Worktree {
+    worktrees
+        .iter()
+        .find(|wt| wt.path.file_name().is_some_and(|n| n == name))
+        .unwrap_or_else(|| panic!("no worktree named {}", name))
+}
+
+#[test]
+fn lists_main_and_linked_worktrees() {
+    let tmp = TempDir::new();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo, false);
+    git(
+        &repo,
+        &["worktree", "add", "-q", "-b", "feature", "../feature"],
+    );
+    git(&repo, &["worktree", "add", "-q", "--detach", "../detached"]);
+    git(&repo, &["worktree", "add", "-q", "-b", "usb", "../locked"]);
+    git(
+        &repo,
+        &["worktree", "lock", "--reason", "on usb", "../locked"],
+    );
+    git(&repo, &["worktree", "add", "-q", "-b", "gone", "../gone"]);
+    std::fs::remove_dir_all(tmp.path().join("gone")).unwrap();
+
+    let worktrees = list_worktrees(&repo).unwrap();
+    assert_eq!(worktrees.len(), 5);
+
+    let main = find(&worktrees, "repo");
+    assert_eq!(main.path, repo);
+    assert!(main.is_main);
+    assert_eq!(main.branch.as_deref(), Some("main"));
+    assert!(!main.is_bare && !main.is_detached && !main.is_locked && !main.is_prunable);
+    assert_eq!(main.commit_message, "init");
+
+    let feature = find(&worktrees, "feature");
+    assert_eq!(feature.path, tmp.path().join("feature"));
+    assert!(!feature.is_main);
+    assert_eq!(feature.branch.as_deref(), Some("feature"));
+    assert_eq!(feature.commit, main.commit);
+
+    let detached = find(&worktrees, "detached");
+    assert!(detached.is_detached);
+    assert_eq!(detached.branch, None);
+
+    let locked = find(&worktrees, "locked");
+    assert!(locked.is_locked);
+    assert_eq!(locked.lock_reason.as_deref(), Some("on usb"));
+    assert!(!locked.is_prunable);
+
+    let gone = find(&worktrees, "gone");
+    assert!(gone.is_prunable);
+    assert!(!gone.is_locked);
+    assert_eq!(gone.branch.as_deref(), Some("gone"));
+}
+
+#[test]
+fn lists_worktrees_of_a_bare_repository() {
+    let tmp = TempDir::new();
+    let seed = tmp.path().join("seed");
+    init_repo(&seed, false);
+    let bare = tmp.path().join("bare.git");
+    git(
+        tmp.path(),
+        &["clone", "-q", "--bare", "seed", bare.to_str().unwrap()],
+    );
+    git(&bare, &["worktree", "add", "-q", "../checkout", "main"]);
+
+    let worktrees = list_worktrees(&bare).unwrap();
+    assert_eq!(worktrees.len(), 2);
+
+    let main = find(&worktrees, "bare.git");
+    assert!(main.is_main);
+    assert!(main.is_bare);
+    assert_eq!(main.branch, None);
+
+    let checkout = find(&worktrees, "checkout");
+    assert!(!checkout.is_main && !checkout.is_bare);
+    assert_eq!(checkout.branch.as_deref(), Some("main"));
+}
@@ -0,0 +1,33 @@
+//! Lives in its own test binary: it moves the process into the symlinked repository
+#![cfg(unix)]
+
+mod common;
+
+use common::{git, init_repo, TempDir};
+use worktree_tui::list_worktrees;
+
+#[test]
+fn identifies_current_worktree_through_a_symlink() {
+    let tmp = TempDir::new();
+    let repo = tmp.path().join("repo");
+    init_repo(&repo, false);
+    git(
+        &repo,
+        &["worktree", "add", "-q", "-b", "feature", "../feature"],
+    );
+    let link = tmp.path().join("link");
+    std::os::unix::fs::symlink(&repo, &link).unwrap();
+
+    std::env::set_current_dir(&link).unwrap();
+    let worktrees = list_worktrees(&link).unwrap();
+    assert_eq!(worktrees.len(), 2);
+
+    let main = worktrees.iter().find(|wt| wt.is_main).unwrap();
+    assert_eq!(main.path, dunce::canonicalize(&link).unwrap());
+    assert_eq!(main.path, repo);
+    assert!(main.is_current);
+
+    let feature = worktrees.iter().find(|wt| !wt.is_main).unwrap();
+    assert_eq!(feature.path, tmp.path().join("feature"));
+    assert!(!feature.is_current);
+}
@@ -0,0 +1,44 @@
+//! Lives in its own test binary: it points every git command at a logging wrapper
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+
+use common::{init_repo, TempDir};
+use worktree_tui::{list_worktrees, set_git_path};
+
+#[test]
+fn lists_a_repository_without_commits() {
+    let tmp = TempDir::new();
+    let repo = tmp.path().join("empty");
+    init_repo(&repo, true);
+
+    // Runs git as usual, noting every command that fails
+    let failures = tmp.path().join("failures");
+    let wrapper = tmp.path().join("git");
+    std::fs::write(
+        &wrapper,
+        format!(
+            "#!/bin/sh\ngit \"$@\" || {{ status=$?; echo \"$*\" >> '{}'; exit $status; }}\n",
+            failures.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+    set_git_path(wrapper.to_str().unwrap());
+
+    let worktrees = list_worktrees(&repo).unwrap();
+    assert_eq!(worktrees.len(), 1);
+
+    let main = &worktrees[0];
+    assert!(main.is_main);
+    assert!(main.is_unborn());
+    assert_eq!(main.branch.as_deref(), Some("main"));
+    assert!(main.recent_commits.is_empty());
+    assert_eq!(main.commit_count, None);
+
+    // Nothing reads HEAD's history before the first commit exists
+    let failed = std::fs::read_to_string(&failures).unwrap_or_default();
+    assert!(failed.is_empty(), "git commands failed:\n{}", failed);
+}